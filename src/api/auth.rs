@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! HTTP authentication helpers
+
+use axum::{
+    extract::{Request, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use secrecy::ExposeSecret;
+use std::sync::Arc;
+
+use crate::api::AppState;
+use crate::config::Config;
+
+/// Requires `METRICS_AUTH_USER`/`METRICS_AUTH_PASSWORD` basic auth
+///
+/// Passes every request through when the credentials aren't configured.
+pub(crate) async fn require_basic_auth(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = state.config();
+    if config.metrics_auth_user.is_none() || basic_auth_matches(&config, request.headers()) {
+        return next.run(request).await;
+    }
+    (
+        StatusCode::UNAUTHORIZED,
+        [(WWW_AUTHENTICATE, "Basic realm=\"mikrotik-exporter\"")],
+        "invalid or missing credentials",
+    )
+        .into_response()
+}
+
+/// Whether `headers` carry the configured basic auth credentials
+///
+/// Always false when `METRICS_AUTH_USER`/`METRICS_AUTH_PASSWORD` aren't set.
+pub(crate) fn basic_auth_matches(config: &Config, headers: &HeaderMap) -> bool {
+    let (Some(user), Some(password)) = (&config.metrics_auth_user, &config.metrics_auth_password)
+    else {
+        return false;
+    };
+    let expected = base64_encode(format!("{user}:{}", password.expose_secret()).as_bytes());
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .is_some_and(|p| constant_time_eq(p.trim().as_bytes(), expected.as_bytes()))
+}
+
+/// Compares two byte strings without stopping at the first difference
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Standard padded base64, as used by the `Basic` authorization scheme
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"s3cret", b"s3cret"));
+        assert!(!constant_time_eq(b"s3cret", b"s3creT"));
+        assert!(!constant_time_eq(b"s3cret", b"s3cre"));
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"prom:s3cret"), "cHJvbTpzM2NyZXQ=");
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Short-lived cache for encoded `/metrics` output
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Caches the last encoded metrics text for a configurable TTL
+///
+/// Rapid scrapes within the TTL are served the same bytes without
+/// re-encoding the registry. The lock is held while encoding, so concurrent
+/// requests on an expired entry share a single encode.
+#[derive(Default)]
+pub(crate) struct MetricsCache {
+    entry: Mutex<Option<(Instant, Arc<str>)>>,
+}
+
+impl MetricsCache {
+    /// Returns the cached text if fresher than `ttl`, otherwise refreshes it via `encode`
+    pub(crate) async fn get_or_encode<F, Fut, E>(
+        &self,
+        ttl: Duration,
+        encode: F,
+    ) -> Result<Arc<str>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let mut entry = self.entry.lock().await;
+        if let Some((encoded_at, text)) = entry.as_ref() {
+            if encoded_at.elapsed() < ttl {
+                tracing::trace!("Serving cached metrics ({:?} old)", encoded_at.elapsed());
+                return Ok(text.clone());
+            }
+        }
+
+        let text: Arc<str> = encode().await?.into();
+        *entry = Some((Instant::now(), text.clone()));
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_serves_entry_within_ttl() {
+        let cache = MetricsCache::default();
+        let ttl = Duration::from_secs(60);
+
+        let first = cache
+            .get_or_encode(ttl, || async { Ok::<_, ()>("first".to_string()) })
+            .await
+            .unwrap();
+        let second = cache
+            .get_or_encode(ttl, || async { Ok::<_, ()>("second".to_string()) })
+            .await
+            .unwrap();
+
+        assert_eq!(&*first, "first");
+        assert_eq!(&*second, "first");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_refreshes_after_ttl() {
+        let cache = MetricsCache::default();
+        let ttl = Duration::from_secs(5);
+
+        cache
+            .get_or_encode(ttl, || async { Ok::<_, ()>("first".to_string()) })
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(6)).await;
+        let refreshed = cache
+            .get_or_encode(ttl, || async { Ok::<_, ()>("second".to_string()) })
+            .await
+            .unwrap();
+
+        assert_eq!(&*refreshed, "second");
+    }
+}
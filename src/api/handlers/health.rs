@@ -28,12 +28,14 @@ pub(crate) struct RouterHealth {
 ///
 /// Health check endpoint with router availability check.
 /// Returns overall service status, version, and individual router health.
+/// A degraded status is answered with 503 unless `HEALTH_FAIL_ON_DEGRADED`
+/// is disabled.
 pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let mut routers_health = Vec::new();
     let mut all_healthy = true;
 
     // Check each router's health from metrics and connection pool
-    for router in &state.config.routers {
+    for router in state.reloader.active_routers().iter() {
         let router_label = crate::metrics::RouterLabels {
             router: router.name.clone(),
         };
@@ -72,7 +74,7 @@ pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoRespon
     }
 
     let overall_status = if all_healthy { "healthy" } else { "degraded" };
-    let status_code = if all_healthy {
+    let status_code = if all_healthy || !state.config().health_fail_on_degraded {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -105,17 +107,14 @@ mod tests {
                 address: "192.168.1.1:8728".to_string(),
                 username: "admin".to_string(),
                 password: secrecy::SecretString::new("password".to_string().into()),
+                ..Default::default()
             }],
-            collection_interval_secs: 30,
+            ..Config::default()
         };
 
         let metrics = MetricsRegistry::new();
         let pool = Arc::new(ConnectionPool::new());
-        let app_state = Arc::new(AppState {
-            config,
-            metrics,
-            pool,
-        });
+        let app_state = Arc::new(AppState::new(config, metrics, pool));
 
         let response = health_check(State(app_state)).await.into_response();
         assert!(
@@ -123,4 +122,77 @@ mod tests {
                 || response.status() == StatusCode::SERVICE_UNAVAILABLE
         );
     }
+
+    #[tokio::test]
+    async fn test_health_check_degraded_status_code() {
+        use crate::mikrotik::ConnectionPool;
+
+        for (fail_on_degraded, expected) in [
+            (true, StatusCode::SERVICE_UNAVAILABLE),
+            (false, StatusCode::OK),
+        ] {
+            let config = Config {
+                routers: vec![RouterConfig {
+                    name: "bad-router".to_string(),
+                    address: "192.168.1.1:8728".to_string(),
+                    ..Default::default()
+                }],
+                health_fail_on_degraded: fail_on_degraded,
+                ..Config::default()
+            };
+            let metrics = MetricsRegistry::new();
+            metrics.record_scrape_error(&crate::metrics::RouterLabels {
+                router: "bad-router".to_string(),
+            });
+            let pool = Arc::new(ConnectionPool::new());
+            let app_state = Arc::new(AppState::new(config, metrics, pool));
+
+            let response = health_check(State(app_state)).await.into_response();
+            assert_eq!(response.status(), expected);
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let health: HealthResponse = serde_json::from_slice(&body).unwrap();
+            assert_eq!(health.status, "degraded");
+            assert_eq!(health.routers[0].status, "degraded");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_check_includes_discovered_routers() {
+        use crate::mikrotik::ConnectionPool;
+
+        let config = Config {
+            routers: vec![RouterConfig {
+                name: "static".to_string(),
+                ..Default::default()
+            }],
+            sd_url: Some("http://sd.local/routers".to_string()),
+            ..Config::default()
+        };
+        let app_state = Arc::new(AppState::new(
+            config,
+            MetricsRegistry::new(),
+            Arc::new(ConnectionPool::new()),
+        ));
+        let discovered = RouterConfig {
+            name: "discovered".to_string(),
+            ..Default::default()
+        };
+        let mut routers = app_state.reloader.routers().to_vec();
+        routers.push(discovered);
+        app_state
+            .reloader
+            .active_sender()
+            .send_replace(Arc::new(routers));
+
+        let response = health_check(State(app_state)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let health: HealthResponse = serde_json::from_slice(&body).unwrap();
+        let names: Vec<&str> = health.routers.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["static", "discovered"]);
+    }
 }
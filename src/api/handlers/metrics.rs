@@ -7,12 +7,25 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::api::AppState;
 
 pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> Response {
-    tracing::debug!("/metrics encode cached scrape");
-    match state.metrics.encode_metrics().await {
+    let cache_ttl_secs = state.config().metrics_cache_ttl_secs;
+    let result = if cache_ttl_secs > 0 {
+        let ttl = Duration::from_secs(cache_ttl_secs);
+        state
+            .metrics_cache
+            .get_or_encode(ttl, || state.metrics.encode_metrics())
+            .await
+            .map(|text| text.to_string())
+    } else {
+        tracing::debug!("/metrics encode cached scrape");
+        state.metrics.encode_metrics().await
+    };
+
+    match result {
         Ok(metrics_text) => (
             StatusCode::OK,
             [(
@@ -37,12 +50,11 @@ pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> Response {
 mod tests {
     use super::*;
     use crate::config::{Config, RouterConfig};
-    use crate::metrics::MetricsRegistry;
-
-    #[tokio::test]
-    async fn test_metrics_endpoint() {
-        use crate::mikrotik::ConnectionPool;
+    use crate::metrics::{MetricsRegistry, RouterLabels};
+    use crate::mikrotik::ConnectionPool;
+    use http_body_util::BodyExt;
 
+    fn make_state(metrics_cache_ttl_secs: u64) -> Arc<AppState> {
         let config = Config {
             server_addr: "127.0.0.1:9090".to_string(),
             routers: vec![RouterConfig {
@@ -50,21 +62,62 @@ mod tests {
                 address: "192.168.1.1:8728".to_string(),
                 username: "admin".to_string(),
                 password: secrecy::SecretString::new("password".to_string().into()),
+                ..Default::default()
             }],
-            collection_interval_secs: 30,
+            metrics_cache_ttl_secs,
+            ..Config::default()
         };
 
         let metrics = MetricsRegistry::new();
         let pool = Arc::new(ConnectionPool::new());
-        let app_state = Arc::new(AppState {
-            config,
-            metrics,
-            pool,
-        });
+        Arc::new(AppState::new(config, metrics, pool))
+    }
+
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let app_state = make_state(0);
 
         let response = metrics_handler(State(app_state)).await;
         let status = response.status();
 
         assert_eq!(status, StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_metrics_cache_serves_identical_bytes_within_ttl() {
+        let app_state = make_state(60);
+        let labels = RouterLabels {
+            router: "test-router".to_string(),
+        };
+
+        let first = body_bytes(metrics_handler(State(app_state.clone())).await).await;
+        app_state.metrics.record_scrape_success(&labels);
+        let second = body_bytes(metrics_handler(State(app_state)).await).await;
+
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_without_cache_reflects_updates() {
+        let app_state = make_state(0);
+        let labels = RouterLabels {
+            router: "test-router".to_string(),
+        };
+
+        let first = body_bytes(metrics_handler(State(app_state.clone())).await).await;
+        app_state.metrics.record_scrape_success(&labels);
+        let second = body_bytes(metrics_handler(State(app_state)).await).await;
+
+        assert_ne!(first, second);
+    }
 }
@@ -3,6 +3,12 @@
 
 mod health;
 mod metrics;
+mod probe;
+mod reload;
+mod snapshot;
 
 pub use health::health_check;
 pub use metrics::metrics_handler;
+pub use probe::probe_handler;
+pub use reload::reload_handler;
+pub use snapshot::snapshot_handler;
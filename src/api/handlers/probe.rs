@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api::AppState;
+use crate::collector::probe_router_into;
+use crate::metrics::{MetricsRegistry, RouterLabels};
+
+/// Query parameters of `/probe`
+#[derive(Debug, Deserialize)]
+pub struct ProbeQuery {
+    target: Option<String>,
+}
+
+/// GET /probe?target=<router-name>
+///
+/// Scrapes one configured or discovered router on demand and returns only
+/// its metrics, for the Prometheus multi-target exporter pattern. Each
+/// target keeps its own registry, separate from the background collection.
+/// Unknown or missing targets are answered with 400.
+pub async fn probe_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ProbeQuery>,
+) -> Response {
+    let Some(target) = query.target.filter(|t| !t.is_empty()) else {
+        return (StatusCode::BAD_REQUEST, "missing target parameter").into_response();
+    };
+    let routers = state.reloader.active_routers();
+    let Some(router) = routers.iter().find(|r| r.name == target) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("unknown target '{target}'"),
+        )
+            .into_response();
+    };
+
+    let config = state.config();
+    let metrics = state
+        .probe_metrics
+        .lock()
+        .await
+        .entry(target)
+        .or_insert_with_key(|name| {
+            let metrics =
+                MetricsRegistry::with_prefix(&config.metric_prefix, config.environment.as_deref());
+            metrics.initialize_router_metrics(&RouterLabels {
+                router: name.clone(),
+            });
+            metrics
+        })
+        .clone();
+
+    tracing::debug!("/probe scraping router '{}'", router.name);
+    probe_router_into(router, &state.pool, &metrics).await;
+
+    match metrics.encode_metrics().await {
+        Ok(metrics_text) => (
+            StatusCode::OK,
+            [(
+                "Content-Type",
+                "application/openmetrics-text; version=1.0.0; charset=utf-8",
+            )],
+            metrics_text,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to encode probe metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to encode metrics: {e}"),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+};
+use secrecy::ExposeSecret;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::api::AppState;
+use crate::api::auth::{basic_auth_matches, constant_time_eq};
+
+/// `POST /reload` response body
+#[derive(Debug, Serialize)]
+struct ReloadResponse {
+    /// Routers configured after the reload
+    routers: usize,
+}
+
+/// POST /reload
+///
+/// Reloads the configured routers like SIGHUP does and answers with the new
+/// router count as JSON. Requires `Authorization: Bearer <RELOAD_TOKEN>` or
+/// the `/metrics` basic auth credentials; with neither configured the
+/// endpoint is disabled and answers 403. A configuration that fails to load
+/// is answered with 500 and the current routers are kept.
+pub async fn reload_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let config = state.config();
+    let token = config.reload_token.as_ref();
+    if token.is_none() && config.metrics_auth_user.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            "reload is disabled: neither RELOAD_TOKEN nor METRICS_AUTH_USER is set",
+        )
+            .into_response();
+    }
+    let bearer_matches = token.is_some_and(|token| {
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|p| constant_time_eq(p.as_bytes(), token.expose_secret().as_bytes()))
+    });
+    if !bearer_matches && !basic_auth_matches(&config, &headers) {
+        return (StatusCode::UNAUTHORIZED, "invalid or missing credentials").into_response();
+    }
+
+    match state.reloader.reload().await {
+        Ok(routers) => Json(ReloadResponse { routers }).into_response(),
+        Err(e) => {
+            tracing::error!("Configuration reload failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("reload failed: {e}\n"),
+            )
+                .into_response()
+        }
+    }
+}
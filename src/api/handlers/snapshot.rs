@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::api::AppState;
+use crate::mikrotik::{
+    ConnectionTrackingStats, InterfaceStats, RouterMetrics, SystemResource,
+    WireGuardInterfaceStats, WireGuardPeerStats,
+};
+
+/// JSON body of the snapshot endpoint
+#[derive(Serialize)]
+struct RouterSnapshot<'a> {
+    router: &'a str,
+    system: Option<&'a SystemResource>,
+    interfaces: &'a [InterfaceStats],
+    connection_tracking: &'a [ConnectionTrackingStats],
+    wireguard_interfaces: &'a [WireGuardInterfaceStats],
+    wireguard_peers: &'a [WireGuardPeerStats],
+}
+
+impl<'a> From<&'a RouterMetrics> for RouterSnapshot<'a> {
+    fn from(metrics: &'a RouterMetrics) -> Self {
+        Self {
+            router: &metrics.router_name,
+            system: metrics.system.as_ref(),
+            interfaces: &metrics.interfaces,
+            connection_tracking: &metrics.connection_tracking,
+            wireguard_interfaces: &metrics.wireguard_interfaces,
+            wireguard_peers: &metrics.wireguard_peers,
+        }
+    }
+}
+
+/// GET /api/routers/{name}/snapshot
+///
+/// Returns the latest metrics collected from one router as JSON, for
+/// dashboards that don't read Prometheus text. Unknown routers are answered
+/// with 404, routers without a successful scrape yet with 503.
+pub async fn snapshot_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    if !state
+        .reloader
+        .active_routers()
+        .iter()
+        .any(|r| r.name == name)
+    {
+        return (StatusCode::NOT_FOUND, format!("unknown router '{name}'")).into_response();
+    }
+    let snapshots = state.snapshots.read().await;
+    match snapshots.get(&name) {
+        Some(metrics) => Json(RouterSnapshot::from(metrics)).into_response(),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("router '{name}' has not been scraped yet"),
+        )
+            .into_response(),
+    }
+}
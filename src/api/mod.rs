@@ -8,28 +8,91 @@
 //! # Endpoints
 //! - `GET /health` — health check
 //! - `GET /metrics` — Prometheus metrics
+//! - `GET /probe?target=<router>` — on-demand scrape of one router
+//! - `POST /reload` — reload the configured routers
+//! - `GET /api/routers/{name}/snapshot` — latest collected metrics of one
+//!   router as JSON
+//!
+//! `/metrics`, `/probe` and the snapshot endpoint require basic auth when
+//! `METRICS_AUTH_USER` and `METRICS_AUTH_PASSWORD` are set; `/reload` accepts
+//! the same credentials as an alternative to its bearer token. Handlers read
+//! the configuration as of the last reload.
 
+mod auth;
+mod cache;
 mod handlers;
 
-use axum::{Router, routing::get};
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
 
+use crate::collector::ConfigReloader;
 use crate::config::Config;
 use crate::metrics::MetricsRegistry;
-use crate::mikrotik::ConnectionPool;
+use crate::mikrotik::{ConnectionPool, RouterMetrics};
+
+use self::cache::MetricsCache;
 
 /// Application state shared with endpoints
 pub struct AppState {
-    pub config: Config,
     pub metrics: MetricsRegistry,
     pub pool: Arc<ConnectionPool>,
+    /// Current configuration and routers, replaced by `POST /reload` and
+    /// SIGHUP, and the collected routers including discovered ones
+    pub reloader: ConfigReloader,
+    pub(crate) metrics_cache: MetricsCache,
+    /// Registries of `/probe` targets, keyed by router name; pruned by the
+    /// collection loop when routers go away
+    pub probe_metrics: Arc<Mutex<HashMap<String, MetricsRegistry>>>,
+    /// Latest successfully collected metrics, keyed by router name; filled
+    /// by the collection loop
+    pub snapshots: Arc<RwLock<HashMap<String, RouterMetrics>>>,
+}
+
+impl AppState {
+    /// Creates application state with an empty metrics cache
+    #[must_use]
+    pub fn new(config: Config, metrics: MetricsRegistry, pool: Arc<ConnectionPool>) -> Self {
+        Self {
+            reloader: ConfigReloader::new(&config),
+            metrics,
+            pool,
+            metrics_cache: MetricsCache::default(),
+            probe_metrics: Arc::default(),
+            snapshots: Arc::default(),
+        }
+    }
+
+    /// Current configuration, as of the last reload
+    #[must_use]
+    pub fn config(&self) -> Arc<Config> {
+        self.reloader.config()
+    }
 }
 
 /// Creates the main Axum router with all endpoints
 pub fn create_router(state: Arc<AppState>) -> Router {
+    // Always layered, since a reload can turn basic auth on or off
+    let protected = Router::new()
+        .route("/metrics", get(handlers::metrics_handler))
+        .route("/probe", get(handlers::probe_handler))
+        .route(
+            "/api/routers/{name}/snapshot",
+            get(handlers::snapshot_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_basic_auth,
+        ));
+
     Router::new()
         .route("/health", get(handlers::health_check))
-        .route("/metrics", get(handlers::metrics_handler))
+        .route("/reload", post(handlers::reload_handler))
+        .merge(protected)
         .with_state(state)
 }
 
@@ -48,17 +111,14 @@ mod tests {
                 address: "192.168.1.1".to_string(),
                 username: "admin".to_string(),
                 password: "password".to_string().into(),
+                ..Default::default()
             }],
-            collection_interval_secs: 30,
+            ..Config::default()
         };
 
         let metrics = MetricsRegistry::new();
         let pool = Arc::new(ConnectionPool::new());
-        let app_state = Arc::new(AppState {
-            config,
-            metrics,
-            pool,
-        });
+        let app_state = Arc::new(AppState::new(config, metrics, pool));
 
         let _router = create_router(app_state);
         // If we get here without panicking, the router was created successfully
@@ -70,13 +130,34 @@ mod tests {
         let metrics = MetricsRegistry::new();
         let pool = Arc::new(ConnectionPool::new());
 
-        let state = AppState {
-            config,
-            metrics,
-            pool,
-        };
+        let state = AppState::new(config, metrics, pool);
 
-        assert_eq!(state.config.server_addr, "0.0.0.0:9090");
-        assert_eq!(state.config.collection_interval_secs, 30);
+        assert_eq!(state.config().server_addr, "0.0.0.0:9090");
+        assert_eq!(state.config().collection_interval_secs, 30);
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_metrics_auth_applies_without_restart() {
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::new(
+            Config::default(),
+            MetricsRegistry::new(),
+            Arc::new(ConnectionPool::new()),
+        ));
+        let app = create_router(state.clone());
+        let get = || Request::get("/metrics").body(String::new()).unwrap();
+
+        let resp = app.clone().oneshot(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        state.reloader.replace(Config {
+            metrics_auth_user: Some("prom".to_string()),
+            metrics_auth_password: Some("s3cret".into()),
+            ..Config::default()
+        });
+        let resp = app.oneshot(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
 }
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! HTTP service discovery of routers
+//!
+//! The endpoint configured in `SD_URL` returns a JSON array of routers in the
+//! same format as `ROUTERS_CONFIG`. It is polled every
+//! `SD_REFRESH_INTERVAL_SECONDS`; discovered routers are scraped in addition
+//! to the statically configured ones, which win on duplicate names. When a
+//! refresh fails the previous list is kept. A reload of the configured
+//! routers is merged with the last discovered list right away.
+//!
+//! Only plain `http://` URLs are supported.
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+use crate::config::{Config, RouterConfig};
+
+type DiscoveryResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Timeout for the whole discovery request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest response accepted from the discovery endpoint (1 MiB)
+const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+/// Starts a background task that polls the discovery endpoint and publishes
+/// the combined static and discovered router list
+///
+/// The first refresh happens immediately.
+pub(super) fn start_discovery_task(
+    config: Arc<Config>,
+    url: String,
+    mut static_rx: watch::Receiver<Arc<Vec<RouterConfig>>>,
+    routers_tx: watch::Sender<Arc<Vec<RouterConfig>>>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tracing::info!(
+        "Discovering routers from {} every {}s",
+        url,
+        config.sd_refresh_interval_secs
+    );
+
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(config.sd_refresh_interval_secs));
+        let mut discovered = Vec::new();
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {},
+                Ok(()) = static_rx.changed() => {
+                    let static_routers = static_rx.borrow_and_update().clone();
+                    let routers = merge_routers(&config, &static_routers, discovered.clone());
+                    let _ = routers_tx.send(Arc::new(routers));
+                    continue;
+                }
+                _ = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow() {
+                        tracing::debug!("Stopping router discovery");
+                        break;
+                    }
+                    continue;
+                }
+            }
+
+            match fetch_routers(&url).await {
+                Ok(routers) => {
+                    discovered = routers;
+                    let static_routers = static_rx.borrow().clone();
+                    let routers = merge_routers(&config, &static_routers, discovered.clone());
+                    tracing::debug!("Discovery returned {} router(s)", routers.len());
+                    let _ = routers_tx.send(Arc::new(routers));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Router discovery from {} failed: {}. Keeping previous router list.",
+                        url,
+                        e
+                    );
+                }
+            }
+        }
+    })
+}
+
+/// Static routers followed by the discovered ones, validated and de-duplicated
+fn merge_routers(
+    config: &Config,
+    static_routers: &[RouterConfig],
+    discovered: Vec<RouterConfig>,
+) -> Vec<RouterConfig> {
+    let routers = static_routers.iter().cloned().chain(discovered).collect();
+    config.prepare_routers(routers)
+}
+
+/// Fetches and parses the router list from the discovery endpoint
+async fn fetch_routers(url: &str) -> DiscoveryResult<Vec<RouterConfig>> {
+    let body = timeout(REQUEST_TIMEOUT, http_get(url))
+        .await
+        .map_err(|_| format!("Discovery timeout: no response within {REQUEST_TIMEOUT:?}"))??;
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Performs a plain HTTP/1.1 GET and returns the body of a 200 response
+async fn http_get(url: &str) -> DiscoveryResult<String> {
+    let (authority, path) = split_url(url)?;
+    let addr = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut stream = TcpStream::connect(addr).await?;
+    let request = format!(
+        "GET {path} HTTP/1.1\r\nHost: {authority}\r\nAccept: application/json\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut response)
+        .await?;
+    parse_response(&response)
+}
+
+/// Splits `http://host[:port][/path]` into authority and path
+fn split_url(url: &str) -> DiscoveryResult<(&str, &str)> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Unsupported discovery URL '{url}': expected http://"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(format!("Invalid discovery URL '{url}': missing host").into());
+    }
+    Ok((authority, path))
+}
+
+/// Extracts the body from a raw HTTP response, decoding chunked transfer
+/// encoding
+fn parse_response(response: &[u8]) -> DiscoveryResult<String> {
+    let response = std::str::from_utf8(response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed HTTP response: no end of headers")?;
+    let mut lines = head.split("\r\n");
+
+    let status = lines.next().unwrap_or_default();
+    let code = status.split_whitespace().nth(1).unwrap_or_default();
+    if code != "200" {
+        return Err(format!("Unexpected HTTP status: {status}").into());
+    }
+
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.to_ascii_lowercase().contains("chunked")
+        })
+    });
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_string())
+    }
+}
+
+/// Decodes a chunked transfer encoded body
+fn decode_chunked(mut body: &str) -> DiscoveryResult<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size_line, rest) = body
+            .split_once("\r\n")
+            .ok_or("Malformed chunked body: missing chunk size")?;
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| format!("Malformed chunked body: invalid chunk size '{size_hex}'"))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = rest
+            .get(..size)
+            .ok_or("Malformed chunked body: truncated chunk")?;
+        decoded.push_str(chunk);
+        body = rest[size..].strip_prefix("\r\n").unwrap_or(&rest[size..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    /// Serves `body` as JSON to every request until the test ends
+    async fn serve(body: Arc<Mutex<String>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let body = body.lock().unwrap().clone();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/routers")
+    }
+
+    fn router_json(name: &str, address: &str) -> String {
+        format!(r#"{{"name":"{name}","address":"{address}","username":"admin","password":"x"}}"#)
+    }
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(
+            split_url("http://sd.local:8080/api/routers?site=a").unwrap(),
+            ("sd.local:8080", "/api/routers?site=a")
+        );
+        assert_eq!(split_url("http://sd.local").unwrap(), ("sd.local", "/"));
+        assert!(split_url("https://sd.local/").is_err());
+        assert!(split_url("http:///routers").is_err());
+    }
+
+    #[test]
+    fn test_parse_response_chunked_and_status() {
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n[{}]\r\n3;ext\r\n,[]\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked).unwrap(), "[{}],[]");
+
+        let not_found = b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let err = parse_response(not_found).unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_discovered_router_is_published_on_refresh() {
+        let body = Arc::new(Mutex::new(format!(
+            "[{}]",
+            router_json("edge", "10.0.0.2:8728")
+        )));
+        let url = serve(body.clone()).await;
+        let config = Arc::new(Config {
+            routers: serde_json::from_str(&format!(
+                "[{},{}]",
+                router_json("core", "10.0.0.1:8728"),
+                router_json("edge", "10.0.0.9:8728")
+            ))
+            .unwrap(),
+            sd_refresh_interval_secs: 1,
+            ..Default::default()
+        });
+        let (static_tx, static_rx) = watch::channel(Arc::new(config.routers.clone()));
+        let (routers_tx, mut routers_rx) = watch::channel(Arc::new(config.routers.clone()));
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        let handle = start_discovery_task(config, url, static_rx, routers_tx, shutdown_rx);
+
+        routers_rx.changed().await.unwrap();
+        let routers = routers_rx.borrow_and_update().clone();
+        let names: Vec<&str> = routers.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["core", "edge"]);
+        assert_eq!(routers[1].address, "10.0.0.9:8728");
+
+        *body.lock().unwrap() = format!(
+            "[{},{}]",
+            router_json("edge", "10.0.0.2:8728"),
+            router_json("branch", "10.0.0.3:8728")
+        );
+        let routers = timeout(Duration::from_secs(5), async {
+            loop {
+                routers_rx.changed().await.unwrap();
+                let routers = routers_rx.borrow_and_update().clone();
+                if routers.len() == 3 {
+                    return routers;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(routers[2].name, "branch");
+
+        // A reload of the configured routers keeps the discovered ones
+        static_tx.send_replace(Arc::new(Vec::new()));
+        let routers = timeout(Duration::from_secs(5), async {
+            loop {
+                routers_rx.changed().await.unwrap();
+                let routers = routers_rx.borrow_and_update().clone();
+                if routers.len() == 2 {
+                    return routers;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        let names: Vec<&str> = routers.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["edge", "branch"]);
+
+        let _ = shutdown_tx.send(true);
+        handle.await.unwrap();
+    }
+}
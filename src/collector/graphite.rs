@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Push sink for Graphite
+//!
+//! After every successful collection the router's snapshot is sent to the
+//! carbon server in `GRAPHITE_ADDR` using the plaintext protocol
+//! (`path value timestamp` per line), under `mikrotik.<router>.`. Counters are
+//! sent as raw cumulative values; use `nonNegativeDerivative()` in Graphite.
+//!
+//! Send failures are logged and never affect the collection.
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::metrics::parsers::parse_uptime_to_seconds;
+use crate::mikrotik::RouterMetrics;
+
+/// Timeout for connecting to carbon and writing one snapshot
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Root of every metric path
+const PREFIX: &str = "mikrotik";
+
+/// Sends router snapshots to a Graphite carbon server
+#[derive(Debug, Clone)]
+pub(super) struct GraphiteSink {
+    addr: String,
+}
+
+impl GraphiteSink {
+    pub(super) fn new(addr: String) -> Self {
+        Self { addr }
+    }
+
+    /// Formats and sends one router snapshot, logging failures
+    pub(super) async fn push(&self, metrics: &RouterMetrics) {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let lines = format_metrics(metrics, timestamp);
+        if let Err(e) = self.send(&lines).await {
+            tracing::warn!(
+                "Failed to push metrics of router {} to Graphite at {}: {}",
+                metrics.router_name,
+                self.addr,
+                e
+            );
+        }
+    }
+
+    async fn send(&self, lines: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        timeout(SEND_TIMEOUT, async {
+            let mut stream = TcpStream::connect(&self.addr).await?;
+            stream.write_all(lines.as_bytes()).await?;
+            stream.shutdown().await
+        })
+        .await
+        .map_err(|_| format!("Graphite timeout: no response within {SEND_TIMEOUT:?}"))??;
+        Ok(())
+    }
+}
+
+/// Renders a snapshot as Graphite plaintext protocol lines
+fn format_metrics(metrics: &RouterMetrics, timestamp: u64) -> String {
+    let router = format!("{PREFIX}.{}", sanitize(&metrics.router_name));
+    let mut out = String::new();
+    let mut line = |path: &str, value: u64| {
+        let _ = writeln!(out, "{router}.{path} {value} {timestamp}");
+    };
+
+    if let Some(system) = &metrics.system {
+        line("system.cpu_load", system.cpu_load);
+        line("system.free_memory", system.free_memory);
+        line("system.total_memory", system.total_memory);
+        line("system.free_hdd_space", system.free_hdd_space);
+        line("system.total_hdd_space", system.total_hdd_space);
+        line(
+            "system.uptime_seconds",
+            parse_uptime_to_seconds(&system.uptime),
+        );
+    }
+
+    for iface in &metrics.interfaces {
+        let name = format!("interface.{}", sanitize(&iface.name));
+        line(&format!("{name}.rx_bytes"), iface.rx_bytes);
+        line(&format!("{name}.tx_bytes"), iface.tx_bytes);
+        line(&format!("{name}.rx_packets"), iface.rx_packets);
+        line(&format!("{name}.tx_packets"), iface.tx_packets);
+        line(&format!("{name}.rx_errors"), iface.rx_errors);
+        line(&format!("{name}.tx_errors"), iface.tx_errors);
+        line(&format!("{name}.rx_drops"), iface.rx_drops);
+        line(&format!("{name}.tx_drops"), iface.tx_drops);
+        line(&format!("{name}.running"), u64::from(iface.running));
+    }
+
+    if let Some(table) = &metrics.conntrack_table {
+        line("conntrack.entries", table.entries);
+    }
+
+    for peer in &metrics.wireguard_peers {
+        let name = format!(
+            "wireguard.{}.{}",
+            sanitize(&peer.interface),
+            sanitize(peer.public_key.as_deref().unwrap_or(&peer.allowed_address))
+        );
+        line(&format!("{name}.rx_bytes"), peer.rx_bytes);
+        line(&format!("{name}.tx_bytes"), peer.tx_bytes);
+    }
+
+    out
+}
+
+/// Makes a value safe to use as one Graphite path component
+fn sanitize(component: &str) -> String {
+    component
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mikrotik::{InterfaceStats, WireGuardPeerStats};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn snapshot() -> RouterMetrics {
+        RouterMetrics {
+            router_name: "core.gw".to_string(),
+            interfaces: vec![InterfaceStats {
+                name: "ether1/wan".to_string(),
+                rx_bytes: 1000,
+                tx_bytes: 2000,
+                rx_packets: 10,
+                tx_packets: 20,
+                rx_errors: 0,
+                tx_errors: 0,
+                rx_drops: 0,
+                tx_drops: 0,
+                running: true,
+                disabled: false,
+                mtu: None,
+                actual_mtu: None,
+                rx_multicast: None,
+                tx_multicast: None,
+                rx_broadcast: None,
+                tx_broadcast: None,
+            }],
+            wireguard_peers: vec![WireGuardPeerStats {
+                interface: "wg0".to_string(),
+                name: "laptop".to_string(),
+                allowed_address: "10.0.0.2/32".to_string(),
+                public_key: None,
+                endpoint: None,
+                rx_bytes: 5,
+                tx_bytes: 7,
+                latest_handshake: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_metrics_sanitizes_paths() {
+        let lines = format_metrics(&snapshot(), 1_700_000_000);
+
+        assert!(lines.contains("mikrotik.core_gw.interface.ether1_wan.rx_bytes 1000 1700000000\n"));
+        assert!(lines.contains("mikrotik.core_gw.interface.ether1_wan.running 1 1700000000\n"));
+        assert!(
+            lines.contains("mikrotik.core_gw.wireguard.wg0.10_0_0_2_32.tx_bytes 7 1700000000\n")
+        );
+        assert!(!lines.contains(".system."));
+    }
+
+    #[tokio::test]
+    async fn test_push_sends_plaintext_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let sink = GraphiteSink::new(listener.local_addr().unwrap().to_string());
+
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = String::new();
+            stream.read_to_string(&mut buf).await.unwrap();
+            buf
+        });
+        sink.push(&snapshot()).await;
+
+        let received = received.await.unwrap();
+        assert!(received.starts_with("mikrotik.core_gw.interface.ether1_wan.rx_bytes 1000 "));
+        assert!(received.ends_with('\n'));
+    }
+}
@@ -3,36 +3,63 @@
 
 //! Metrics collection orchestration module for MikroTik routers
 //!
-//! Starts background metrics collection, manages connection pool and cleanup.
+//! Starts background metrics collection, manages connection pool and cleanup,
+//! runs on-demand probes against isolated registries, validates router
+//! connectivity in one shot, discovers routers over HTTP, reloads the
+//! configured routers at runtime, and pushes snapshots to Graphite.
 
 mod cache;
 mod cleanup;
+mod discovery;
+mod graphite;
+mod probe;
+mod reload;
 mod router_task;
+mod validate;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::watch;
+use tokio::sync::{Mutex, RwLock, Semaphore, watch};
 use tokio::task::JoinHandle;
 
-use crate::config::Config;
+use crate::config::{Config, RouterConfig};
 use crate::metrics::{MetricsRegistry, RouterLabels};
-use crate::mikrotik::ConnectionPool;
+use crate::mikrotik::{ConnectionPool, RouterMetrics};
 
 use self::cache::SystemInfoCache;
+use self::graphite::GraphiteSink;
 use self::router_task::spawn_router_collection;
 
+pub use self::probe::probe_router;
+pub(crate) use self::probe::probe_router_into;
+pub use self::reload::ConfigReloader;
+pub use self::validate::{RouterValidation, validate_routers};
+
 /// Starts the background metrics collection loop
 ///
 /// Spawns a background task that periodically collects metrics from all configured routers.
-/// The collection interval is configurable via `Config::collection_interval_secs`.
+/// The collection interval is configurable via `Config::collection_interval_secs`;
+/// at most `Config::max_concurrent_scrapes` routers are scraped at once, each
+/// after a random delay of up to `Config::scrape_splay_secs`.
+///
+/// Also starts the connection pool cleanup task and, when `Config::sd_url`
+/// is set, the router discovery task. The configured routers come from
+/// `reloader`, so a reload takes effect on the next cycle. Routers that
+/// disappear from discovery or the configuration have their metrics removed
+/// on the next cycle.
 ///
-/// Also starts the connection pool cleanup task.
+/// Every successful scrape replaces the router's entry in `snapshots`.
+/// Entries of `snapshots` and of the `/probe` registries in `probe_metrics`
+/// are dropped as soon as their router is gone.
 pub fn start_collection_loop(
     mut shutdown_rx: watch::Receiver<bool>,
     config: Arc<Config>,
     metrics: MetricsRegistry,
     pool: Arc<ConnectionPool>,
+    reloader: &ConfigReloader,
+    snapshots: Arc<RwLock<HashMap<String, RouterMetrics>>>,
+    probe_metrics: Arc<Mutex<HashMap<String, MetricsRegistry>>>,
 ) -> JoinHandle<()> {
     let interval = config.collection_interval_secs;
     tracing::info!("Starting background collection loop every {}s", interval);
@@ -43,14 +70,40 @@ pub fn start_collection_loop(
     // Start cleanup task for expired connections (joined inside collection loop on shutdown)
     let cleanup_handle = cleanup::start_pool_cleanup_task(pool.clone(), shutdown_rx.clone());
 
-    // Initialize metrics for all routers to ensure counters start at zero
-    for router in &config.routers {
-        let router_label = RouterLabels {
-            router: router.name.clone(),
-        };
-        metrics.initialize_router_metrics(&router_label);
+    // Router list, replaced by reloads and discovery refreshes
+    let mut routers_rx = reloader.subscribe_active();
+    let discovery_handle = config.sd_url.clone().map(|url| {
+        discovery::start_discovery_task(
+            config.clone(),
+            url,
+            reloader.subscribe(),
+            reloader.active_sender(),
+            shutdown_rx.clone(),
+        )
+    });
+
+    let graphite = config.graphite_addr.clone().map(|addr| {
+        tracing::info!("Pushing metrics to Graphite at {}", addr);
+        GraphiteSink::new(addr)
+    });
+
+    // Splay stays below the interval so a delayed scrape still starts
+    // before the next cycle
+    let max_splay = Duration::from_secs(config.scrape_splay_secs.min(interval));
+    if config.scrape_splay_secs > interval {
+        tracing::warn!(
+            "SCRAPE_SPLAY_SECONDS ({}s) exceeds the {}s collection interval; limiting it to the interval",
+            config.scrape_splay_secs,
+            interval
+        );
     }
 
+    // Routers beyond the limit wait for a permit instead of connecting at once
+    let scrape_permits = Arc::new(Semaphore::new(config.max_concurrent_scrapes));
+
+    // Initialize metrics for all routers to ensure counters start at zero
+    initialize_routers(&metrics, &config.routers);
+
     tracing::trace!(
         "Collection loop initialized with {} routers",
         config.routers.len()
@@ -60,16 +113,7 @@ pub fn start_collection_loop(
     const CLEANUP_EVERY_N_CYCLES: u64 = 20;
     const STALE_LABEL_TTL: Duration = Duration::from_secs(60 * 30);
 
-    let active_routers: HashSet<String> = config
-        .routers
-        .iter()
-        .map(|router| router.name.clone())
-        .collect();
-    let active_pool_keys: HashSet<String> = config
-        .routers
-        .iter()
-        .map(|router| format!("{}:{}", router.address, router.username))
-        .collect();
+    let (mut active_routers, mut active_pool_keys) = active_sets(&config.routers);
 
     tokio::spawn(async move {
         let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval));
@@ -82,6 +126,9 @@ pub fn start_collection_loop(
                     if *shutdown_rx.borrow() {
                         tracing::info!("Stopping collection loop");
                         let _ = cleanup_handle.await;
+                        if let Some(handle) = discovery_handle {
+                            let _ = handle.await;
+                        }
                         break;
                     }
                 }
@@ -89,18 +136,43 @@ pub fn start_collection_loop(
 
             let cycle_start = std::time::Instant::now();
 
+            let routers = routers_rx.borrow_and_update().clone();
+            let (routers_now, pool_keys_now) = active_sets(&routers);
+            if routers_now != active_routers || pool_keys_now != active_pool_keys {
+                tracing::info!("Router list changed, now {} router(s)", routers.len());
+                initialize_routers(&metrics, &routers);
+                active_routers = routers_now;
+                active_pool_keys = pool_keys_now;
+                metrics.cleanup_stale_routers(&active_routers).await;
+                system_cache.cleanup_stale(&active_routers).await;
+                pool.cleanup_states(&active_pool_keys).await;
+                snapshots
+                    .write()
+                    .await
+                    .retain(|router, _| active_routers.contains(router));
+                probe_metrics
+                    .lock()
+                    .await
+                    .retain(|router, _| active_routers.contains(router));
+            }
+
             // Track active interfaces for cleanup
-            let active_interfaces = Arc::new(tokio::sync::Mutex::new(HashSet::new()));
+            let active_interfaces = Arc::new(Mutex::new(HashSet::new()));
 
             // Collect metrics from all routers
             let mut tasks = Vec::new();
-            for router in &config.routers {
+            for router in routers.iter() {
                 let task = spawn_router_collection(
                     router.clone(),
                     pool.clone(),
                     metrics.clone(),
                     system_cache.clone(),
                     active_interfaces.clone(),
+                    Duration::from_secs(interval),
+                    max_splay,
+                    graphite.clone(),
+                    scrape_permits.clone(),
+                    snapshots.clone(),
                 );
                 tasks.push(task);
             }
@@ -112,7 +184,18 @@ pub fn start_collection_loop(
 
             // Update pool statistics after all routers processed
             let (total, active) = pool.get_pool_stats().await;
-            metrics.update_pool_stats(total, active);
+            metrics.update_pool_stats(total, active, pool.oldest_idle().await);
+            for router in routers.iter() {
+                if let Some(age) = pool
+                    .get_last_success_age(&router.address, &router.username)
+                    .await
+                {
+                    let labels = RouterLabels {
+                        router: router.name.clone(),
+                    };
+                    metrics.update_connection_last_success_age(&labels, age);
+                }
+            }
 
             // Record full collection cycle duration
             metrics.record_collection_cycle_duration(cycle_start.elapsed().as_secs_f64());
@@ -137,3 +220,73 @@ pub fn start_collection_loop(
         }
     })
 }
+
+/// Ensures per-router counters exist so they start at zero
+fn initialize_routers(metrics: &MetricsRegistry, routers: &[RouterConfig]) {
+    for router in routers {
+        let router_label = RouterLabels {
+            router: router.name.clone(),
+        };
+        metrics.initialize_router_metrics(&router_label);
+    }
+}
+
+/// Router names and connection pool keys of the given routers
+fn active_sets(routers: &[RouterConfig]) -> (HashSet<String>, HashSet<String>) {
+    let names = routers.iter().map(|router| router.name.clone()).collect();
+    let pool_keys = routers
+        .iter()
+        .map(|router| format!("{}:{}", router.address, router.username))
+        .collect();
+    (names, pool_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_removed_router_prunes_snapshots_and_probe_registries() {
+        let config = Arc::new(Config {
+            routers: vec![RouterConfig {
+                name: "gone".to_string(),
+                address: "127.0.0.1:1".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let reloader = ConfigReloader::new(&config);
+        let snapshots = Arc::new(RwLock::new(HashMap::from([(
+            "gone".to_string(),
+            RouterMetrics::default(),
+        )])));
+        let probe_metrics = Arc::new(Mutex::new(HashMap::from([(
+            "gone".to_string(),
+            MetricsRegistry::new(),
+        )])));
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        reloader.active_sender().send_replace(Arc::new(Vec::new()));
+        let handle = start_collection_loop(
+            shutdown_rx,
+            config,
+            MetricsRegistry::new(),
+            Arc::new(ConnectionPool::new()),
+            &reloader,
+            snapshots.clone(),
+            probe_metrics.clone(),
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !probe_metrics.lock().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(snapshots.read().await.is_empty());
+
+        shutdown_tx.send(true).unwrap();
+        handle.await.unwrap();
+    }
+}
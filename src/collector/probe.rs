@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! On-demand collection into an isolated registry
+
+use std::sync::Arc;
+
+use crate::config::RouterConfig;
+use crate::metrics::{MetricsRegistry, RouterLabels};
+use crate::mikrotik::ConnectionPool;
+
+use super::router_task::collect_router;
+
+/// Collects a single router into a fresh, throwaway registry
+///
+/// The returned registry owns its own counter-delta state, so an on-demand
+/// probe never pollutes the background registry. Connections are still
+/// taken from the shared pool.
+pub async fn probe_router(router: &RouterConfig, pool: Arc<ConnectionPool>) -> MetricsRegistry {
+    let metrics = MetricsRegistry::new();
+    metrics.initialize_router_metrics(&RouterLabels {
+        router: router.name.clone(),
+    });
+    probe_router_into(router, &pool, &metrics).await;
+    metrics
+}
+
+/// Collects a single router into a registry kept for probing it
+///
+/// Reusing the registry across probes of the same router keeps its counter
+/// deltas continuous while staying apart from the background registry.
+pub(crate) async fn probe_router_into(
+    router: &RouterConfig,
+    pool: &Arc<ConnectionPool>,
+    metrics: &MetricsRegistry,
+) {
+    collect_router(router, pool, metrics, None).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_probe_router_does_not_touch_background_registry() {
+        let router = RouterConfig {
+            name: "probe-router".to_string(),
+            address: "invalid:address".to_string(),
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            ..Default::default()
+        };
+        let labels = RouterLabels {
+            router: router.name.clone(),
+        };
+        let background = MetricsRegistry::new();
+        let pool = Arc::new(ConnectionPool::new());
+
+        let probe = probe_router(&router, pool).await;
+
+        assert_eq!(probe.get_scrape_error_count(&labels).await, 1);
+        assert_eq!(background.get_scrape_error_count(&labels).await, 0);
+    }
+}
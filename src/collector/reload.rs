@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Runtime reload of the configured routers
+//!
+//! Shared by the SIGHUP handler and `POST /reload`. The configuration is read
+//! again with [`Config::from_env`] and its routers replace the statically
+//! configured ones; the collection loop picks them up on its next cycle.
+//! The reloaded configuration is published too, so the HTTP handlers apply
+//! the metrics auth credentials, the reload token, the `/metrics` cache TTL
+//! and `HEALTH_FAIL_ON_DEGRADED` right away. Other settings, such as the
+//! listen address, the collection interval or the metric prefix, still need
+//! a restart.
+//!
+//! The reloader also publishes the routers actually collected: the configured
+//! ones, plus the discovered ones when service discovery is enabled.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::AppError;
+use crate::config::{Collector, Config, RouterConfig};
+
+/// Publishes the configuration and its routers and replaces them on reload
+#[derive(Clone)]
+pub struct ConfigReloader {
+    /// Current configuration, read by the HTTP handlers
+    config: watch::Sender<Arc<Config>>,
+    routers: watch::Sender<Arc<Vec<RouterConfig>>>,
+    /// Configured routers plus discovered ones, as scraped by the collection loop
+    active: watch::Sender<Arc<Vec<RouterConfig>>>,
+    /// Whether service discovery publishes `active`; without it `active`
+    /// follows the configured routers
+    discovery: bool,
+    /// `--only` restriction from the command line, kept across reloads
+    only_collectors: Option<HashSet<Collector>>,
+}
+
+impl ConfigReloader {
+    /// Starts from the routers of the initial configuration
+    #[must_use]
+    pub fn new(config: &Config) -> Self {
+        let (routers, _) = watch::channel(Arc::new(config.routers.clone()));
+        let (active, _) = watch::channel(Arc::new(config.routers.clone()));
+        let (current, _) = watch::channel(Arc::new(config.clone()));
+        Self {
+            config: current,
+            routers,
+            active,
+            discovery: config.sd_url.is_some(),
+            only_collectors: config.only_collectors.clone(),
+        }
+    }
+
+    /// Current configuration, including settings changed by the last reload
+    #[must_use]
+    pub fn config(&self) -> Arc<Config> {
+        self.config.borrow().clone()
+    }
+
+    /// Currently configured routers
+    #[must_use]
+    pub fn routers(&self) -> Arc<Vec<RouterConfig>> {
+        self.routers.borrow().clone()
+    }
+
+    /// Receiver notified whenever the routers are replaced
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Arc<Vec<RouterConfig>>> {
+        self.routers.subscribe()
+    }
+
+    /// Routers currently collected, discovered ones included
+    #[must_use]
+    pub fn active_routers(&self) -> Arc<Vec<RouterConfig>> {
+        self.active.borrow().clone()
+    }
+
+    /// Receiver notified whenever the collected routers change
+    pub(crate) fn subscribe_active(&self) -> watch::Receiver<Arc<Vec<RouterConfig>>> {
+        self.active.subscribe()
+    }
+
+    /// Sender the discovery task publishes the merged router list through
+    pub(crate) fn active_sender(&self) -> watch::Sender<Arc<Vec<RouterConfig>>> {
+        self.active.clone()
+    }
+
+    /// Reads the configuration again and replaces the configured routers
+    ///
+    /// Returns the number of routers now configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns the configuration error if it can't be loaded; the current
+    /// configuration is kept in that case.
+    pub async fn reload(&self) -> crate::Result<usize> {
+        // Reading the configuration file blocks
+        let config = tokio::task::spawn_blocking(Config::from_env)
+            .await
+            .map_err(|e| AppError::Config(format!("configuration reload task failed: {e}")))??;
+        Ok(self.replace(config))
+    }
+
+    /// Replaces the configuration and the configured routers with `config`
+    pub(crate) fn replace(&self, config: Config) -> usize {
+        let mut config = match &self.only_collectors {
+            Some(only) => config.with_only_collectors(only.clone()),
+            None => config,
+        };
+        // Baked into the metrics registries at startup
+        {
+            let current = self.config.borrow();
+            config.metric_prefix.clone_from(&current.metric_prefix);
+            config.environment.clone_from(&current.environment);
+        }
+        let count = config.routers.len();
+        tracing::info!("Configuration reloaded, {} router(s) configured", count);
+        for router in &config.routers {
+            tracing::info!("  - Router '{}' at {}", router.name, router.address);
+        }
+        let routers = Arc::new(config.routers.clone());
+        if !self.discovery {
+            self.active.send_replace(routers.clone());
+        }
+        self.routers.send_replace(routers);
+        self.config.send_replace(Arc::new(config));
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(name: &str) -> RouterConfig {
+        RouterConfig {
+            name: name.to_string(),
+            address: "10.0.0.1:8728".to_string(),
+            username: "admin".to_string(),
+            password: "secret".to_string().into(),
+            collectors: Collector::ALL.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_publishes_routers_and_keeps_only_restriction() {
+        let initial = Config {
+            routers: vec![router("core")],
+            ..Default::default()
+        }
+        .with_only_collectors(HashSet::from([Collector::System]));
+        let reloader = ConfigReloader::new(&initial);
+        let mut routers_rx = reloader.subscribe();
+
+        let count = reloader.replace(Config {
+            routers: vec![router("core"), router("edge")],
+            ..Default::default()
+        });
+
+        assert_eq!(count, 2);
+        assert!(routers_rx.has_changed().unwrap());
+        let routers = routers_rx.borrow_and_update().clone();
+        assert_eq!(routers[1].name, "edge");
+        assert_eq!(routers[1].collectors, HashSet::from([Collector::System]));
+        assert_eq!(reloader.routers().len(), 2);
+        assert_eq!(reloader.active_routers().len(), 2);
+    }
+
+    #[test]
+    fn test_replace_publishes_settings_but_keeps_metric_prefix() {
+        let reloader = ConfigReloader::new(&Config {
+            metric_prefix: "edge".to_string(),
+            environment: Some("prod".to_string()),
+            ..Default::default()
+        });
+
+        reloader.replace(Config {
+            metrics_cache_ttl_secs: 15,
+            health_fail_on_degraded: false,
+            metrics_auth_user: Some("prom".to_string()),
+            ..Default::default()
+        });
+
+        let config = reloader.config();
+        assert_eq!(config.metrics_cache_ttl_secs, 15);
+        assert!(!config.health_fail_on_degraded);
+        assert_eq!(config.metrics_auth_user.as_deref(), Some("prom"));
+        assert_eq!(config.metric_prefix, "edge");
+        assert_eq!(config.environment.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_active_routers_come_from_discovery_when_enabled() {
+        let reloader = ConfigReloader::new(&Config {
+            routers: vec![router("core")],
+            sd_url: Some("http://sd.local/routers".to_string()),
+            ..Default::default()
+        });
+
+        reloader
+            .active_sender()
+            .send_replace(Arc::new(vec![router("core"), router("discovered")]));
+        reloader.replace(Config {
+            routers: vec![router("core"), router("edge")],
+            ..Default::default()
+        });
+
+        // The discovery task merges the reloaded routers itself
+        let names: Vec<String> = reloader
+            .active_routers()
+            .iter()
+            .map(|r| r.name.clone())
+            .collect();
+        assert_eq!(names, ["core", "discovered"]);
+        assert_eq!(reloader.routers().len(), 2);
+    }
+}
@@ -6,97 +6,293 @@
 use crate::config::RouterConfig;
 use crate::metrics::labels::InterfaceLabels;
 use crate::metrics::{MetricsRegistry, RouterLabels};
-use crate::mikrotik::{ConnectionPool, MikroTikClient};
-use std::collections::HashSet;
+use crate::mikrotik::{ConnectionPool, MikroTikClient, RouterMetrics, ScrapeErrorReason};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher, RandomState};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, Semaphore};
 
 use super::cache::SystemInfoCache;
+use super::graphite::GraphiteSink;
 
+/// Spawns the scrape of one router for the current cycle
+///
+/// The scrape starts after a random delay below `max_splay`, taken before
+/// waiting for a scrape permit.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn spawn_router_collection(
     router: RouterConfig,
     pool: Arc<ConnectionPool>,
     metrics: MetricsRegistry,
     system_cache: SystemInfoCache,
     active_interfaces: Arc<tokio::sync::Mutex<HashSet<InterfaceLabels>>>,
+    interval: Duration,
+    max_splay: Duration,
+    graphite: Option<GraphiteSink>,
+    scrape_permits: Arc<Semaphore>,
+    snapshots: Arc<RwLock<HashMap<String, RouterMetrics>>>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let router_name = router.name.clone();
-        let client = MikroTikClient::with_pool(router.clone(), pool.clone());
-        let router_label = RouterLabels {
-            router: router_name.clone(),
+        let splay = random_splay(max_splay);
+        if !splay.is_zero() {
+            tokio::time::sleep(splay).await;
+        }
+        let result = {
+            let Ok(_permit) = scrape_permits.acquire().await else {
+                return;
+            };
+            metrics.collection_started();
+            let result = collect_router(&router, &pool, &metrics, Some(interval)).await;
+            metrics.collection_finished();
+            result
+        };
+        let Some(m) = result else {
+            return;
         };
 
-        tracing::trace!("Starting metrics collection for router: {}", router_name);
-        let start = std::time::Instant::now();
-        match client.collect_metrics().await {
-            Ok(m) => {
-                let duration = start.elapsed().as_secs_f64();
-
-                // Track active interfaces
-                {
-                    let mut active = active_interfaces.lock().await;
-                    for iface in &m.interfaces {
-                        active.insert(InterfaceLabels {
-                            router: router_name.clone(),
-                            interface: iface.name.clone(),
-                        });
-                    }
-                }
+        if let Some(sink) = &graphite {
+            sink.push(&m).await;
+        }
 
-                metrics.update_metrics(&m).await;
-                metrics.record_scrape_success(&router_label);
-                metrics.record_scrape_duration(&router_label, duration);
+        // Track active interfaces
+        {
+            let mut active = active_interfaces.lock().await;
+            for iface in &m.interfaces {
+                active.insert(InterfaceLabels {
+                    router: router_name.clone(),
+                    interface: iface.name.clone(),
+                });
+            }
+        }
 
-                // Cache system info if it's the first time
-                if system_cache.get(&router_name).await.is_none() {
-                    system_cache
-                        .set(router_name.clone(), m.system.clone())
-                        .await;
-                }
+        snapshots
+            .write()
+            .await
+            .insert(router_name.clone(), m.clone());
+
+        // Cache system info if it's the first time
+        if let Some(system) = m.system {
+            if system_cache.get(&router_name).await.is_none() {
+                system_cache.set(router_name, system).await;
+            }
+        }
+    })
+}
+
+/// Random delay in `[0, max)` with millisecond resolution
+fn random_splay(max: Duration) -> Duration {
+    let max_millis = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+    // Every `RandomState` is seeded differently, which is random enough to
+    // spread scrapes without pulling in an RNG
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_millis(random % max_millis)
+}
+
+/// Collects metrics from one router into the given registry
+///
+/// Updates the registry with the collected snapshot and records scrape
+/// status, duration and connection errors. Delta state lives in `metrics`,
+/// so passing a fresh registry keeps the scrape fully isolated.
+///
+/// When `interval` is given, scrapes slower than the collection interval are
+/// logged and flagged via `mikrotik_scrape_interval_too_short`.
+///
+/// Returns the collected snapshot on success.
+pub(super) async fn collect_router(
+    router: &RouterConfig,
+    pool: &Arc<ConnectionPool>,
+    metrics: &MetricsRegistry,
+    interval: Option<Duration>,
+) -> Option<RouterMetrics> {
+    let router_name = &router.name;
+    let client = MikroTikClient::with_pool(router.clone(), pool.clone());
+    let router_label = RouterLabels {
+        router: router_name.clone(),
+    };
+
+    metrics
+        .record_enabled_collectors(router_name, router.collectors.iter().copied())
+        .await;
+
+    tracing::trace!("Starting metrics collection for router: {}", router_name);
+    let start = std::time::Instant::now();
+    let result = client.collect_metrics().await;
+    let duration = start.elapsed().as_secs_f64();
 
-                // Update connection error count
-                if let Some((errors, _)) = pool
-                    .get_connection_state(&router.address, &router.username)
-                    .await
-                {
-                    metrics.update_connection_errors(&router_label, errors);
+    match &result {
+        Ok(m) => {
+            metrics.update_metrics(m).await;
+            metrics.record_scrape_success(&router_label);
+            metrics
+                .record_scrape_duration(&router_label, duration)
+                .await;
+        }
+        Err(e) => {
+            let reason = match router.depends_on.as_deref() {
+                Some(dependency) if metrics.router_up(dependency) == Some(false) => {
+                    tracing::warn!(
+                        "Router {} depends on {}, whose last scrape failed",
+                        router_name,
+                        dependency
+                    );
+                    ScrapeErrorReason::DependencyDown
                 }
+                _ => ScrapeErrorReason::classify(e.as_ref()),
+            };
+            metrics.record_scrape_error(&router_label);
+            metrics
+                .record_scrape_error_reason(router_name, reason)
+                .await;
+            metrics
+                .record_scrape_duration(&router_label, duration)
+                .await;
+        }
+    }
 
-                tracing::debug!(
-                    "Collected metrics for router {} in {:.3}s",
-                    router_name,
-                    duration
-                );
+    if let Some(interval) = interval {
+        let too_short = duration > interval.as_secs_f64();
+        if too_short {
+            tracing::warn!(
+                "Scrape of router {} took {:.3}s, longer than the {}s collection interval; \
+                 increase COLLECTION_INTERVAL_SECONDS to avoid overlapping scrapes",
+                router_name,
+                duration,
+                interval.as_secs()
+            );
+        }
+        metrics.record_scrape_interval_check(&router_label, too_short);
+    }
+
+    // Update connection error count
+    if let Some((errors, _)) = pool
+        .get_connection_state(&router.address, &router.username)
+        .await
+    {
+        metrics.update_connection_errors(&router_label, errors);
+    }
+    if let Some(state) = pool
+        .get_circuit_state(&router.address, &router.username)
+        .await
+    {
+        metrics.update_circuit_breaker_state(&router_label, state);
+    }
+    if let Some((method, changes)) = pool
+        .get_login_method(&router.address, &router.username)
+        .await
+    {
+        metrics.update_login_method(&router_label, method, changes);
+    }
+    if let Some(resets) = pool
+        .get_connection_resets(&router.address, &router.username)
+        .await
+    {
+        metrics.update_connection_resets(&router_label, resets);
+    }
+
+    match result {
+        Ok(m) => {
+            tracing::debug!(
+                "Collected metrics for router {} in {:.3}s",
+                router_name,
+                duration
+            );
+            if let Some(system) = &m.system {
                 tracing::trace!(
                     "Router {} metrics: {} interfaces, CPU: {}%, Memory: {}/{} bytes",
                     router_name,
                     m.interfaces.len(),
-                    m.system.cpu_load,
-                    m.system.free_memory,
-                    m.system.total_memory
+                    system.cpu_load,
+                    system.free_memory,
+                    system.total_memory
                 );
             }
-            Err(e) => {
-                let duration = start.elapsed().as_secs_f64();
-                metrics.record_scrape_error(&router_label);
-                metrics.record_scrape_duration(&router_label, duration);
-
-                // Update connection error count
-                if let Some((errors, _)) = pool
-                    .get_connection_state(&router.address, &router.username)
-                    .await
-                {
-                    metrics.update_connection_errors(&router_label, errors);
-                }
+            Some(m)
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to collect metrics for {} in {:.3}s: {}",
+                router_name,
+                duration,
+                e
+            );
+            tracing::trace!("Error details for {}: {:?}", router_name, e);
+            None
+        }
+    }
+}
 
-                tracing::warn!(
-                    "Failed to collect metrics for {} in {:.3}s: {}",
-                    router_name,
-                    duration,
-                    e
-                );
-                tracing::trace!("Error details for {}: {:?}", router_name, e);
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_splay_stays_below_max() {
+        assert_eq!(random_splay(Duration::ZERO), Duration::ZERO);
+        let max = Duration::from_secs(2);
+        for _ in 0..100 {
+            assert!(random_splay(max) < max);
         }
-    })
+    }
+
+    #[tokio::test]
+    async fn test_collect_router_flags_scrape_longer_than_interval() {
+        let router = RouterConfig {
+            name: "slow-router".to_string(),
+            address: "invalid:address".to_string(),
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            ..Default::default()
+        };
+        let metrics = MetricsRegistry::new();
+        let pool = Arc::new(ConnectionPool::new());
+
+        let series = "mikrotik_scrape_interval_too_short{router=\"slow-router\"}";
+
+        collect_router(&router, &pool, &metrics, Some(Duration::ZERO)).await;
+        let encoded = metrics.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("{series} 1")));
+
+        collect_router(&router, &pool, &metrics, Some(Duration::from_secs(3600))).await;
+        let encoded = metrics.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("{series} 0")));
+    }
+
+    #[tokio::test]
+    async fn test_collect_router_records_error_reason_and_dependency() {
+        // A port nothing listens on anymore refuses connections
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let mut router = RouterConfig {
+            name: "branch".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            ..Default::default()
+        };
+        let metrics = MetricsRegistry::new();
+        let pool = Arc::new(ConnectionPool::new());
+
+        collect_router(&router, &pool, &metrics, None).await;
+        let encoded = metrics.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_scrape_error_reason_total{router=\"branch\",reason=\"connect_refused\"} 1"
+        ));
+
+        metrics.record_scrape_error(&RouterLabels {
+            router: "vpn-gw".to_string(),
+        });
+        router.depends_on = Some("vpn-gw".to_string());
+        collect_router(&router, &pool, &metrics, None).await;
+        let encoded = metrics.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_scrape_error_reason_total{router=\"branch\",reason=\"dependency_down\"} 1"
+        ));
+    }
 }
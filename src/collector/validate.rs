@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! One-shot connectivity check of every configured router
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::mikrotik::{ConnectionPool, MikroTikClient};
+
+/// Outcome of a single validation collection for one router
+#[derive(Debug, Clone)]
+pub struct RouterValidation {
+    pub router: String,
+    pub address: String,
+    /// How long the collection took
+    pub duration: Duration,
+    /// Why the collection failed, `None` on success
+    pub error: Option<String>,
+    /// RouterOS commands that returned an error during a successful collection
+    pub failed_commands: Vec<String>,
+}
+
+impl RouterValidation {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for RouterValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.is_ok() { "OK" } else { "FAIL" };
+        write!(
+            f,
+            "{status:<4} {} ({}) in {} ms",
+            self.router,
+            self.address,
+            self.duration.as_millis()
+        )?;
+        if let Some(error) = &self.error {
+            write!(f, ": {error}")?;
+        } else if !self.failed_commands.is_empty() {
+            write!(f, ", failed commands: {}", self.failed_commands.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects every configured router once, concurrently, without touching a
+/// metrics registry
+///
+/// Results are returned in configuration order.
+pub async fn validate_routers(config: &Config, pool: Arc<ConnectionPool>) -> Vec<RouterValidation> {
+    let handles: Vec<_> = config
+        .routers
+        .iter()
+        .map(|router| {
+            let client = MikroTikClient::with_pool(router.clone(), pool.clone());
+            tokio::spawn(async move {
+                let start = Instant::now();
+                let result = client.collect_metrics().await;
+                (start.elapsed(), result)
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for (handle, router) in handles.into_iter().zip(&config.routers) {
+        let (duration, error, failed_commands) = match handle.await {
+            Ok((duration, Ok(metrics))) => (duration, None, metrics.command_errors),
+            Ok((duration, Err(e))) => (duration, Some(e.to_string()), Vec::new()),
+            Err(e) => (
+                Duration::ZERO,
+                Some(format!("validation task failed: {e}")),
+                Vec::new(),
+            ),
+        };
+        results.push(RouterValidation {
+            router: router.name.clone(),
+            address: router.address.clone(),
+            duration,
+            error,
+            failed_commands,
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RouterConfig;
+
+    #[tokio::test]
+    async fn test_validate_routers_reports_unreachable_router() {
+        let config = Config {
+            routers: vec![RouterConfig {
+                name: "broken".to_string(),
+                address: "invalid:address".to_string(),
+                username: "admin".to_string(),
+                password: "password".to_string().into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let results = validate_routers(&config, Arc::new(ConnectionPool::new())).await;
+
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].is_ok());
+        assert!(
+            results[0]
+                .to_string()
+                .starts_with("FAIL broken (invalid:address)")
+        );
+    }
+
+    #[test]
+    fn test_router_validation_display_lists_failed_commands() {
+        let validation = RouterValidation {
+            router: "core".to_string(),
+            address: "10.0.0.1:8728".to_string(),
+            duration: Duration::from_millis(250),
+            error: None,
+            failed_commands: vec!["/caps-man/radio/print".to_string()],
+        };
+
+        assert_eq!(
+            validation.to_string(),
+            "OK   core (10.0.0.1:8728) in 250 ms, failed commands: /caps-man/radio/print"
+        );
+    }
+}
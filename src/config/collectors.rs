@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Collector selection and named collector modules
+//!
+//! A module is a named set of collectors defined once in the configuration
+//! and referenced by routers, similar to blackbox_exporter modules.
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// A group of RouterOS commands that can be enabled or disabled per router
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collector {
+    /// `/system/resource/print`
+    System,
+    /// `/interface/print`
+    Interfaces,
+    /// `/ip/firewall/connection/print` and its IPv6 counterpart
+    Conntrack,
+    /// `/ip/firewall/connection/tracking/print` for the table size and limit
+    ConntrackTable,
+    /// `/interface/wireguard/print` and `/interface/wireguard/peers/print`
+    Wireguard,
+    /// `/ip/service/print`
+    IpServices,
+    /// `/interface/bonding/print`, with slave state from the interfaces collector
+    Bonding,
+    /// `/system/script/job/print`
+    ScriptJobs,
+    /// `/system/clock/print`
+    Clock,
+    /// `/system/license/print`
+    License,
+    /// `/interface/wifi/print` and a one-shot `/interface/wifi/monitor` per interface
+    Wifi,
+    /// `/caps-man/radio/print` and `/caps-man/registration-table/print` on controllers
+    Capsman,
+    /// `/ping` towards each of the router's `ping_targets`
+    Ping,
+    /// `/queue/simple/print` with stats
+    Queues,
+    /// `/queue/interface/print` with stats
+    InterfaceQueues,
+    /// `/ip/route/print` limited to default routes, and the number of
+    /// `/ip/route` and `/ipv6/route` entries
+    Routes,
+    /// `/user-manager/session/print` counting active sessions; skipped when
+    /// the User Manager package isn't installed
+    UserManager,
+    /// `/ppp/active/print`, counting newly established sessions
+    Ppp,
+    /// `/interface/eoip/print`, `/interface/gre/print` and `/interface/vxlan/print`
+    Tunnels,
+    /// `/interface/vrrp/print`
+    Vrrp,
+    /// `/ip/dhcp-server/lease/print`
+    Dhcp,
+    /// `/ip/firewall/filter/print` with stats
+    Firewall,
+    /// `/container/print`; skipped when the container package isn't installed
+    Container,
+    /// `/system/watchdog/print` and critical `/log/print` entries left by
+    /// unexpected reboots
+    Watchdog,
+    /// `/system/health/print` temperature, voltage and fan sensors
+    Health,
+    /// `/ip/ipsec/active-peers/print` peer state and traffic
+    Ipsec,
+    /// `/interface/wireless/registration-table/print`; skipped when the
+    /// wireless package isn't installed
+    Wireless,
+    /// Print commands of the configured custom collectors
+    Custom,
+    /// `/routing/bgp/session/print`, or `/routing/bgp/peer/print` on RouterOS 6
+    Bgp,
+    /// `/interface/ethernet/print` and a one-shot `/interface/ethernet/monitor`
+    /// per interface for SFP diagnostics
+    Sfp,
+}
+
+impl Collector {
+    /// Every known collector
+    pub const ALL: [Self; 30] = [
+        Self::System,
+        Self::Interfaces,
+        Self::Conntrack,
+        Self::ConntrackTable,
+        Self::Wireguard,
+        Self::IpServices,
+        Self::Bonding,
+        Self::ScriptJobs,
+        Self::Clock,
+        Self::License,
+        Self::Wifi,
+        Self::Capsman,
+        Self::Ping,
+        Self::Queues,
+        Self::InterfaceQueues,
+        Self::Routes,
+        Self::UserManager,
+        Self::Ppp,
+        Self::Tunnels,
+        Self::Vrrp,
+        Self::Dhcp,
+        Self::Firewall,
+        Self::Container,
+        Self::Watchdog,
+        Self::Health,
+        Self::Ipsec,
+        Self::Wireless,
+        Self::Custom,
+        Self::Bgp,
+        Self::Sfp,
+    ];
+
+    /// Name used in configuration and metric labels
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Interfaces => "interfaces",
+            Self::Conntrack => "conntrack",
+            Self::ConntrackTable => "conntrack_table",
+            Self::Wireguard => "wireguard",
+            Self::IpServices => "ip_services",
+            Self::Bonding => "bonding",
+            Self::ScriptJobs => "script_jobs",
+            Self::Clock => "clock",
+            Self::License => "license",
+            Self::Wifi => "wifi",
+            Self::Capsman => "capsman",
+            Self::Ping => "ping",
+            Self::Queues => "queues",
+            Self::InterfaceQueues => "interface_queues",
+            Self::Routes => "routes",
+            Self::UserManager => "user_manager",
+            Self::Ppp => "ppp",
+            Self::Tunnels => "tunnels",
+            Self::Vrrp => "vrrp",
+            Self::Dhcp => "dhcp",
+            Self::Firewall => "firewall",
+            Self::Container => "container",
+            Self::Watchdog => "watchdog",
+            Self::Health => "health",
+            Self::Ipsec => "ipsec",
+            Self::Wireless => "wireless",
+            Self::Custom => "custom",
+            Self::Bgp => "bgp",
+            Self::Sfp => "sfp",
+        }
+    }
+}
+
+impl Collector {
+    /// Looks up a collector by its configuration name
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
+/// Parses a comma-separated list of collector names, skipping unknown ones
+/// with a warning
+#[must_use]
+pub fn parse_collector_list(value: &str) -> HashSet<Collector> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let collector = Collector::from_name(name);
+            if collector.is_none() {
+                tracing::warn!("Unknown collector '{}' in collector list. Ignoring.", name);
+            }
+            collector
+        })
+        .collect()
+}
+
+/// Collectors enabled for routers that don't reference a module
+#[must_use]
+pub fn default_collectors() -> HashSet<Collector> {
+    Collector::ALL.into_iter().collect()
+}
+
+/// Named collector modules, keyed by module name
+pub type CollectorModules = HashMap<String, HashSet<Collector>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_deserialize_snake_case() {
+        let collectors: Vec<Collector> = serde_json::from_str(
+            r#"["system", "interfaces", "conntrack", "conntrack_table", "wireguard", "ip_services", "bonding", "script_jobs", "clock", "license", "wifi", "capsman", "ping", "queues", "interface_queues", "routes", "user_manager", "ppp", "tunnels", "vrrp", "dhcp", "firewall", "container", "watchdog", "health", "ipsec", "wireless", "custom", "bgp", "sfp"]"#,
+        )
+        .unwrap();
+        assert_eq!(collectors, Collector::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_collector_names_roundtrip() {
+        for collector in Collector::ALL {
+            let json = format!("\"{}\"", collector.name());
+            let parsed: Collector = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, collector);
+        }
+    }
+
+    #[test]
+    fn test_parse_collector_list() {
+        let collectors = parse_collector_list(" interfaces,system,,bogus ");
+        assert_eq!(
+            collectors,
+            [Collector::Interfaces, Collector::System]
+                .into_iter()
+                .collect()
+        );
+        assert!(parse_collector_list("").is_empty());
+    }
+
+    #[test]
+    fn test_default_collectors_include_all() {
+        assert_eq!(default_collectors().len(), Collector::ALL.len());
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! User-defined collectors exporting fields of any RouterOS print command
+
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Label names owned by the exporter itself
+const RESERVED_LABELS: [&str; 2] = ["router", "env"];
+
+/// A print command whose entries become a `mikrotik_custom_<name>` gauge
+///
+/// Only `value` and `labels` are requested from the router (`.proplist`).
+/// Entries without the value field, or whose value isn't a number or a
+/// boolean, are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CustomCollector {
+    /// Metric name suffix, e.g. `graphing_interfaces`
+    pub name: String,
+    /// Print command path, e.g. `/tool/graphing/interface/print`
+    pub command: String,
+    /// Property exported as the gauge value
+    pub value: String,
+    /// Properties exported as labels; `-` in a property name becomes `_` in
+    /// the label name
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl CustomCollector {
+    /// Properties to request with `.proplist`
+    #[must_use]
+    pub fn properties(&self) -> Vec<&str> {
+        std::iter::once(self.value.as_str())
+            .chain(self.labels.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Validates the collector definition
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_identifier(&self.name) {
+            return Err(format!(
+                "Invalid custom collector name '{}': use letters, digits and '_'",
+                self.name
+            ));
+        }
+        if !self.command.starts_with('/') {
+            return Err(format!(
+                "Invalid command '{}' for custom collector '{}': paths must start with '/'",
+                self.command, self.name
+            ));
+        }
+        if self.value.trim().is_empty() {
+            return Err(format!(
+                "Custom collector '{}' has no value property",
+                self.name
+            ));
+        }
+        let mut seen = HashSet::new();
+        for property in &self.labels {
+            let label = label_name(property);
+            if !is_identifier(&label) || RESERVED_LABELS.contains(&label.as_str()) {
+                return Err(format!(
+                    "Invalid label property '{}' for custom collector '{}'",
+                    property, self.name
+                ));
+            }
+            if !seen.insert(label) {
+                return Err(format!(
+                    "Duplicate label property '{}' for custom collector '{}'",
+                    property, self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Label name for a RouterOS property, e.g. `mac-address` -> `mac_address`
+#[must_use]
+pub fn label_name(property: &str) -> String {
+    property.replace('-', "_")
+}
+
+/// Drops invalid custom collectors and repeated names, logging an error for each
+#[must_use]
+pub fn valid_custom_collectors(collectors: Vec<CustomCollector>) -> Vec<CustomCollector> {
+    let mut names = HashSet::new();
+    collectors
+        .into_iter()
+        .filter(|collector| match collector.validate() {
+            Ok(()) if names.insert(collector.name.clone()) => true,
+            Ok(()) => {
+                tracing::error!(
+                    "Duplicate custom collector name '{}'; skipping",
+                    collector.name
+                );
+                false
+            }
+            Err(e) => {
+                tracing::error!("{}; skipping", e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Whether `s` is a valid Prometheus metric or label name fragment
+pub(super) fn is_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector(name: &str, labels: &[&str]) -> CustomCollector {
+        CustomCollector {
+            name: name.to_string(),
+            command: "/tool/graphing/interface/print".to_string(),
+            value: "store-on-disk".to_string(),
+            labels: labels.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_custom_collector_deserialize() {
+        let parsed: CustomCollector = serde_json::from_str(
+            r#"{"name": "leases", "command": "/ip/dhcp-server/lease/print",
+                "value": "expires-after", "labels": ["mac-address", "server"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.name, "leases");
+        assert_eq!(
+            parsed.properties(),
+            vec!["expires-after", "mac-address", "server"]
+        );
+        assert!(parsed.validate().is_ok());
+    }
+
+    #[test]
+    fn test_custom_collector_validate() {
+        assert!(
+            collector("graphing", &["interface", "allow-address"])
+                .validate()
+                .is_ok()
+        );
+        assert!(collector("bad-name", &[]).validate().is_err());
+        assert!(collector("2fast", &[]).validate().is_err());
+        assert!(collector("graphing", &["router"]).validate().is_err());
+        assert!(collector("graphing", &["a-b", "a_b"]).validate().is_err());
+
+        let mut relative = collector("graphing", &[]);
+        relative.command = "tool/graphing/print".to_string();
+        assert!(relative.validate().is_err());
+    }
+
+    #[test]
+    fn test_valid_custom_collectors_drops_invalid_and_duplicates() {
+        let collectors = valid_custom_collectors(vec![
+            collector("graphing", &[]),
+            collector("bad-name", &[]),
+            collector("graphing", &["interface"]),
+        ]);
+
+        assert_eq!(collectors, vec![collector("graphing", &[])]);
+    }
+}
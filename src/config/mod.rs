@@ -3,14 +3,30 @@
 
 //! Configuration module for MikroTik Exporter application
 //!
-//! Loads and parses configuration from environment variables and JSON.
+//! Loads and parses configuration from environment variables and JSON, or
+//! from a TOML file named by `CONFIG_FILE`.
 
+use crate::error::AppError;
 use secrecy::SecretString;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
+mod collectors;
+mod custom;
+mod prefix;
 #[cfg(test)]
 mod tests;
 
+pub use collectors::{Collector, CollectorModules, default_collectors, parse_collector_list};
+pub use custom::{CustomCollector, label_name, valid_custom_collectors};
+pub use prefix::IpPrefix;
+
+use custom::is_identifier;
+
+/// Prefix of every exported metric name unless `METRIC_PREFIX` is set
+pub const DEFAULT_METRIC_PREFIX: &str = "mikrotik";
+
 /// Default configuration values
 mod defaults {
     pub const SERVER_ADDR: &str = "0.0.0.0:9090";
@@ -22,6 +38,86 @@ mod defaults {
 mod env_vars {
     pub const SERVER_ADDR: &str = "SERVER_ADDR";
     pub const ROUTERS_CONFIG: &str = "ROUTERS_CONFIG";
+    pub const METRICS_CACHE_TTL_SECONDS: &str = "METRICS_CACHE_TTL_SECONDS";
+    pub const COLLECTOR_MODULES: &str = "COLLECTOR_MODULES";
+    pub const INCLUDE_IDS: &str = "INCLUDE_IDS";
+    pub const CIRCUIT_BREAKER_OPEN_SECONDS: &str = "CIRCUIT_BREAKER_OPEN_SECONDS";
+    pub const CONNECT_TIMEOUT_SECONDS: &str = "CONNECT_TIMEOUT_SECONDS";
+    pub const READ_TIMEOUT_SECONDS: &str = "READ_TIMEOUT_SECONDS";
+    pub const COLLECTION_TIMEOUT_SECONDS: &str = "COLLECTION_TIMEOUT_SECONDS";
+    pub const MAX_CONCURRENT_SCRAPES: &str = "MAX_CONCURRENT_SCRAPES";
+    pub const SCRAPE_SPLAY_SECONDS: &str = "SCRAPE_SPLAY_SECONDS";
+    pub const WIREGUARD_ACTIVE_WINDOW_SECONDS: &str = "WIREGUARD_ACTIVE_WINDOW_SECONDS";
+    pub const SCRAPE_DURATION_WINDOW: &str = "SCRAPE_DURATION_WINDOW";
+    pub const ENVIRONMENT: &str = "ENVIRONMENT";
+    pub const HEALTH_FAIL_ON_DEGRADED: &str = "HEALTH_FAIL_ON_DEGRADED";
+    pub const VALIDATE: &str = "VALIDATE";
+    pub const SD_URL: &str = "SD_URL";
+    pub const SD_REFRESH_INTERVAL_SECONDS: &str = "SD_REFRESH_INTERVAL_SECONDS";
+    pub const GRAPHITE_ADDR: &str = "GRAPHITE_ADDR";
+    pub const RELOAD_TOKEN: &str = "RELOAD_TOKEN";
+    pub const METRICS_AUTH_USER: &str = "METRICS_AUTH_USER";
+    pub const METRICS_AUTH_PASSWORD: &str = "METRICS_AUTH_PASSWORD";
+    pub const ONLY_COLLECTORS: &str = "ONLY_COLLECTORS";
+    pub const CONFIG_FILE: &str = "CONFIG_FILE";
+    pub const CUSTOM_COLLECTORS: &str = "CUSTOM_COLLECTORS";
+    pub const COUNTER_MODE: &str = "COUNTER_MODE";
+    pub const METRIC_PREFIX: &str = "METRIC_PREFIX";
+}
+
+/// Settings read from a configuration file; anything else still comes from
+/// the environment
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    #[serde(default)]
+    server_addr: Option<String>,
+    #[serde(default)]
+    collection_interval_secs: Option<u64>,
+    #[serde(default)]
+    scrape_splay_secs: Option<u64>,
+    #[serde(default)]
+    routers: Vec<RouterConfig>,
+    #[serde(default)]
+    custom_collectors: Option<Vec<CustomCollector>>,
+    #[serde(default)]
+    counter_mode: Option<CounterMode>,
+    #[serde(default)]
+    metric_prefix: Option<String>,
+}
+
+/// How interface counters are derived from the router's values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CounterMode {
+    /// Add the increase between scrapes; the first scrape is the baseline
+    #[default]
+    Delta,
+    /// Export the router's own value, so traffic before the exporter started
+    /// counts and a reset is visible to Prometheus
+    Absolute,
+}
+
+impl CounterMode {
+    /// Parses `delta` or `absolute`, case-insensitively
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "delta" => Some(Self::Delta),
+            "absolute" => Some(Self::Absolute),
+            _ => None,
+        }
+    }
+}
+
+/// Peer property used to identify WireGuard peers in metric labels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WireGuardPeerKey {
+    /// `allowed-address`, keeping public keys out of the exported metrics
+    #[default]
+    AllowedAddress,
+    /// `public-key`, stable even when allowed addresses change or repeat
+    PublicKey,
 }
 
 /// Configuration for a single MikroTik router
@@ -34,17 +130,109 @@ mod env_vars {
 /// - Incorrect data aggregation in the metrics registry
 /// - Race conditions in delta calculations for counter metrics
 ///
-/// The configuration loading process validates and filters out routers with duplicate names,
-/// logging errors for any duplicates found.
+/// Loading the configuration fails with [`AppError::Config`] listing every
+/// duplicate name, so the exporter doesn't start (and a reload is rejected)
+/// until they are renamed. Discovered routers are the exception: one whose
+/// name is already taken is dropped with an error log.
 #[derive(Debug, Clone, Deserialize)]
 pub struct RouterConfig {
     pub name: String,
     pub address: String,
     pub username: String,
     pub password: SecretString,
+    /// Name of the collector module to use (all collectors when unset)
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Collectors enabled for this router, resolved from `module` at load time
+    #[serde(skip, default = "default_collectors")]
+    pub collectors: HashSet<Collector>,
+    /// Add the RouterOS `.id` as an `id` label on firewall rule, queue and
+    /// DHCP lease metrics, copied from `Config::include_ids` at load time
+    #[serde(skip)]
+    pub include_ids: bool,
+    /// Source prefixes kept with per-address detail in conntrack metrics;
+    /// other sources are aggregated as `other` (all sources when empty)
+    #[serde(default)]
+    pub conntrack_prefixes: Vec<IpPrefix>,
+    /// Hosts the router pings for the `ping` collector
+    #[serde(default)]
+    pub ping_targets: Vec<String>,
+    /// Router this one is reached through, e.g. a VPN gateway; while its last
+    /// scrape failed, errors here are reported as `dependency_down`
+    #[serde(default)]
+    pub depends_on: Option<String>,
+    /// Replacement command paths keyed by the default path, e.g.
+    /// `/interface/print` -> `/interface/ethernet/print`, for routers that
+    /// keep data in nonstandard menus
+    #[serde(default)]
+    pub command_paths: HashMap<String, String>,
+    /// Only entries whose comment starts with this prefix are collected for
+    /// simple queues and firewall rules (all entries when unset)
+    #[serde(default)]
+    pub comment_prefix: Option<String>,
+    /// Connect to the api-ssl service (usually port 8729) over TLS
+    #[serde(default)]
+    pub tls: bool,
+    /// Accept any TLS certificate, e.g. the self-signed one RouterOS ships with
+    #[serde(default)]
+    pub tls_skip_verify: bool,
+    /// Identifier of WireGuard peers in metric labels
+    #[serde(default)]
+    pub wireguard_peer_key: WireGuardPeerKey,
+    /// Custom collectors run for this router, copied from
+    /// `Config::custom_collectors` at load time
+    #[serde(skip)]
+    pub custom_collectors: Vec<CustomCollector>,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            address: String::new(),
+            username: defaults::ROUTEROS_USERNAME.to_string(),
+            password: SecretString::from(defaults::ROUTEROS_PASSWORD),
+            module: None,
+            collectors: default_collectors(),
+            include_ids: false,
+            conntrack_prefixes: Vec::new(),
+            ping_targets: Vec::new(),
+            depends_on: None,
+            command_paths: HashMap::new(),
+            comment_prefix: None,
+            tls: false,
+            tls_skip_verify: false,
+            wireguard_peer_key: WireGuardPeerKey::AllowedAddress,
+            custom_collectors: Vec::new(),
+        }
+    }
 }
 
 impl RouterConfig {
+    /// Returns whether the given collector runs for this router
+    #[must_use]
+    pub fn collector_enabled(&self, collector: Collector) -> bool {
+        self.collectors.contains(&collector)
+    }
+
+    /// Resolves the referenced collector module into the enabled collector set
+    pub fn apply_module(&mut self, modules: &CollectorModules) -> Result<(), String> {
+        let Some(module) = &self.module else {
+            self.collectors = default_collectors();
+            return Ok(());
+        };
+        match modules.get(module) {
+            Some(collectors) => {
+                self.collectors = collectors.clone();
+                Ok(())
+            }
+            None => Err(format!(
+                "Unknown collector module '{}' for router '{}'",
+                module, self.name
+            )),
+        }
+    }
+
     /// Validates router configuration
     pub fn validate(&self) -> Result<(), String> {
         // Validate name is not empty
@@ -68,6 +256,16 @@ impl RouterConfig {
             ));
         }
 
+        // Validate command path overrides are menu paths
+        for (default, path) in &self.command_paths {
+            if !default.starts_with('/') || !path.starts_with('/') {
+                return Err(format!(
+                    "Invalid command path override '{}' -> '{}': paths must start with '/'",
+                    default, path
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -78,6 +276,65 @@ pub struct Config {
     pub server_addr: String,
     pub routers: Vec<RouterConfig>,
     pub collection_interval_secs: u64,
+    /// How long encoded `/metrics` output is reused (0 disables the cache)
+    pub metrics_cache_ttl_secs: u64,
+    /// Named collector modules that routers can reference
+    pub modules: CollectorModules,
+    /// Whether dynamic entities carry their RouterOS `.id` as a label (off by
+    /// default, since ids change when entries are re-created)
+    pub include_ids: bool,
+    /// How long a failing router's circuit breaker stays open before it is
+    /// probed again
+    pub circuit_breaker_open_secs: u64,
+    /// Limit for the TCP connect and TLS handshake to a router
+    pub connect_timeout_secs: u64,
+    /// Limit for a router's reply to a single command
+    pub read_timeout_secs: u64,
+    /// Limit for a router's whole scrape, every command included; defaults to
+    /// the connect timeout plus the read timeout
+    pub collection_timeout_secs: u64,
+    /// Routers scraped at the same time by the collection loop; the rest
+    /// wait for a free slot
+    pub max_concurrent_scrapes: usize,
+    /// Upper bound of the random delay before each router's scrape, so
+    /// routers aren't all hit at the same instant (0 disables it)
+    pub scrape_splay_secs: u64,
+    /// How recent a WireGuard handshake must be for the peer to count as active
+    pub wireguard_active_window_secs: u64,
+    /// Recent scrapes per router the duration quantiles are computed over (0
+    /// disables them)
+    pub scrape_duration_window: usize,
+    /// Value of the `env` label added to every series, if set
+    pub environment: Option<String>,
+    /// Whether `/health` answers 503 while any router is degraded
+    pub health_fail_on_degraded: bool,
+    /// Collect every router once, report the result and exit instead of
+    /// serving metrics
+    pub validate: bool,
+    /// HTTP endpoint returning additional routers as JSON, in the same format
+    /// as `ROUTERS_CONFIG`
+    pub sd_url: Option<String>,
+    /// How often the service discovery endpoint is polled
+    pub sd_refresh_interval_secs: u64,
+    /// Graphite carbon `host:port` every collected snapshot is pushed to
+    pub graphite_addr: Option<String>,
+    /// Bearer token accepted by `POST /reload`; without it and without
+    /// metrics basic auth the endpoint is disabled
+    pub reload_token: Option<SecretString>,
+    /// Basic auth user required by `/metrics`, `/probe` and `/api`; auth is
+    /// disabled unless both the user and the password are set
+    pub metrics_auth_user: Option<String>,
+    /// Basic auth password paired with `metrics_auth_user`
+    pub metrics_auth_password: Option<SecretString>,
+    /// Collectors allowed to run on any router, overriding router modules
+    /// (no restriction when unset)
+    pub only_collectors: Option<HashSet<Collector>>,
+    /// User-defined collectors exporting fields of arbitrary print commands
+    pub custom_collectors: Vec<CustomCollector>,
+    /// How interface counters are derived from the router's values
+    pub counter_mode: CounterMode,
+    /// Prefix of every exported metric name, `mikrotik` by default
+    pub metric_prefix: String,
 }
 
 impl Default for Config {
@@ -86,6 +343,30 @@ impl Default for Config {
             server_addr: defaults::SERVER_ADDR.to_string(),
             routers: vec![],
             collection_interval_secs: 30,
+            metrics_cache_ttl_secs: 0,
+            modules: CollectorModules::new(),
+            include_ids: false,
+            circuit_breaker_open_secs: 3600,
+            connect_timeout_secs: 5,
+            read_timeout_secs: 30,
+            collection_timeout_secs: 35,
+            max_concurrent_scrapes: 16,
+            scrape_splay_secs: 0,
+            wireguard_active_window_secs: 180,
+            scrape_duration_window: 100,
+            environment: None,
+            health_fail_on_degraded: true,
+            validate: false,
+            sd_url: None,
+            sd_refresh_interval_secs: 60,
+            graphite_addr: None,
+            reload_token: None,
+            metrics_auth_user: None,
+            metrics_auth_password: None,
+            only_collectors: None,
+            custom_collectors: Vec::new(),
+            counter_mode: CounterMode::Delta,
+            metric_prefix: DEFAULT_METRIC_PREFIX.to_string(),
         }
     }
 }
@@ -94,9 +375,18 @@ impl Config {
     /// Loads configuration from environment variables
     ///
     /// Expects `dotenvy::dotenv()` to have been called by the application entry point.
-    pub fn from_env() -> Self {
-        let server_addr = std::env::var(env_vars::SERVER_ADDR)
-            .unwrap_or_else(|_| defaults::SERVER_ADDR.to_string());
+    /// When `CONFIG_FILE` is set, the configuration is loaded with
+    /// [`Config::from_file`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AppError::Config`] if two routers share a name, if only one
+    /// of `METRICS_AUTH_USER` and `METRICS_AUTH_PASSWORD` is set, or if
+    /// `CONFIG_FILE` can't be loaded.
+    pub fn from_env() -> crate::Result<Self> {
+        if let Ok(path) = std::env::var(env_vars::CONFIG_FILE) {
+            return Self::from_file(&path);
+        }
 
         // Load routers configuration from JSON
         let routers = if let Ok(config_json) = std::env::var(env_vars::ROUTERS_CONFIG) {
@@ -119,6 +409,7 @@ impl Config {
                     address: addr,
                     username,
                     password: password_secret,
+                    ..RouterConfig::default()
                 }]
             } else {
                 tracing::warn!(
@@ -128,14 +419,297 @@ impl Config {
             }
         };
 
+        Self::settings_from_env().with_routers(routers)
+    }
+
+    /// Loads server address, collection interval and routers from a TOML file
+    ///
+    /// Settings the file doesn't cover are read from the environment as in
+    /// [`Config::from_env`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AppError::Config`] if the file can't be read, has an
+    /// unsupported extension, doesn't parse or two routers share a name.
+    pub fn from_file(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Config(format!("cannot read {}: {e}", path.display())))?;
+        let is_toml = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if !is_toml {
+            return Err(AppError::Config(format!(
+                "{}: expected a .toml file",
+                path.display()
+            )));
+        }
+        let file: FileConfig = toml::from_str(&contents)
+            .map_err(|e| AppError::Config(format!("{}: {e}", path.display())))?;
+
+        let mut config = Self::settings_from_env();
+        if let Some(server_addr) = file.server_addr {
+            config.server_addr = server_addr;
+        }
+        if let Some(interval) = file.collection_interval_secs {
+            config.collection_interval_secs = interval;
+        }
+        if let Some(splay) = file.scrape_splay_secs {
+            config.scrape_splay_secs = splay;
+        }
+        if let Some(counter_mode) = file.counter_mode {
+            config.counter_mode = counter_mode;
+        }
+        if let Some(metric_prefix) = file.metric_prefix {
+            config.metric_prefix = metric_prefix;
+        }
+        if let Some(custom_collectors) = file.custom_collectors {
+            config.custom_collectors = valid_custom_collectors(custom_collectors);
+        }
+        config.with_routers(file.routers)
+    }
+
+    /// Reads every setting except the routers from the environment
+    fn settings_from_env() -> Self {
+        let server_addr = std::env::var(env_vars::SERVER_ADDR)
+            .unwrap_or_else(|_| defaults::SERVER_ADDR.to_string());
+
         let collection_interval_secs = std::env::var("COLLECTION_INTERVAL_SECONDS")
             .ok()
             .and_then(|v| v.parse::<u64>().ok())
             .unwrap_or(30);
 
+        let metrics_cache_ttl_secs = std::env::var(env_vars::METRICS_CACHE_TTL_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        // Load named collector modules from JSON
+        let modules: CollectorModules = std::env::var(env_vars::COLLECTOR_MODULES)
+            .ok()
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| {
+                        tracing::warn!("Failed to parse COLLECTOR_MODULES: {}. Ignoring.", e);
+                    })
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        let include_ids = std::env::var(env_vars::INCLUDE_IDS)
+            .ok()
+            .is_some_and(|v| parse_flag(&v));
+
+        let circuit_breaker_open_secs = std::env::var(env_vars::CIRCUIT_BREAKER_OPEN_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(3600);
+
+        let connect_timeout_secs = std::env::var(env_vars::CONNECT_TIMEOUT_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(5);
+
+        let read_timeout_secs = std::env::var(env_vars::READ_TIMEOUT_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(30);
+
+        let collection_timeout_secs = std::env::var(env_vars::COLLECTION_TIMEOUT_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(connect_timeout_secs + read_timeout_secs);
+
+        let max_concurrent_scrapes = std::env::var(env_vars::MAX_CONCURRENT_SCRAPES)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(16);
+
+        let scrape_splay_secs = std::env::var(env_vars::SCRAPE_SPLAY_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let wireguard_active_window_secs = std::env::var(env_vars::WIREGUARD_ACTIVE_WINDOW_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(180);
+
+        let scrape_duration_window = std::env::var(env_vars::SCRAPE_DURATION_WINDOW)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(100);
+
+        let environment = std::env::var(env_vars::ENVIRONMENT)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let health_fail_on_degraded = std::env::var(env_vars::HEALTH_FAIL_ON_DEGRADED)
+            .ok()
+            .is_none_or(|v| parse_flag(&v));
+
+        let validate = std::env::var(env_vars::VALIDATE)
+            .ok()
+            .is_some_and(|v| parse_flag(&v));
+
+        let sd_url = std::env::var(env_vars::SD_URL)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let sd_refresh_interval_secs = std::env::var(env_vars::SD_REFRESH_INTERVAL_SECONDS)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(60);
+
+        let graphite_addr = std::env::var(env_vars::GRAPHITE_ADDR)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let reload_token = std::env::var(env_vars::RELOAD_TOKEN)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(SecretString::from);
+
+        let metrics_auth_user = std::env::var(env_vars::METRICS_AUTH_USER)
+            .ok()
+            .filter(|v| !v.is_empty());
+        let metrics_auth_password = std::env::var(env_vars::METRICS_AUTH_PASSWORD)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(SecretString::from);
+
+        let only_collectors = std::env::var(env_vars::ONLY_COLLECTORS)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| parse_collector_list(&v));
+
+        // Load custom collector definitions from JSON
+        let custom_collectors = std::env::var(env_vars::CUSTOM_COLLECTORS)
+            .ok()
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| {
+                        tracing::warn!("Failed to parse CUSTOM_COLLECTORS: {}. Ignoring.", e);
+                    })
+                    .ok()
+            })
+            .map(valid_custom_collectors)
+            .unwrap_or_default();
+
+        let counter_mode = std::env::var(env_vars::COUNTER_MODE)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .and_then(|v| {
+                let mode = CounterMode::from_name(&v);
+                if mode.is_none() {
+                    tracing::warn!("Unknown COUNTER_MODE '{}'. Using delta.", v);
+                }
+                mode
+            })
+            .unwrap_or_default();
+
+        let metric_prefix = std::env::var(env_vars::METRIC_PREFIX)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| DEFAULT_METRIC_PREFIX.to_string());
+
+        Config {
+            server_addr,
+            routers: vec![],
+            collection_interval_secs,
+            metrics_cache_ttl_secs,
+            modules,
+            include_ids,
+            circuit_breaker_open_secs,
+            connect_timeout_secs,
+            read_timeout_secs,
+            collection_timeout_secs,
+            max_concurrent_scrapes,
+            scrape_splay_secs,
+            wireguard_active_window_secs,
+            scrape_duration_window,
+            environment,
+            health_fail_on_degraded,
+            validate,
+            sd_url,
+            sd_refresh_interval_secs,
+            graphite_addr,
+            reload_token,
+            metrics_auth_user,
+            metrics_auth_password,
+            only_collectors,
+            custom_collectors,
+            counter_mode,
+            metric_prefix,
+        }
+    }
+
+    /// Checks the metric prefix, then prepares and sets the configured routers
+    ///
+    /// Unlike routers from service discovery, statically configured routers
+    /// must have unique names: they key the delta state of every counter.
+    fn with_routers(mut self, routers: Vec<RouterConfig>) -> crate::Result<Self> {
+        check_metric_prefix(&self.metric_prefix)?;
+        check_metrics_auth(
+            self.metrics_auth_user.as_deref(),
+            self.metrics_auth_password.as_ref(),
+        )?;
+        check_unique_names(&routers)?;
+        self.routers = self.prepare_routers(routers);
+
+        if self.routers.is_empty() && self.sd_url.is_none() {
+            tracing::warn!(
+                "No valid router configuration found. Service will start but /metrics will be empty."
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// Restricts every router to the given collectors, overriding their modules
+    #[must_use]
+    pub fn with_only_collectors(mut self, collectors: HashSet<Collector>) -> Self {
+        for router in &mut self.routers {
+            router.collectors.retain(|c| collectors.contains(c));
+        }
+        self.only_collectors = Some(collectors);
+        self
+    }
+
+    /// Resolves collector modules, validates and de-duplicates routers
+    ///
+    /// Invalid routers and routers whose name is already taken are dropped
+    /// with an error log; dependencies on routers not in the list are ignored.
+    #[must_use]
+    pub fn prepare_routers(&self, routers: Vec<RouterConfig>) -> Vec<RouterConfig> {
         // Validate and filter router configurations
         let routers: Vec<RouterConfig> = routers
             .into_iter()
+            .filter_map(|mut router| match router.apply_module(&self.modules) {
+                Ok(()) => {
+                    router.include_ids = self.include_ids;
+                    router.custom_collectors.clone_from(&self.custom_collectors);
+                    if let Some(only) = &self.only_collectors {
+                        router.collectors.retain(|c| only.contains(c));
+                    }
+                    Some(router)
+                }
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    tracing::warn!("Skipping invalid router: {}", router.name);
+                    None
+                }
+            })
             .filter(|router| match router.validate() {
                 Ok(()) => true,
                 Err(e) => {
@@ -148,7 +722,7 @@ impl Config {
 
         // Check for duplicate router names
         let mut seen_names = std::collections::HashSet::new();
-        let routers: Vec<RouterConfig> = routers
+        let mut routers: Vec<RouterConfig> = routers
             .into_iter()
             .filter(|router| {
                 if seen_names.contains(&router.name) {
@@ -164,16 +738,72 @@ impl Config {
             })
             .collect();
 
-        if routers.is_empty() {
-            tracing::warn!(
-                "No valid router configuration found. Service will start but /metrics will be empty."
-            );
+        // Drop dependencies on routers that are not configured
+        for router in &mut routers {
+            if let Some(dependency) = &router.depends_on {
+                if dependency == &router.name || !seen_names.contains(dependency) {
+                    tracing::warn!(
+                        "Router '{}' depends on unknown router '{}'; ignoring dependency",
+                        router.name,
+                        dependency
+                    );
+                    router.depends_on = None;
+                }
+            }
         }
 
-        Config {
-            server_addr,
-            routers,
-            collection_interval_secs,
-        }
+        routers
+    }
+}
+
+/// Fails unless `prefix` is a valid Prometheus metric name
+///
+/// Colons are rejected too: Prometheus reserves them for recording rules.
+fn check_metric_prefix(prefix: &str) -> crate::Result<()> {
+    if is_identifier(prefix) {
+        return Ok(());
+    }
+    Err(AppError::Config(format!(
+        "invalid metric prefix '{prefix}': use letters, digits and '_', not starting with a digit"
+    )))
+}
+
+/// Fails if only one of the basic auth user and password is set
+///
+/// A half-configured pair is an error rather than a warning, so a typo can't
+/// leave `/metrics` open.
+fn check_metrics_auth(user: Option<&str>, password: Option<&SecretString>) -> crate::Result<()> {
+    if user.is_some() == password.is_some() {
+        return Ok(());
     }
+    Err(AppError::Config(
+        "METRICS_AUTH_USER and METRICS_AUTH_PASSWORD must be set together".to_string(),
+    ))
+}
+
+/// Fails if two routers share a name, listing every name that does
+fn check_unique_names(routers: &[RouterConfig]) -> crate::Result<()> {
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<&str> = routers
+        .iter()
+        .filter(|router| !seen.insert(router.name.as_str()))
+        .map(|router| router.name.as_str())
+        .collect();
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    duplicates.sort_unstable();
+    duplicates.dedup();
+    Err(AppError::Config(format!(
+        "duplicate router name(s): {}; router names must be unique",
+        duplicates.join(", ")
+    )))
+}
+
+/// Interprets a boolean environment flag (`1`, `true`, `yes`, `on`)
+fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
 }
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! CIDR prefixes used to filter per-address metrics
+
+use serde::Deserialize;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IPv4 or IPv6 network such as `10.0.0.0/8` or `2001:db8::/32`
+///
+/// A bare address is treated as a single-host prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct IpPrefix {
+    network: IpAddr,
+    len: u8,
+}
+
+impl IpPrefix {
+    /// Returns whether `addr` belongs to this prefix
+    #[must_use]
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.network, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.len)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpPrefix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, len) = s.trim().split_once('/').unwrap_or((s.trim(), ""));
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid prefix '{s}': bad address"))?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let len = if len.is_empty() {
+            max_len
+        } else {
+            len.parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("Invalid prefix '{s}': bad length"))?
+        };
+        Ok(Self { network, len })
+    }
+}
+
+impl TryFrom<String> for IpPrefix {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ipv4_prefix_contains() {
+        let prefix: IpPrefix = "10.0.0.0/8".parse().unwrap();
+        assert!(prefix.contains(ip("10.20.30.40")));
+        assert!(!prefix.contains(ip("11.0.0.1")));
+        assert!(!prefix.contains(ip("::1")));
+    }
+
+    #[test]
+    fn test_ipv6_prefix_contains() {
+        let prefix: IpPrefix = "2001:db8::/32".parse().unwrap();
+        assert!(prefix.contains(ip("2001:db8:1::5")));
+        assert!(!prefix.contains(ip("2001:db9::1")));
+    }
+
+    #[test]
+    fn test_zero_length_and_host_prefixes() {
+        let any: IpPrefix = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(ip("203.0.113.7")));
+
+        let host: IpPrefix = "192.168.1.1".parse().unwrap();
+        assert_eq!(host.to_string(), "192.168.1.1/32");
+        assert!(host.contains(ip("192.168.1.1")));
+        assert!(!host.contains(ip("192.168.1.2")));
+    }
+
+    #[test]
+    fn test_invalid_prefixes() {
+        assert!("10.0.0.0/33".parse::<IpPrefix>().is_err());
+        assert!("not-an-ip/8".parse::<IpPrefix>().is_err());
+    }
+}
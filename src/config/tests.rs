@@ -13,7 +13,69 @@ mod test {
         let config = Config::default();
         assert_eq!(config.server_addr, "0.0.0.0:9090");
         assert_eq!(config.collection_interval_secs, 30);
+        assert_eq!(config.metrics_cache_ttl_secs, 0);
+        assert_eq!(config.circuit_breaker_open_secs, 3600);
+        assert_eq!(config.connect_timeout_secs, 5);
+        assert_eq!(config.read_timeout_secs, 30);
+        assert_eq!(config.collection_timeout_secs, 35);
+        assert_eq!(config.max_concurrent_scrapes, 16);
+        assert_eq!(config.scrape_splay_secs, 0);
+        assert_eq!(config.wireguard_active_window_secs, 180);
+        assert_eq!(config.scrape_duration_window, 100);
+        assert!(!config.validate);
+        assert!(config.environment.is_none());
+        assert!(config.health_fail_on_degraded);
         assert!(config.routers.is_empty());
+        assert!(config.sd_url.is_none());
+        assert_eq!(config.sd_refresh_interval_secs, 60);
+        assert!(config.graphite_addr.is_none());
+        assert!(config.reload_token.is_none());
+        assert!(config.metrics_auth_user.is_none());
+        assert!(config.metrics_auth_password.is_none());
+        assert!(config.only_collectors.is_none());
+        assert!(config.custom_collectors.is_empty());
+        assert_eq!(config.counter_mode, CounterMode::Delta);
+        assert_eq!(config.metric_prefix, "mikrotik");
+    }
+
+    #[test]
+    fn test_only_collectors_override_modules() {
+        let config = Config {
+            modules: serde_json::from_str(r#"{"edge": ["system", "interfaces", "routes"]}"#)
+                .unwrap(),
+            ..Default::default()
+        };
+        let routers = serde_json::from_str(
+            r#"[
+                {"name": "r1", "address": "10.0.0.1:8728", "username": "admin", "password": "a"},
+                {"name": "r2", "address": "10.0.0.2:8728", "username": "admin", "password": "b",
+                 "module": "edge"}
+            ]"#,
+        )
+        .unwrap();
+        let mut config = Config {
+            routers: config.prepare_routers(routers),
+            ..config
+        }
+        .with_only_collectors([Collector::System].into_iter().collect());
+
+        for router in &config.routers {
+            assert_eq!(
+                router.collectors,
+                [Collector::System].into_iter().collect(),
+                "{}",
+                router.name
+            );
+        }
+
+        // Routers prepared later, e.g. discovered ones, are restricted as well
+        config.routers = config.prepare_routers(config.routers.clone());
+        assert!(
+            config
+                .routers
+                .iter()
+                .all(|r| r.collectors.len() == 1 && r.collector_enabled(Collector::System))
+        );
     }
 
     #[test]
@@ -30,6 +92,46 @@ mod test {
         assert_eq!(router.address, "192.168.1.1:8728");
         assert_eq!(router.username, "admin");
         assert_eq!(router.password.expose_secret(), "secret");
+        assert!(router.conntrack_prefixes.is_empty());
+        assert!(router.ping_targets.is_empty());
+    }
+
+    #[test]
+    fn test_router_config_debug_redacts_password() {
+        let json = r#"{
+            "name": "test-router",
+            "address": "192.168.1.1:8728",
+            "username": "admin",
+            "password": "hunter2"
+        }"#;
+
+        let router: RouterConfig = serde_json::from_str(json).unwrap();
+        let config = Config {
+            routers: vec![router.clone()],
+            ..Config::default()
+        };
+        for debug in [format!("{router:?}"), format!("{config:?}")] {
+            assert!(!debug.contains("hunter2"), "{debug}");
+            assert!(debug.contains("REDACTED"), "{debug}");
+        }
+    }
+
+    #[test]
+    fn test_router_config_conntrack_prefixes() {
+        let json = r#"{
+            "name": "edge",
+            "address": "192.168.1.1:8728",
+            "username": "admin",
+            "password": "secret",
+            "conntrack_prefixes": ["10.0.0.0/8", "2001:db8::/32"]
+        }"#;
+
+        let router: RouterConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(router.conntrack_prefixes.len(), 2);
+        assert_eq!(router.conntrack_prefixes[0].to_string(), "10.0.0.0/8");
+
+        let invalid = json.replace("10.0.0.0/8", "10.0.0.0/40");
+        assert!(serde_json::from_str::<RouterConfig>(&invalid).is_err());
     }
 
     #[test]
@@ -62,6 +164,7 @@ mod test {
             address: "192.168.1.1:8728".to_string(),
             username: "admin".to_string(),
             password: secrecy::SecretString::new("password".to_string().into()),
+            ..Default::default()
         };
 
         assert!(config.validate().is_ok());
@@ -74,6 +177,7 @@ mod test {
             address: "192.168.1.1:8728".to_string(),
             username: "admin".to_string(),
             password: "password".to_string().into(),
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -88,6 +192,7 @@ mod test {
             address: "192.168.1.1".to_string(), // Missing port
             username: "admin".to_string(),
             password: "password".to_string().into(),
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -102,10 +207,338 @@ mod test {
             address: "192.168.1.1:8728".to_string(),
             username: "  ".to_string(),
             password: "password".to_string().into(),
+            ..Default::default()
         };
 
         let result = config.validate();
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Username cannot be empty"));
     }
+
+    #[test]
+    fn test_router_without_module_enables_all_collectors() {
+        let mut router: RouterConfig = serde_json::from_str(
+            r#"{"name": "r1", "address": "192.168.1.1:8728", "username": "admin", "password": "x"}"#,
+        )
+        .unwrap();
+
+        router.apply_module(&CollectorModules::new()).unwrap();
+        for collector in Collector::ALL {
+            assert!(router.collector_enabled(collector));
+        }
+    }
+
+    #[test]
+    fn test_router_module_limits_collectors() {
+        let modules: CollectorModules =
+            serde_json::from_str(r#"{"edge": ["system", "interfaces"]}"#).unwrap();
+        let mut router: RouterConfig = serde_json::from_str(
+            r#"{"name": "r1", "address": "192.168.1.1:8728", "username": "admin", "password": "x", "module": "edge"}"#,
+        )
+        .unwrap();
+
+        router.apply_module(&modules).unwrap();
+        assert!(router.collector_enabled(Collector::System));
+        assert!(router.collector_enabled(Collector::Interfaces));
+        assert!(!router.collector_enabled(Collector::Conntrack));
+        assert!(!router.collector_enabled(Collector::Wireguard));
+    }
+
+    #[test]
+    fn test_router_unknown_module_rejected() {
+        let mut router = RouterConfig {
+            name: "r1".to_string(),
+            module: Some("missing".to_string()),
+            ..Default::default()
+        };
+
+        let result = router.apply_module(&CollectorModules::new());
+        assert!(
+            result
+                .unwrap_err()
+                .contains("Unknown collector module 'missing'")
+        );
+    }
+
+    #[test]
+    fn test_counter_mode_from_name() {
+        assert_eq!(CounterMode::from_name("delta"), Some(CounterMode::Delta));
+        assert_eq!(
+            CounterMode::from_name(" Absolute "),
+            Some(CounterMode::Absolute)
+        );
+        assert_eq!(CounterMode::from_name("native"), None);
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        for value in ["1", "true", "TRUE", " yes ", "on"] {
+            assert!(parse_flag(value), "{value}");
+        }
+        for value in ["", "0", "false", "off", "maybe"] {
+            assert!(!parse_flag(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_router_command_paths_deserialize_and_validate() {
+        let json = r#"{
+            "name": "custom",
+            "address": "192.168.1.1:8728",
+            "username": "admin",
+            "password": "secret",
+            "command_paths": {"/interface/print": "/interface/ethernet/print"}
+        }"#;
+
+        let mut router: RouterConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            router.command_paths["/interface/print"],
+            "/interface/ethernet/print"
+        );
+        assert!(router.validate().is_ok());
+
+        router
+            .command_paths
+            .insert("/ip/route/print".to_string(), "ip/route/print".to_string());
+        assert!(router.validate().is_err());
+    }
+
+    #[test]
+    fn test_router_tls_deserialize() {
+        let json = r#"{
+            "name": "secure",
+            "address": "192.168.1.1:8729",
+            "username": "admin",
+            "password": "secret",
+            "tls": true,
+            "tls_skip_verify": true
+        }"#;
+
+        let router: RouterConfig = serde_json::from_str(json).unwrap();
+        assert!(router.tls);
+        assert!(router.tls_skip_verify);
+        assert!(router.validate().is_ok());
+        assert!(!RouterConfig::default().tls_skip_verify);
+    }
+
+    #[test]
+    fn test_prepare_routers_drops_invalid_and_duplicates() {
+        let json = r#"[
+            {"name": "r1", "address": "10.0.0.1:8728", "username": "admin", "password": "a"},
+            {"name": "r1", "address": "10.0.0.2:8728", "username": "admin", "password": "b"},
+            {"name": "r2", "address": "10.0.0.3", "username": "admin", "password": "c"},
+            {"name": "r3", "address": "10.0.0.4:8728", "username": "admin", "password": "d",
+             "depends_on": "missing"}
+        ]"#;
+        let config = Config {
+            include_ids: true,
+            ..Default::default()
+        };
+
+        let routers = config.prepare_routers(serde_json::from_str(json).unwrap());
+
+        let names: Vec<&str> = routers.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["r1", "r3"]);
+        assert_eq!(routers[0].address, "10.0.0.1:8728");
+        assert!(routers[1].depends_on.is_none());
+        assert!(routers.iter().all(|r| r.include_ids));
+    }
+
+    #[test]
+    fn test_include_ids_off_by_default() {
+        assert!(!Config::default().include_ids);
+        assert!(!RouterConfig::default().include_ids);
+    }
+
+    /// Writes `contents` to a file with the given name in a fresh temp directory
+    fn write_config_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mikrotik-exporter-config-{}-{name}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_file_custom_collectors() {
+        let path = write_config_file(
+            "custom.toml",
+            r#"
+server_addr = "127.0.0.1:9100"
+collection_interval_secs = 15
+
+[[routers]]
+name = "core"
+address = "10.0.0.1:8728"
+username = "monitor"
+password = "s3cret"
+wireguard_peer_key = "public-key"
+
+[[custom_collectors]]
+name = "graphing_interfaces"
+command = "/tool/graphing/interface/print"
+value = "store-on-disk"
+labels = ["interface"]
+"#,
+        );
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.server_addr, "127.0.0.1:9100");
+        assert_eq!(config.collection_interval_secs, 15);
+        assert_eq!(config.routers.len(), 1);
+        assert_eq!(config.routers[0].username, "monitor");
+        assert_eq!(config.routers[0].password.expose_secret(), "s3cret");
+        assert_eq!(
+            config.routers[0].wireguard_peer_key,
+            WireGuardPeerKey::PublicKey
+        );
+        // Custom collectors are handed to every router
+        assert_eq!(config.custom_collectors.len(), 1);
+        assert_eq!(
+            config.routers[0].custom_collectors,
+            config.custom_collectors
+        );
+    }
+
+    #[test]
+    fn test_from_file_toml() {
+        let path = write_config_file(
+            "config.toml",
+            r#"
+collection_interval_secs = 60
+scrape_splay_secs = 5
+counter_mode = "absolute"
+
+[[routers]]
+name = "edge"
+address = "10.0.0.2:8728"
+username = "admin"
+password = "pw"
+ping_targets = ["192.0.2.1"]
+
+[[routers]]
+name = "broken"
+address = "10.0.0.3"
+username = "admin"
+password = "pw"
+"#,
+        );
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.collection_interval_secs, 60);
+        assert_eq!(config.scrape_splay_secs, 5);
+        assert_eq!(config.counter_mode, CounterMode::Absolute);
+        // Invalid routers are dropped just like with ROUTERS_CONFIG
+        assert_eq!(config.routers.len(), 1);
+        assert_eq!(config.routers[0].name, "edge");
+        assert_eq!(config.routers[0].ping_targets, ["192.0.2.1"]);
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_extension_and_bad_syntax() {
+        for name in ["config.json", "config.yaml"] {
+            let unsupported = write_config_file(name, "{}");
+            let err = Config::from_file(&unsupported).unwrap_err();
+            assert!(err.to_string().contains(".toml"), "{err}");
+        }
+
+        let broken = write_config_file("broken.toml", "routers = [");
+        assert!(matches!(
+            Config::from_file(&broken),
+            Err(AppError::Config(_))
+        ));
+
+        assert!(Config::from_file("/nonexistent/config.toml").is_err());
+    }
+
+    fn named_router(name: &str) -> RouterConfig {
+        RouterConfig {
+            name: name.to_string(),
+            address: "10.0.0.1:8728".to_string(),
+            username: "admin".to_string(),
+            password: "pw".to_string().into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_duplicate_router_names_are_rejected() {
+        let routers = vec![
+            named_router("core"),
+            named_router("edge"),
+            named_router("core"),
+            named_router("edge"),
+            named_router("core"),
+        ];
+        let err = Config::default().with_routers(routers).unwrap_err();
+        assert!(matches!(err, AppError::Config(_)));
+        assert!(err.to_string().contains("core, edge"), "{err}");
+
+        let path = write_config_file(
+            "duplicates.toml",
+            r#"
+routers = [
+  { name = "core", address = "10.0.0.1:8728", username = "a", password = "b" },
+  { name = "core", address = "10.0.0.2:8728", username = "a", password = "b" },
+]
+"#,
+        );
+        let err = Config::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("core"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_metric_prefix_is_rejected() {
+        for prefix in ["", "2fast", "edge-exporter", "edge:exporter"] {
+            let config = Config {
+                metric_prefix: prefix.to_string(),
+                ..Config::default()
+            };
+            assert!(
+                matches!(config.with_routers(Vec::new()), Err(AppError::Config(_))),
+                "{prefix:?}"
+            );
+        }
+
+        let path = write_config_file("prefix.toml", "metric_prefix = \"edge_exporter\"\n");
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.metric_prefix, "edge_exporter");
+    }
+
+    #[test]
+    fn test_half_configured_metrics_auth_is_rejected() {
+        let user_only = Config {
+            metrics_auth_user: Some("prometheus".to_string()),
+            ..Config::default()
+        };
+        let err = user_only.with_routers(Vec::new()).unwrap_err();
+        assert!(matches!(err, AppError::Config(_)));
+        assert!(err.to_string().contains("METRICS_AUTH_PASSWORD"), "{err}");
+
+        let password_only = Config {
+            metrics_auth_password: Some("secret".to_string().into()),
+            ..Config::default()
+        };
+        assert!(matches!(
+            password_only.with_routers(Vec::new()),
+            Err(AppError::Config(_))
+        ));
+
+        let both = Config {
+            metrics_auth_user: Some("prometheus".to_string()),
+            metrics_auth_password: Some("secret".to_string().into()),
+            ..Config::default()
+        };
+        assert!(both.with_routers(Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_unique_router_names_are_accepted() {
+        let routers = vec![named_router("core"), named_router("edge")];
+        let config = Config::default().with_routers(routers).unwrap();
+        assert_eq!(config.routers.len(), 2);
+    }
 }
@@ -27,7 +27,7 @@ pub mod prelude;
 
 // Re-export commonly used types
 /// Application configuration
-pub use config::{Config, RouterConfig};
+pub use config::{Collector, Config, RouterConfig, WireGuardPeerKey, parse_collector_list};
 
 /// Application error and result type
 pub use error::{AppError, Result};
@@ -35,16 +35,23 @@ pub use error::{AppError, Result};
 /// HTTP API router and state
 pub use api::{AppState, create_router};
 
-/// Metrics collection loop
-pub use collector::start_collection_loop;
+/// Metrics collection loop, on-demand probes, configuration reload and
+/// connectivity validation
+pub use collector::{
+    ConfigReloader, RouterValidation, probe_router, start_collection_loop, validate_routers,
+};
 
 /// Metrics registry and labels
 pub use metrics::{MetricsRegistry, RouterLabels};
 
 /// MikroTik connection pool and metric input types
 pub use mikrotik::{
-    ConnectionPool, ConnectionTrackingStats, InterfaceStats, RouterMetrics, SystemResource,
-    WireGuardInterfaceStats, WireGuardPeerStats,
+    BondSlaveStats, BondStats, CircuitState, ClockStats, ConnectionPool, ConnectionTrackingStats,
+    ConntrackTableStats, ContainerStats, CpuCoreStats, DefaultRouteStats, DhcpLeaseStats,
+    FirewallRuleStats, HealthSensor, HealthStats, InterfaceQueueStats, InterfaceStats,
+    IpServiceStats, LicenseStats, LoginMethod, ManagedDeviceStats, PingStats, PppSessionStats,
+    QueueStats, RouterMetrics, SystemResource, TunnelStats, UnexpectedReboot, VrrpStats,
+    WifiChannelStats, WireGuardInterfaceStats, WireGuardPeerStats,
 };
 
 /// RouterOS wire protocol length encoding (public for tests)
@@ -7,16 +7,20 @@
 //! - Loads environment variables
 //! - Sets up logging
 //! - Reads router configuration
+//! - In validate mode, checks every router once and exits
 //! - Starts background metrics collection
+//! - Reloads the configured routers on SIGHUP
 //! - Waits for shutdown signal
 //! - Runs HTTP server for Prometheus
 
 use mikrotik_exporter::{
-    AppState, Config, ConnectionPool, MetricsRegistry, Result, create_router, start_collection_loop,
+    AppState, Config, ConnectionPool, MetricsRegistry, Result, create_router, parse_collector_list,
+    start_collection_loop, validate_routers,
 };
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use tokio::sync::watch;
@@ -30,7 +34,10 @@ async fn main() -> Result<()> {
     setup_tracing();
 
     // Initialize configuration before creating Tokio runtime
-    let config = Config::from_env();
+    let mut config = Config::from_env()?;
+    if let Some(only) = only_flag() {
+        config = config.with_only_collectors(parse_collector_list(&only));
+    }
 
     // Log configuration info
     tracing::info!(
@@ -40,19 +47,36 @@ async fn main() -> Result<()> {
     for router in &config.routers {
         tracing::info!("  - Router '{}' at {}", router.name, router.address);
     }
+    if let Some(only) = &config.only_collectors {
+        let mut names: Vec<&str> = only.iter().map(|c| c.name()).collect();
+        names.sort_unstable();
+        tracing::info!("Collectors restricted to: {}", names.join(", "));
+    }
+
+    if config.validate || std::env::args().skip(1).any(|arg| arg == "--validate") {
+        return validate(&config).await;
+    }
 
     // Create metrics registry
-    let metrics = MetricsRegistry::new();
+    let metrics =
+        MetricsRegistry::with_prefix(&config.metric_prefix, config.environment.as_deref())
+            .with_wireguard_active_window(Duration::from_secs(config.wireguard_active_window_secs))
+            .with_scrape_duration_window(config.scrape_duration_window)
+            .with_counter_mode(config.counter_mode);
 
     // Create shared connection pool
-    let pool = Arc::new(ConnectionPool::new());
+    let pool = Arc::new(
+        ConnectionPool::new()
+            .with_circuit_open_duration(Duration::from_secs(config.circuit_breaker_open_secs))
+            .with_timeouts(
+                Duration::from_secs(config.connect_timeout_secs),
+                Duration::from_secs(config.read_timeout_secs),
+            )
+            .with_collection_timeout(Duration::from_secs(config.collection_timeout_secs)),
+    );
 
     // Create application state
-    let state = Arc::new(AppState {
-        config: config.clone(),
-        metrics: metrics.clone(),
-        pool: pool.clone(),
-    });
+    let state = Arc::new(AppState::new(config.clone(), metrics.clone(), pool.clone()));
 
     // Graceful shutdown channel
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
@@ -99,8 +123,39 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Reload the configured routers on SIGHUP, like POST /reload
+    #[cfg(unix)]
+    tokio::spawn({
+        let reloader = state.reloader.clone();
+        async move {
+            use tokio::signal::unix::{SignalKind, signal};
+
+            let mut sighup = match signal(SignalKind::hangup()) {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!("Failed to create SIGHUP handler: {}", e);
+                    return;
+                }
+            };
+            while sighup.recv().await.is_some() {
+                tracing::info!("Reload signal received (SIGHUP)");
+                if let Err(e) = reloader.reload().await {
+                    tracing::error!("Configuration reload failed: {}", e);
+                }
+            }
+        }
+    });
+
     // Start periodic background metrics collection
-    start_collection_loop(shutdown_rx.clone(), Arc::new(config.clone()), metrics, pool);
+    start_collection_loop(
+        shutdown_rx.clone(),
+        Arc::new(config.clone()),
+        metrics,
+        pool,
+        &state.reloader,
+        state.snapshots.clone(),
+        state.probe_metrics.clone(),
+    );
 
     // Create the router
     let app = create_router(state);
@@ -120,6 +175,8 @@ async fn main() -> Result<()> {
     tracing::info!("Endpoints:");
     tracing::info!("  - GET /health  - Health check");
     tracing::info!("  - GET /metrics - Prometheus metrics");
+    tracing::info!("  - GET /probe   - On-demand scrape of one router");
+    tracing::info!("  - POST /reload - Reload configured routers");
 
     // Start server with graceful shutdown
     axum::serve(listener, app)
@@ -136,6 +193,48 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Collects every router once, prints an OK/FAIL line per router and exits
+/// with status 1 if any of them failed
+async fn validate(config: &Config) -> Result<()> {
+    let pool = Arc::new(
+        ConnectionPool::new()
+            .with_timeouts(
+                Duration::from_secs(config.connect_timeout_secs),
+                Duration::from_secs(config.read_timeout_secs),
+            )
+            .with_collection_timeout(Duration::from_secs(config.collection_timeout_secs)),
+    );
+    let results = validate_routers(config, pool).await;
+    for result in &results {
+        println!("{result}");
+    }
+
+    let failed = results.iter().filter(|r| !r.is_ok()).count();
+    println!(
+        "{} of {} router(s) OK",
+        results.len() - failed,
+        results.len()
+    );
+    if failed > 0 || results.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Value of `--only <collectors>` or `--only=<collectors>`, if given
+fn only_flag() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--only" {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix("--only=") {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
 fn setup_tracing() {
     // Use EnvFilter::from_default_env() for proper RUST_LOG handling
     // If RUST_LOG is not set, use "info" by default
@@ -3,7 +3,7 @@
 
 //! Label types for Prometheus metrics
 
-use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::encoding::{EncodeLabelSet, LabelSetEncoder};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 pub(crate) struct InterfaceLabels {
@@ -21,6 +21,23 @@ pub(crate) struct SystemInfoLabels {
     pub(crate) router: String,
     pub(crate) version: String,
     pub(crate) board: String,
+    pub(crate) architecture: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct RouterInfoLabels {
+    pub(crate) router: String,
+    pub(crate) identity: String,
+    pub(crate) version: String,
+    pub(crate) board: String,
+    pub(crate) architecture: String,
+    pub(crate) serial: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct CpuCoreLabels {
+    pub(crate) router: String,
+    pub(crate) core: String,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -37,22 +54,268 @@ pub(crate) struct WireGuardInterfaceLabels {
     pub(crate) interface: String,
 }
 
+/// Label identifying a WireGuard peer: `allowed_address` or `public_key`,
+/// depending on the router's `wireguard_peer_key`
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) enum WireGuardPeerKeyLabel {
+    AllowedAddress(String),
+    PublicKey(String),
+}
+
+impl EncodeLabelSet for WireGuardPeerKeyLabel {
+    fn encode(&self, encoder: &mut LabelSetEncoder) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::AllowedAddress(address) => {
+                [("allowed_address", address.as_str())].encode(encoder)
+            }
+            Self::PublicKey(key) => [("public_key", key.as_str())].encode(encoder),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 pub(crate) struct WireGuardPeerLabels {
     pub(crate) router: String,
     pub(crate) interface: String,
-    pub(crate) allowed_address: String,
+    #[prometheus(flatten)]
+    pub(crate) peer: WireGuardPeerKeyLabel,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 pub(crate) struct WireGuardPeerInfoLabels {
     pub(crate) router: String,
     pub(crate) interface: String,
-    pub(crate) allowed_address: String,
+    #[prometheus(flatten)]
+    pub(crate) peer: WireGuardPeerKeyLabel,
     pub(crate) name: String,
     pub(crate) endpoint: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct IpsecPeerLabels {
+    pub(crate) router: String,
+    pub(crate) peer: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct IpServiceLabels {
+    pub(crate) router: String,
+    pub(crate) service: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct DhcpServerLabels {
+    pub(crate) router: String,
+    pub(crate) server: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct DhcpLeaseLabels {
+    pub(crate) router: String,
+    pub(crate) server: String,
+    pub(crate) status: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct PppServiceLabels {
+    pub(crate) router: String,
+    pub(crate) service: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct PppUserLabels {
+    pub(crate) router: String,
+    pub(crate) user: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct BondLabels {
+    pub(crate) router: String,
+    pub(crate) bond: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct BondSlaveLabels {
+    pub(crate) router: String,
+    pub(crate) bond: String,
+    pub(crate) slave: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct TimezoneLabels {
+    pub(crate) router: String,
+    pub(crate) timezone: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct LicenseLabels {
+    pub(crate) router: String,
+    pub(crate) level: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct WirelessStationLabels {
+    pub(crate) router: String,
+    pub(crate) interface: String,
+    pub(crate) mac: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct ManagedDeviceLabels {
+    pub(crate) router: String,
+    pub(crate) managed_device: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct CapsmanInterfaceLabels {
+    pub(crate) router: String,
+    pub(crate) interface: String,
+    pub(crate) ssid: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct CapsmanClientLabels {
+    pub(crate) router: String,
+    pub(crate) interface: String,
+    pub(crate) ssid: String,
+    pub(crate) mac: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct AddressFamilyLabels {
+    pub(crate) router: String,
+    pub(crate) address_family: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct GatewayLabels {
+    pub(crate) router: String,
+    pub(crate) gateway: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct TunnelLabels {
+    pub(crate) router: String,
+    pub(crate) r#type: String,
+    pub(crate) name: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct VpnTunnelLabels {
+    pub(crate) router: String,
+    pub(crate) r#type: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct CommandLabels {
+    pub(crate) router: String,
+    pub(crate) command: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct PingLabels {
+    pub(crate) router: String,
+    pub(crate) target: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct CollectorLabels {
+    pub(crate) router: String,
+    pub(crate) collector: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct QuantileLabels {
+    pub(crate) router: String,
+    pub(crate) quantile: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct ScrapeErrorLabels {
+    pub(crate) router: String,
+    pub(crate) reason: String,
+}
+
+/// Optional `id` label carrying the RouterOS `.id` when `INCLUDE_IDS` is set
+///
+/// Flattened into label sets, so no `id` label is written at all when unset.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub(crate) struct IdLabel(pub(crate) Option<String>);
+
+impl EncodeLabelSet for IdLabel {
+    fn encode(&self, encoder: &mut LabelSetEncoder) -> Result<(), std::fmt::Error> {
+        match &self.0 {
+            Some(id) => [("id", id.as_str())].encode(encoder),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct QueueLabels {
+    pub(crate) router: String,
+    pub(crate) queue: String,
+    pub(crate) direction: String,
+    #[prometheus(flatten)]
+    pub(crate) id: IdLabel,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct FirewallRuleLabels {
+    pub(crate) router: String,
+    pub(crate) chain: String,
+    pub(crate) comment: String,
+    #[prometheus(flatten)]
+    pub(crate) id: IdLabel,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct ContainerStatusLabels {
+    pub(crate) router: String,
+    pub(crate) container: String,
+    pub(crate) status: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct ContainerInfoLabels {
+    pub(crate) router: String,
+    pub(crate) container: String,
+    pub(crate) tag: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct RebootLabels {
+    pub(crate) router: String,
+    pub(crate) reason: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct BgpSessionLabels {
+    pub(crate) router: String,
+    pub(crate) peer: String,
+    pub(crate) remote_as: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub(crate) struct HealthSensorLabels {
+    pub(crate) router: String,
+    pub(crate) sensor: String,
+}
+
+/// Labels of a custom collector entry: the router, then the configured ones
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct CustomLabels {
+    pub(crate) router: String,
+    pub(crate) labels: Vec<(String, String)>,
+}
+
+impl EncodeLabelSet for CustomLabels {
+    fn encode(&self, encoder: &mut LabelSetEncoder) -> Result<(), std::fmt::Error> {
+        [("router", self.router.as_str())].encode(encoder)?;
+        self.labels.encode(encoder)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,6 +394,7 @@ mod tests {
             router: "router1".to_string(),
             version: "7.10".to_string(),
             board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
         };
 
         assert_eq!(labels.router, "router1");
@@ -144,6 +408,7 @@ mod tests {
             router: "router1".to_string(),
             version: "7.10".to_string(),
             board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
         };
 
         let cloned = labels.clone();
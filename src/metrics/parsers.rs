@@ -5,45 +5,63 @@
 
 /// Parse MikroTik uptime string to seconds
 ///
-/// Accepts formats like: 1d2h3m4s, 2w1d, 05:23:10, 1h5m, 30s
+/// Accepts formats like: 1d2h3m4s, 2w1d, 05:23:10, 1h5m, 30s, and a unit
+/// prefix followed by a colon time such as `1d 02:03:04`. Fractional and
+/// sub-second parts (`04.5`, `4s500ms`) are ignored.
 ///
 /// Uses saturating arithmetic to prevent integer overflow on malicious/corrupted input.
 pub(crate) fn parse_uptime_to_seconds(s: &str) -> u64 {
-    // Accept formats like 1d2h3m4s, 2w1d, 05:23:10, 1h5m, 30s
-    if s.contains(':') {
-        // HH:MM:SS or MM:SS
-        let parts: Vec<&str> = s.split(':').collect();
-        if parts.len() == 3 {
-            let h = parts[0].parse::<u64>().unwrap_or(0);
-            let m = parts[1].parse::<u64>().unwrap_or(0);
-            let sec = parts[2].parse::<u64>().unwrap_or(0);
-            return h
-                .saturating_mul(3600)
-                .saturating_add(m.saturating_mul(60))
-                .saturating_add(sec);
-        } else if parts.len() == 2 {
-            let m = parts[0].parse::<u64>().unwrap_or(0);
-            let sec = parts[1].parse::<u64>().unwrap_or(0);
-            return m.saturating_mul(60).saturating_add(sec);
-        }
-    }
+    let s = s.trim();
+    let Some(colon) = s.find(':') else {
+        return parse_unit_duration(s);
+    };
+    // The colon time starts with the digits right before the first colon
+    let clock_start = s[..colon]
+        .trim_end_matches(|c: char| c.is_ascii_digit())
+        .len();
+    parse_unit_duration(&s[..clock_start]).saturating_add(parse_clock_time(&s[clock_start..]))
+}
+
+/// Seconds from `HH:MM:SS` or `MM:SS`
+fn parse_clock_time(s: &str) -> u64 {
+    let whole = s.split('.').next().unwrap_or(s);
+    whole.split(':').fold(0u64, |total, part| {
+        total
+            .saturating_mul(60)
+            .saturating_add(part.parse::<u64>().unwrap_or(0))
+    })
+}
+
+/// Seconds from unit-suffixed values such as `1w2d3h4m5s`
+fn parse_unit_duration(s: &str) -> u64 {
     let mut total = 0u64;
     let mut num = String::new();
-    for ch in s.chars() {
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
         if ch.is_ascii_digit() {
             num.push(ch);
             continue;
         }
-        if num.is_empty() {
+        if ch == '.' {
+            // Drop the fractional part of the current value
+            while chars.next_if(char::is_ascii_digit).is_some() {}
+            continue;
+        }
+        if !ch.is_ascii_alphabetic() {
             continue;
         }
+        let mut unit = String::from(ch);
+        while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+            unit.push(c);
+        }
         let value = num.parse::<u64>().unwrap_or(0);
-        let unit_seconds = match ch {
-            'w' => 7 * 24 * 3600,
-            'd' => 24 * 3600,
-            'h' => 3600,
-            'm' => 60,
-            's' => 1,
+        // Sub-second units (ms, us) and unknown units contribute nothing
+        let unit_seconds = match unit.as_str() {
+            "w" => 7 * 24 * 3600,
+            "d" => 24 * 3600,
+            "h" => 3600,
+            "m" => 60,
+            "s" => 1,
             _ => 0,
         };
         total = total.saturating_add(value.saturating_mul(unit_seconds));
@@ -56,6 +74,41 @@ pub(crate) fn parse_uptime_to_seconds(s: &str) -> u64 {
     total
 }
 
+/// Whether `parse_uptime_to_seconds` returning 0 for `s` means the format
+/// wasn't understood rather than a genuinely zero uptime
+///
+/// Empty values and values made only of zeros and units (`0s`, `00:00:00`)
+/// are legitimate zeros.
+pub(crate) fn uptime_parse_failed(s: &str, seconds: u64) -> bool {
+    let s = s.trim();
+    seconds == 0
+        && !s.is_empty()
+        && (s.chars().any(|c| c.is_ascii_digit() && c != '0')
+            || !s.chars().any(|c| c.is_ascii_digit()))
+}
+
+/// Normalize a MAC address for use as a label value
+///
+/// RouterOS commands report MACs as `AA:BB:CC:DD:EE:FF`, `aa-bb-cc-dd-ee-ff`
+/// or `aabb.ccdd.eeff` depending on the menu; all become lowercase
+/// colon-separated so the same device yields one series across collectors.
+/// Values that are not 12 hex digits are returned trimmed and unchanged.
+pub(crate) fn normalize_mac(mac: &str) -> String {
+    let digits: Vec<char> = mac
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect();
+    if digits.len() != 12 || !digits.iter().all(char::is_ascii_hexdigit) {
+        return mac.trim().to_string();
+    }
+    digits
+        .chunks(2)
+        .map(|pair| pair.iter().collect::<String>().to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +121,48 @@ mod tests {
         assert_eq!(parse_uptime_to_seconds("05:23:10"), 19390);
         assert_eq!(parse_uptime_to_seconds("23:10"), 1390);
     }
+
+    #[test]
+    fn test_parse_uptime_day_prefix_with_colon_time() {
+        assert_eq!(parse_uptime_to_seconds("1d 02:03:04"), 93784);
+        assert_eq!(parse_uptime_to_seconds("1w2d 00:00:05"), 777_605);
+        assert_eq!(parse_uptime_to_seconds("1d02:03:04"), 93784);
+    }
+
+    #[test]
+    fn test_parse_uptime_ignores_sub_second_parts() {
+        assert_eq!(parse_uptime_to_seconds("05:23:10.250"), 19390);
+        assert_eq!(parse_uptime_to_seconds("1m4s500ms"), 64);
+        assert_eq!(parse_uptime_to_seconds("4.5s"), 4);
+        assert_eq!(parse_uptime_to_seconds("750ms"), 0);
+    }
+
+    #[test]
+    fn test_uptime_parse_failed() {
+        assert!(!uptime_parse_failed("0s", 0));
+        assert!(!uptime_parse_failed("00:00:00", 0));
+        assert!(!uptime_parse_failed("", 0));
+        assert!(!uptime_parse_failed("1d2h", 93_600));
+        assert!(uptime_parse_failed("1y", 0));
+        assert!(uptime_parse_failed("unknown", 0));
+    }
+
+    #[test]
+    fn test_normalize_mac_formats() {
+        for mac in [
+            "AA:BB:CC:0D:0E:0F",
+            "aa-bb-cc-0d-0e-0f",
+            "aabb.cc0d.0e0f",
+            "AABBCC0D0E0F",
+            " aa:bb:cc:0d:0e:0f ",
+        ] {
+            assert_eq!(normalize_mac(mac), "aa:bb:cc:0d:0e:0f", "input: {mac:?}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_mac_keeps_invalid_values() {
+        assert_eq!(normalize_mac("unknown"), "unknown");
+        assert_eq!(normalize_mac("AA:BB:CC"), "AA:BB:CC");
+    }
 }
@@ -4,13 +4,15 @@
 //! Cleanup helpers for stale and expired metric labels
 
 use crate::metrics::labels::{
-    ConntrackLabels, InterfaceLabels, RouterLabels, SystemInfoLabels, WireGuardPeerInfoLabels,
-    WireGuardPeerLabels,
+    AddressFamilyLabels, CapsmanInterfaceLabels, ConntrackLabels, FirewallRuleLabels,
+    InterfaceLabels, IpsecPeerLabels, PppUserLabels, QuantileLabels, RouterInfoLabels,
+    RouterLabels, SystemInfoLabels, WireGuardPeerInfoLabels, WireGuardPeerLabels,
 };
 use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 use super::MetricsRegistry;
+use super::scrape::SCRAPE_DURATION_QUANTILES;
 
 impl MetricsRegistry {
     /// Clean up stale interface metrics for interfaces that no longer exist
@@ -49,7 +51,17 @@ impl MetricsRegistry {
                 self.interface_tx_packets.remove(labels);
                 self.interface_rx_errors.remove(labels);
                 self.interface_tx_errors.remove(labels);
+                self.interface_rx_drops.remove(labels);
+                self.interface_tx_drops.remove(labels);
+                self.interface_rx_multicast.remove(labels);
+                self.interface_tx_multicast.remove(labels);
+                self.interface_rx_broadcast.remove(labels);
+                self.interface_tx_broadcast.remove(labels);
+                self.interface_flaps.remove(labels);
                 self.interface_running.remove(labels);
+                self.interface_disabled.remove(labels);
+                self.interface_mtu.remove(labels);
+                self.interface_actual_mtu.remove(labels);
             }
             tracing::debug!(
                 "Removed {} stale interface label sets",
@@ -150,6 +162,95 @@ impl MetricsRegistry {
                 stale_peer_info.len()
             );
         }
+
+        let stale_ipsec_peers: Vec<IpsecPeerLabels> = {
+            let mut last_seen = self.ipsec_peer_last_seen.lock().await;
+            let stale: Vec<_> = last_seen
+                .iter()
+                .filter(|(_, ts)| now.duration_since(**ts) > ttl)
+                .map(|(label, _)| label.clone())
+                .collect();
+            for label in &stale {
+                last_seen.remove(label);
+            }
+            stale
+        };
+        if !stale_ipsec_peers.is_empty() {
+            let mut prev_map = self.prev_ipsec_peers.lock().await;
+            for label in &stale_ipsec_peers {
+                self.ipsec_peer_state.remove(label);
+                self.ipsec_peer_rx_bytes.remove(label);
+                self.ipsec_peer_tx_bytes.remove(label);
+                if let Some(set) = prev_map.get_mut(&label.router) {
+                    set.remove(label);
+                    if set.is_empty() {
+                        prev_map.remove(&label.router);
+                    }
+                }
+            }
+            tracing::debug!(
+                "Expired {} ipsec peer labels via TTL cleanup",
+                stale_ipsec_peers.len()
+            );
+        }
+
+        let stale_capsman_interfaces: Vec<CapsmanInterfaceLabels> = {
+            let mut last_seen = self.capsman_interface_last_seen.lock().await;
+            let stale: Vec<_> = last_seen
+                .iter()
+                .filter(|(_, ts)| now.duration_since(**ts) > ttl)
+                .map(|(label, _)| label.clone())
+                .collect();
+            for label in &stale {
+                last_seen.remove(label);
+            }
+            stale
+        };
+        if !stale_capsman_interfaces.is_empty() {
+            let mut prev_map = self.prev_capsman_interfaces.lock().await;
+            for label in &stale_capsman_interfaces {
+                self.capsman_interface_clients.remove(label);
+                if let Some(set) = prev_map.get_mut(&label.router) {
+                    set.remove(label);
+                    if set.is_empty() {
+                        prev_map.remove(&label.router);
+                    }
+                }
+            }
+            tracing::debug!(
+                "Expired {} capsman interface labels via TTL cleanup",
+                stale_capsman_interfaces.len()
+            );
+        }
+
+        let stale_ppp_users: Vec<PppUserLabels> = {
+            let mut last_seen = self.ppp_user_last_seen.lock().await;
+            let stale: Vec<_> = last_seen
+                .iter()
+                .filter(|(_, ts)| now.duration_since(**ts) > ttl)
+                .map(|(label, _)| label.clone())
+                .collect();
+            for label in &stale {
+                last_seen.remove(label);
+            }
+            stale
+        };
+        if !stale_ppp_users.is_empty() {
+            let mut prev_map = self.prev_ppp_users.lock().await;
+            for label in &stale_ppp_users {
+                self.ppp_session_uptime_seconds.remove(label);
+                if let Some(set) = prev_map.get_mut(&label.router) {
+                    set.remove(label);
+                    if set.is_empty() {
+                        prev_map.remove(&label.router);
+                    }
+                }
+            }
+            tracing::debug!(
+                "Expired {} ppp user labels via TTL cleanup",
+                stale_ppp_users.len()
+            );
+        }
     }
 
     /// Clean up cached state for routers that are no longer configured
@@ -174,7 +275,49 @@ impl MetricsRegistry {
             self.interface_tx_packets.remove(label);
             self.interface_rx_errors.remove(label);
             self.interface_tx_errors.remove(label);
+            self.interface_rx_drops.remove(label);
+            self.interface_tx_drops.remove(label);
+            self.interface_rx_multicast.remove(label);
+            self.interface_tx_multicast.remove(label);
+            self.interface_rx_broadcast.remove(label);
+            self.interface_tx_broadcast.remove(label);
+            self.interface_flaps.remove(label);
             self.interface_running.remove(label);
+            self.interface_disabled.remove(label);
+            self.interface_mtu.remove(label);
+            self.interface_actual_mtu.remove(label);
+        }
+
+        let stale_interface_queues: Vec<InterfaceLabels> = {
+            let mut prev = self.prev_interface_queue_drops.lock().await;
+            let stale: Vec<_> = prev
+                .keys()
+                .filter(|labels| !active_routers.contains(&labels.router))
+                .cloned()
+                .collect();
+            prev.retain(|labels, _| active_routers.contains(&labels.router));
+            stale
+        };
+        for label in &stale_interface_queues {
+            stale_routers.insert(label.router.clone());
+            self.interface_queue_drops.remove(label);
+            self.interface_queue_length.remove(label);
+        }
+
+        let stale_firewall_rules: Vec<FirewallRuleLabels> = {
+            let mut prev = self.prev_firewall_rules.lock().await;
+            let stale: Vec<_> = prev
+                .keys()
+                .filter(|labels| !active_routers.contains(&labels.router))
+                .cloned()
+                .collect();
+            prev.retain(|labels, _| active_routers.contains(&labels.router));
+            stale
+        };
+        for label in &stale_firewall_rules {
+            stale_routers.insert(label.router.clone());
+            self.firewall_filter_bytes.remove(label);
+            self.firewall_filter_packets.remove(label);
         }
 
         let stale_system: Vec<SystemInfoLabels> = {
@@ -195,6 +338,24 @@ impl MetricsRegistry {
             self.system_info.remove(label);
         }
 
+        let stale_router_info: Vec<RouterInfoLabels> = {
+            let mut prev_router_info = self.prev_router_info.lock().await;
+            let mut stale = Vec::new();
+            prev_router_info.retain(|router, labels| {
+                if active_routers.contains(router) {
+                    true
+                } else {
+                    stale_routers.insert(router.clone());
+                    stale.push(labels.clone());
+                    false
+                }
+            });
+            stale
+        };
+        for label in &stale_router_info {
+            self.router_info.remove(label);
+        }
+
         let stale_conntrack: Vec<ConntrackLabels> = {
             let mut prev_map = self.prev_conntrack.lock().await;
             let mut stale = Vec::new();
@@ -251,20 +412,418 @@ impl MetricsRegistry {
             self.wireguard_peer_info.remove(label);
         }
 
+        let stale_ipsec_peers: Vec<IpsecPeerLabels> = {
+            let mut prev_map = self.prev_ipsec_peers.lock().await;
+            let mut stale = Vec::new();
+            prev_map.retain(|router, labels| {
+                if active_routers.contains(router) {
+                    true
+                } else {
+                    stale_routers.insert(router.clone());
+                    stale.extend(labels.iter().cloned());
+                    false
+                }
+            });
+            stale
+        };
+        for label in &stale_ipsec_peers {
+            self.ipsec_peer_state.remove(label);
+            self.ipsec_peer_rx_bytes.remove(label);
+            self.ipsec_peer_tx_bytes.remove(label);
+        }
+
+        let stale_capsman_interfaces: Vec<CapsmanInterfaceLabels> = {
+            let mut prev_map = self.prev_capsman_interfaces.lock().await;
+            let mut stale = Vec::new();
+            prev_map.retain(|router, labels| {
+                if active_routers.contains(router) {
+                    true
+                } else {
+                    stale_routers.insert(router.clone());
+                    stale.extend(labels.iter().cloned());
+                    false
+                }
+            });
+            stale
+        };
+        for label in &stale_capsman_interfaces {
+            self.capsman_interface_clients.remove(label);
+        }
+
+        let stale_ip_services = self
+            .prev_ip_services
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_ip_services {
+            self.ip_service_enabled.remove(label);
+            self.ip_service_port.remove(label);
+        }
+
+        let stale_bonds = self
+            .prev_bonds
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_bonds {
+            self.bonding_active_slaves.remove(label);
+        }
+        let stale_bond_slaves = self
+            .prev_bond_slaves
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_bond_slaves {
+            self.bonding_slave_up.remove(label);
+        }
+
+        let stale_wireguard_interfaces = self
+            .prev_wireguard_interfaces
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_wireguard_interfaces {
+            self.wireguard_peers_active.remove(label);
+        }
+
+        let stale_wifi_interfaces = self
+            .prev_wifi_interfaces
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_wifi_interfaces {
+            self.wifi_channel_utilization_ratio.remove(label);
+        }
+        let stale_sfp_interfaces = self
+            .prev_sfp_interfaces
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_sfp_interfaces {
+            self.sfp_rx_power_dbm.remove(label);
+            self.sfp_tx_power_dbm.remove(label);
+            self.sfp_temperature_celsius.remove(label);
+            self.sfp_supply_voltage_volts.remove(label);
+        }
+        let stale_wireless_stations = self
+            .prev_wireless_stations
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_wireless_stations {
+            self.wireless_station_signal_dbm.remove(label);
+            self.wireless_station_tx_rate_bps.remove(label);
+        }
+
+        let stale_command_errors = self
+            .seen_command_errors
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_command_errors {
+            self.command_errors.remove(label);
+        }
+
+        let stale_ppp_services = self
+            .seen_ppp_services
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_ppp_services {
+            self.ppp_sessions_established.remove(label);
+        }
+        self.prev_ppp_sessions
+            .lock()
+            .await
+            .retain(|router, _| active_routers.contains(router));
+
+        let stale_ppp_active = self
+            .prev_ppp_services
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_ppp_active {
+            self.ppp_active_sessions.remove(label);
+        }
+
+        let stale_ppp_users: Vec<PppUserLabels> = {
+            let mut prev_map = self.prev_ppp_users.lock().await;
+            let mut stale = Vec::new();
+            prev_map.retain(|router, labels| {
+                if active_routers.contains(router) {
+                    true
+                } else {
+                    stale_routers.insert(router.clone());
+                    stale.extend(labels.iter().cloned());
+                    false
+                }
+            });
+            stale
+        };
+        for label in &stale_ppp_users {
+            self.ppp_session_uptime_seconds.remove(label);
+        }
+        self.ppp_user_last_seen
+            .lock()
+            .await
+            .retain(|label, _| active_routers.contains(&label.router));
+
+        let stale_reboot_reasons = self
+            .seen_reboot_reasons
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_reboot_reasons {
+            self.unexpected_reboots.remove(label);
+        }
+        self.prev_reboot_events
+            .lock()
+            .await
+            .retain(|router, _| active_routers.contains(router));
+
+        let stale_dhcp_servers = self
+            .seen_dhcp_servers
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_dhcp_servers {
+            self.dhcp_leases_granted.remove(label);
+        }
+
+        let stale_dhcp_leases = self
+            .prev_dhcp_leases
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_dhcp_leases {
+            self.dhcp_leases.remove(label);
+        }
+        self.prev_dhcp_bound_macs
+            .lock()
+            .await
+            .retain(|router, _| active_routers.contains(router));
+
+        let stale_scrape_error_reasons = self
+            .seen_scrape_error_reasons
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_scrape_error_reasons {
+            self.scrape_error_reason.remove(label);
+        }
+
+        let stale_managed_devices = self
+            .prev_managed_devices
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_managed_devices {
+            self.capsman_clients.remove(label);
+        }
+        let stale_capsman_clients = self
+            .prev_capsman_clients
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_capsman_clients {
+            self.capsman_client_signal_dbm.remove(label);
+        }
+
+        let stale_ping_targets = self
+            .prev_ping_targets
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_ping_targets {
+            self.ping_rtt_ms.remove(label);
+            self.ping_loss_ratio.remove(label);
+        }
+
+        let stale_queues = self
+            .prev_queues
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_queues {
+            self.queue_simple_max_limit_bps.remove(label);
+            self.queue_simple_limit_at_bps.remove(label);
+            self.queue_simple_bytes.remove(label);
+            self.queue_simple_packets.remove(label);
+        }
+        self.prev_simple_queue_counters
+            .lock()
+            .await
+            .retain(|labels, _| active_routers.contains(&labels.router));
+
+        let stale_default_routes = self
+            .prev_default_routes
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_default_routes {
+            self.default_route_active.remove(label);
+        }
+
+        let stale_cpu_cores = self
+            .prev_cpu_cores
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_cpu_cores {
+            self.system_cpu_core_load.remove(label);
+        }
+
+        let stale_tunnels = self
+            .prev_tunnels
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_tunnels {
+            self.tunnel_running.remove(label);
+        }
+
+        let stale_vrrp = self
+            .prev_vrrp
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_vrrp {
+            self.vrrp_master.remove(label);
+        }
+
+        let stale_bgp_sessions = self
+            .prev_bgp_sessions
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_bgp_sessions {
+            self.bgp_session_state.remove(label);
+            self.bgp_session_prefix_count.remove(label);
+            self.bgp_session_uptime_seconds.remove(label);
+        }
+
+        let stale_container_status = self
+            .prev_container_status
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_container_status {
+            self.container_status.remove(label);
+        }
+        let stale_container_info = self
+            .prev_container_info
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_container_info {
+            self.container_info.remove(label);
+        }
+
+        let stale_health_sensors = self
+            .prev_health_sensors
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_health_sensors {
+            self.system_temperature_celsius.remove(label);
+            self.system_voltage.remove(label);
+            self.system_fan_rpm.remove(label);
+        }
+
+        let stale_custom_metrics = self
+            .prev_custom_metrics
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        let custom_gauges = self.custom_gauges.lock().await;
+        for (collector, labels) in &stale_custom_metrics {
+            if let Some(family) = custom_gauges.get(collector) {
+                family.remove(labels);
+            }
+        }
+        drop(custom_gauges);
+
+        let stale_vpn_tunnels = self
+            .prev_vpn_tunnels
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_vpn_tunnels {
+            self.vpn_tunnels_active.remove(label);
+        }
+
+        let stale_collectors = self
+            .prev_collectors
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_collectors {
+            self.collector_enabled.remove(label);
+        }
+
+        let stale_collector_durations = self
+            .prev_collector_durations
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_collector_durations {
+            self.collector_duration_milliseconds.remove(label);
+        }
+
+        {
+            let mut windows = self.scrape_duration_windows.lock().await;
+            for router in windows.keys() {
+                if !active_routers.contains(router) {
+                    stale_routers.insert(router.clone());
+                }
+            }
+            windows.retain(|router, _| active_routers.contains(router));
+        }
+
+        let stale_timezones = self
+            .prev_timezones
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_timezones {
+            self.system_timezone_info.remove(label);
+        }
+
+        let stale_licenses = self
+            .prev_licenses
+            .drop_inactive(active_routers, &mut stale_routers)
+            .await;
+        for label in &stale_licenses {
+            self.license_info.remove(label);
+        }
+
         for router in &stale_routers {
             let router_labels = RouterLabels {
                 router: router.clone(),
             };
             self.system_cpu_load.remove(&router_labels);
+            self.system_cpu_count.remove(&router_labels);
             self.system_free_memory.remove(&router_labels);
             self.system_total_memory.remove(&router_labels);
+            self.system_free_hdd.remove(&router_labels);
+            self.system_total_hdd.remove(&router_labels);
+            self.system_hdd_usage_ratio.remove(&router_labels);
             self.system_uptime_seconds.remove(&router_labels);
+            self.uptime_parse_failures.remove(&router_labels);
+            self.system_time_offset_seconds.remove(&router_labels);
+            self.router_clock_skew_seconds.remove(&router_labels);
+            self.license_level.remove(&router_labels);
+            self.watchdog_enabled.remove(&router_labels);
+            self.conntrack_table_entries.remove(&router_labels);
+            self.conntrack_table_max.remove(&router_labels);
+            self.license_deadline_timestamp_seconds
+                .remove(&router_labels);
             self.scrape_success.remove(&router_labels);
             self.scrape_errors.remove(&router_labels);
             self.scrape_duration_milliseconds.remove(&router_labels);
+            self.scrape_duration_seconds.remove(&router_labels);
+            for (_, quantile) in SCRAPE_DURATION_QUANTILES {
+                self.scrape_duration_quantile_milliseconds
+                    .remove(&QuantileLabels {
+                        router: router.clone(),
+                        quantile: quantile.to_string(),
+                    });
+            }
             self.scrape_last_success_timestamp_seconds
                 .remove(&router_labels);
+            self.scrape_interval_too_short.remove(&router_labels);
+            self.metric_stale.remove(&router_labels);
+            self.router_reachable_since_seconds.remove(&router_labels);
+            self.reachable_streak_start
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .remove(router);
+            self.commands_per_scrape.remove(&router_labels);
+            self.script_jobs_running.remove(&router_labels);
+            self.userman_active_sessions.remove(&router_labels);
+            for address_family in ["ipv4", "ipv6"] {
+                self.ip_routes.remove(&AddressFamilyLabels {
+                    router: router.clone(),
+                    address_family: address_family.to_string(),
+                });
+            }
+            self.conntrack_distinct_sources.remove(&router_labels);
             self.connection_consecutive_errors.remove(&router_labels);
+            self.circuit_breaker_state.remove(&router_labels);
+            self.login_method.remove(&router_labels);
+            self.login_method_changes.remove(&router_labels);
+            self.connection_resets.remove(&router_labels);
+            self.connection_last_success_age_seconds
+                .remove(&router_labels);
         }
 
         let mut conntrack_seen = self.conntrack_last_seen.lock().await;
@@ -276,19 +835,31 @@ impl MetricsRegistry {
         let mut peer_info_seen = self.wireguard_peer_info_last_seen.lock().await;
         peer_info_seen.retain(|label, _| active_routers.contains(&label.router));
 
+        let mut ipsec_peer_seen = self.ipsec_peer_last_seen.lock().await;
+        ipsec_peer_seen.retain(|label, _| active_routers.contains(&label.router));
+
+        let mut capsman_interface_seen = self.capsman_interface_last_seen.lock().await;
+        capsman_interface_seen.retain(|label, _| active_routers.contains(&label.router));
+
         if !stale_interfaces.is_empty()
             || !stale_system.is_empty()
             || !stale_conntrack.is_empty()
             || !stale_peers.is_empty()
             || !stale_peer_info.is_empty()
+            || !stale_ip_services.is_empty()
+            || !stale_bonds.is_empty()
+            || !stale_bond_slaves.is_empty()
         {
             tracing::debug!(
-                "Removed stale router data: interfaces={}, system_info={}, conntrack={}, wg_peers={}, wg_peer_info={}",
+                "Removed stale router data: interfaces={}, system_info={}, conntrack={}, wg_peers={}, wg_peer_info={}, ip_services={}, bonds={}, bond_slaves={}",
                 stale_interfaces.len(),
                 stale_system.len(),
                 stale_conntrack.len(),
                 stale_peers.len(),
-                stale_peer_info.len()
+                stale_peer_info.len(),
+                stale_ip_services.len(),
+                stale_bonds.len(),
+                stale_bond_slaves.len()
             );
         }
     }
@@ -3,182 +3,821 @@
 
 //! Registry initialization and metric registration
 
+use crate::config::{CounterMode, DEFAULT_METRIC_PREFIX};
 use crate::metrics::labels::{
-    ConntrackLabels, InterfaceLabels, RouterLabels, SystemInfoLabels, WireGuardPeerInfoLabels,
-    WireGuardPeerLabels,
+    AddressFamilyLabels, BgpSessionLabels, BondLabels, BondSlaveLabels, CapsmanClientLabels,
+    CapsmanInterfaceLabels, CollectorLabels, CommandLabels, ConntrackLabels, ContainerInfoLabels,
+    ContainerStatusLabels, CpuCoreLabels, DhcpLeaseLabels, DhcpServerLabels, FirewallRuleLabels,
+    GatewayLabels, HealthSensorLabels, InterfaceLabels, IpServiceLabels, IpsecPeerLabels,
+    LicenseLabels, ManagedDeviceLabels, PingLabels, PppServiceLabels, PppUserLabels,
+    QuantileLabels, QueueLabels, RebootLabels, RouterInfoLabels, RouterLabels, ScrapeErrorLabels,
+    SystemInfoLabels, TimezoneLabels, TunnelLabels, VpnTunnelLabels, WireGuardInterfaceLabels,
+    WireGuardPeerInfoLabels, WireGuardPeerLabels, WirelessStationLabels,
 };
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::Histogram;
 use prometheus_client::registry::Registry;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 use super::MetricsRegistry;
+use super::label_set::RouterLabelSet;
+use super::scrape::scrape_duration_histogram;
+
+/// Semantics of the interface counters, appended to their help text
+const INTERFACE_COUNTER_HELP: &str = "; with counter_mode=delta (default) the increase seen \
+since the exporter started, with counter_mode=absolute the router's own counter, restarting \
+when the router resets it";
+
+/// WireGuard re-handshakes every 2 minutes while traffic flows
+const DEFAULT_WIREGUARD_ACTIVE_WINDOW: Duration = Duration::from_secs(180);
+
+/// Scrapes kept per router for `mikrotik_scrape_duration_quantile_milliseconds`
+const DEFAULT_SCRAPE_DURATION_WINDOW: usize = 100;
 
 impl MetricsRegistry {
-    #[allow(clippy::similar_names)] // rx/tx naming pattern is intentional
     pub fn new() -> Self {
-        let mut registry = Registry::default();
+        Self::with_prefix(DEFAULT_METRIC_PREFIX, None)
+    }
+
+    /// Creates a registry whose series all carry an `env` label
+    ///
+    /// Distinguishes routers with the same name across environments
+    /// (e.g. staging and prod) scraped by one Prometheus.
+    pub fn with_environment(environment: &str) -> Self {
+        Self::with_prefix(DEFAULT_METRIC_PREFIX, Some(environment))
+    }
 
+    /// Creates a registry whose metric names start with `prefix_` instead of
+    /// `mikrotik_`, optionally with an `env` label on every series
+    ///
+    /// The prefix must be a valid metric name; see [`Config`](crate::config::Config)
+    /// for its validation.
+    pub fn with_prefix(prefix: &str, environment: Option<&str>) -> Self {
+        let labels = environment
+            .map(|environment| (Cow::Borrowed("env"), Cow::Owned(environment.to_string())));
+        Self::with_registry(Registry::with_prefix_and_labels(prefix, labels.into_iter()))
+    }
+
+    #[allow(clippy::similar_names)] // rx/tx naming pattern is intentional
+    fn with_registry(mut registry: Registry) -> Self {
         let interface_rx_bytes = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_rx_bytes",
-            "Received bytes on interface",
+            "interface_rx_bytes",
+            format!("Received bytes on interface{INTERFACE_COUNTER_HELP}"),
             interface_rx_bytes.clone(),
         );
         let interface_tx_bytes = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_tx_bytes",
-            "Transmitted bytes on interface",
+            "interface_tx_bytes",
+            format!("Transmitted bytes on interface{INTERFACE_COUNTER_HELP}"),
             interface_tx_bytes.clone(),
         );
         let interface_rx_packets = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_rx_packets",
-            "Received packets on interface",
+            "interface_rx_packets",
+            format!("Received packets on interface{INTERFACE_COUNTER_HELP}"),
             interface_rx_packets.clone(),
         );
         let interface_tx_packets = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_tx_packets",
-            "Transmitted packets on interface",
+            "interface_tx_packets",
+            format!("Transmitted packets on interface{INTERFACE_COUNTER_HELP}"),
             interface_tx_packets.clone(),
         );
         let interface_rx_errors = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_rx_errors",
-            "Receive errors on interface",
+            "interface_rx_errors",
+            format!("Receive errors on interface{INTERFACE_COUNTER_HELP}"),
             interface_rx_errors.clone(),
         );
         let interface_tx_errors = Family::<InterfaceLabels, Counter>::default();
         registry.register(
-            "mikrotik_interface_tx_errors",
-            "Transmit errors on interface",
+            "interface_tx_errors",
+            format!("Transmit errors on interface{INTERFACE_COUNTER_HELP}"),
             interface_tx_errors.clone(),
         );
+        let interface_rx_drops = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_rx_drops",
+            format!("Packets dropped on receive on interface{INTERFACE_COUNTER_HELP}"),
+            interface_rx_drops.clone(),
+        );
+        let interface_tx_drops = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_tx_drops",
+            format!("Packets dropped on transmit on interface{INTERFACE_COUNTER_HELP}"),
+            interface_tx_drops.clone(),
+        );
+        let interface_rx_multicast = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_rx_multicast",
+            format!("Multicast packets received on interface{INTERFACE_COUNTER_HELP}"),
+            interface_rx_multicast.clone(),
+        );
+        let interface_tx_multicast = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_tx_multicast",
+            format!("Multicast packets transmitted on interface{INTERFACE_COUNTER_HELP}"),
+            interface_tx_multicast.clone(),
+        );
+        let interface_rx_broadcast = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_rx_broadcast",
+            format!("Broadcast packets received on interface{INTERFACE_COUNTER_HELP}"),
+            interface_rx_broadcast.clone(),
+        );
+        let interface_tx_broadcast = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_tx_broadcast",
+            format!("Broadcast packets transmitted on interface{INTERFACE_COUNTER_HELP}"),
+            interface_tx_broadcast.clone(),
+        );
+        let interface_flaps = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_flaps",
+            "Running state changes of interface seen between scrapes",
+            interface_flaps.clone(),
+        );
         let interface_running = Family::<InterfaceLabels, Gauge>::default();
         registry.register(
-            "mikrotik_interface_running",
+            "interface_running",
             "Interface running status (1=running,0=down)",
             interface_running.clone(),
         );
+        let interface_disabled = Family::<InterfaceLabels, Gauge>::default();
+        registry.register(
+            "interface_disabled",
+            "Interface administratively disabled (1=disabled,0=enabled)",
+            interface_disabled.clone(),
+        );
+        let interface_mtu = Family::<InterfaceLabels, Gauge>::default();
+        registry.register(
+            "interface_mtu",
+            "Configured interface MTU in bytes",
+            interface_mtu.clone(),
+        );
+        let interface_actual_mtu = Family::<InterfaceLabels, Gauge>::default();
+        registry.register(
+            "interface_actual_mtu",
+            "Interface MTU in effect in bytes",
+            interface_actual_mtu.clone(),
+        );
+        let interface_queue_drops = Family::<InterfaceLabels, Counter>::default();
+        registry.register(
+            "interface_queue_drops",
+            "Packets dropped by the interface transmit queue",
+            interface_queue_drops.clone(),
+        );
+        let interface_queue_length = Family::<InterfaceLabels, Gauge>::default();
+        registry.register(
+            "interface_queue_length",
+            "Packets currently waiting in the interface transmit queue",
+            interface_queue_length.clone(),
+        );
 
         let system_cpu_load = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_system_cpu_load",
+            "system_cpu_load",
             "CPU load percentage",
             system_cpu_load.clone(),
         );
+        let system_cpu_count = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "system_cpu_count",
+            "Number of CPU cores",
+            system_cpu_count.clone(),
+        );
+        let system_cpu_core_load = Family::<CpuCoreLabels, Gauge>::default();
+        registry.register(
+            "system_cpu_core_load",
+            "CPU load percentage per core",
+            system_cpu_core_load.clone(),
+        );
         let system_free_memory = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_system_free_memory_bytes",
+            "system_free_memory_bytes",
             "Free memory bytes",
             system_free_memory.clone(),
         );
         let system_total_memory = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_system_total_memory_bytes",
+            "system_total_memory_bytes",
             "Total memory bytes",
             system_total_memory.clone(),
         );
+        let system_free_hdd = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "system_free_hdd_bytes",
+            "Free storage bytes",
+            system_free_hdd.clone(),
+        );
+        let system_total_hdd = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "system_total_hdd_bytes",
+            "Total storage bytes",
+            system_total_hdd.clone(),
+        );
+        let system_hdd_usage_ratio = Family::<RouterLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "system_hdd_usage_ratio",
+            "Used share of storage (0..1)",
+            system_hdd_usage_ratio.clone(),
+        );
         let system_info = Family::<SystemInfoLabels, Gauge>::default();
         registry.register(
-            "mikrotik_system_info",
+            "system_info",
             "Static system info (value=1)",
             system_info.clone(),
         );
+        let router_info = Family::<RouterInfoLabels, Gauge>::default();
+        registry.register(
+            "router_info",
+            "Router identity, version, board, architecture and serial (value=1)",
+            router_info.clone(),
+        );
         let system_uptime_seconds = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_system_uptime_seconds",
+            "system_uptime_seconds",
             "System uptime in seconds",
             system_uptime_seconds.clone(),
         );
+        let uptime_parse_failures = Family::<RouterLabels, Counter>::default();
+        registry.register(
+            "uptime_parse_failures",
+            "Uptime values that could not be parsed and were reported as 0",
+            uptime_parse_failures.clone(),
+        );
+        let system_time_offset_seconds = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "system_time_offset_seconds",
+            "Router clock minus exporter clock in seconds",
+            system_time_offset_seconds.clone(),
+        );
+        let router_clock_skew_seconds = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "router_clock_skew_seconds",
+            "Router clock minus exporter clock when WireGuard peers were read, in seconds",
+            router_clock_skew_seconds.clone(),
+        );
+        let system_timezone_info = Family::<TimezoneLabels, Gauge>::default();
+        registry.register(
+            "system_timezone_info",
+            "Configured router timezone (value=1)",
+            system_timezone_info.clone(),
+        );
+        let license_level = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "license_level",
+            "License level (0-6; CHR: 0=free, 1=p1, 2=p10, 3=p-unlimited)",
+            license_level.clone(),
+        );
+        let license_info = Family::<LicenseLabels, Gauge>::default();
+        registry.register(
+            "license_info",
+            "License level as reported by the router (value=1)",
+            license_info.clone(),
+        );
+        let license_deadline_timestamp_seconds = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "license_deadline_timestamp_seconds",
+            "Unix timestamp by which the CHR license must be renewed",
+            license_deadline_timestamp_seconds.clone(),
+        );
         let scrape_success = Family::<RouterLabels, Counter>::default();
         registry.register(
-            "mikrotik_scrape_success",
+            "scrape_success",
             "Successful scrape cycles per router",
             scrape_success.clone(),
         );
         let scrape_errors = Family::<RouterLabels, Counter>::default();
         registry.register(
-            "mikrotik_scrape_errors",
+            "scrape_errors",
             "Failed scrape cycles per router",
             scrape_errors.clone(),
         );
+        let scrape_error_reason = Family::<ScrapeErrorLabels, Counter>::default();
+        registry.register(
+            "scrape_error_reason",
+            "Failed scrape cycles per router by cause",
+            scrape_error_reason.clone(),
+        );
+        let command_errors = Family::<CommandLabels, Counter>::default();
+        registry.register(
+            "command_errors",
+            "RouterOS commands that returned an error, per command path",
+            command_errors.clone(),
+        );
         let scrape_duration_milliseconds = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_scrape_duration_milliseconds",
-            "Duration of last scrape in milliseconds",
+            "scrape_duration_milliseconds",
+            "Duration of last scrape in milliseconds (deprecated, use \
+             mikrotik_scrape_duration_seconds)",
             scrape_duration_milliseconds.clone(),
         );
+        let scrape_duration_seconds =
+            Family::<RouterLabels, Histogram, fn() -> Histogram>::new_with_constructor(
+                scrape_duration_histogram,
+            );
+        registry.register(
+            "scrape_duration_seconds",
+            "Duration of scrapes in seconds, including failed ones",
+            scrape_duration_seconds.clone(),
+        );
+        let scrape_duration_quantile_milliseconds =
+            Family::<QuantileLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "scrape_duration_quantile_milliseconds",
+            "Quantiles of recent scrape durations in milliseconds",
+            scrape_duration_quantile_milliseconds.clone(),
+        );
         let scrape_last_success_timestamp_seconds = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_scrape_last_success_timestamp_seconds",
+            "scrape_last_success_timestamp_seconds",
             "Unix timestamp of last successful scrape",
             scrape_last_success_timestamp_seconds.clone(),
         );
+        let scrape_interval_too_short = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "scrape_interval_too_short",
+            "Last scrape took longer than the collection interval (1=yes,0=no)",
+            scrape_interval_too_short.clone(),
+        );
+        let metric_stale = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "metric_stale",
+            "Router gauges are from an earlier scrape because the last one failed (1=stale,0=fresh)",
+            metric_stale.clone(),
+        );
+        let router_reachable_since_seconds = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "router_reachable_since_seconds",
+            "Seconds the router has been scraped successfully without a failure in between",
+            router_reachable_since_seconds.clone(),
+        );
+        let commands_per_scrape = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "commands_per_scrape",
+            "Number of RouterOS commands issued during the last scrape",
+            commands_per_scrape.clone(),
+        );
+        let script_jobs_running = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "script_jobs_running",
+            "Number of running script jobs",
+            script_jobs_running.clone(),
+        );
+        let userman_active_sessions = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "userman_active_sessions",
+            "Active User Manager sessions",
+            userman_active_sessions.clone(),
+        );
+        let ip_routes = Family::<AddressFamilyLabels, Gauge>::default();
+        registry.register(
+            "ip_routes",
+            "Routing table entries per address family",
+            ip_routes.clone(),
+        );
+        let conntrack_distinct_sources = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "conntrack_distinct_sources",
+            "Distinct source addresses in connection tracking",
+            conntrack_distinct_sources.clone(),
+        );
+        let ppp_sessions_established = Family::<PppServiceLabels, Counter>::default();
+        registry.register(
+            "ppp_sessions_established",
+            "PPP sessions established since the exporter started",
+            ppp_sessions_established.clone(),
+        );
+        let ppp_active_sessions = Family::<PppServiceLabels, Gauge>::default();
+        registry.register(
+            "ppp_active_sessions",
+            "Active PPP sessions",
+            ppp_active_sessions.clone(),
+        );
+        let ppp_session_uptime_seconds = Family::<PppUserLabels, Gauge>::default();
+        registry.register(
+            "ppp_session_uptime_seconds",
+            "Duration of a user's PPP session in seconds (0 once disconnected)",
+            ppp_session_uptime_seconds.clone(),
+        );
+        let dhcp_leases_granted = Family::<DhcpServerLabels, Counter>::default();
+        registry.register(
+            "dhcp_leases_granted",
+            "DHCP leases bound to a new MAC address since the exporter started",
+            dhcp_leases_granted.clone(),
+        );
+        let dhcp_leases = Family::<DhcpLeaseLabels, Gauge>::default();
+        registry.register(
+            "dhcp_leases_total",
+            "DHCP leases per server and status",
+            dhcp_leases.clone(),
+        );
         let connection_consecutive_errors = Family::<RouterLabels, Gauge>::default();
         registry.register(
-            "mikrotik_connection_consecutive_errors",
+            "connection_consecutive_errors",
             "Number of consecutive connection errors",
             connection_consecutive_errors.clone(),
         );
+        let circuit_breaker_state = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "circuit_breaker_state",
+            "Circuit breaker state (0=closed,1=half-open,2=open)",
+            circuit_breaker_state.clone(),
+        );
+        let login_method = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "login_method",
+            "API login method of the latest login (0=new,1=legacy)",
+            login_method.clone(),
+        );
+        let login_method_changes = Family::<RouterLabels, Counter>::default();
+        registry.register(
+            "login_method_changes",
+            "Number of logins that used a different method than the previous login",
+            login_method_changes.clone(),
+        );
+        let connection_resets = Family::<RouterLabels, Counter>::default();
+        registry.register(
+            "connection_resets",
+            "Number of times the router dropped the API connection mid-reply",
+            connection_resets.clone(),
+        );
+        let connection_last_success_age_seconds = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "connection_last_success_age_seconds",
+            "Time since the last successful operation with the router in seconds",
+            connection_last_success_age_seconds.clone(),
+        );
+        let collector_enabled = Family::<CollectorLabels, Gauge>::default();
+        registry.register(
+            "collector_enabled",
+            "Collectors enabled for the router (value=1)",
+            collector_enabled.clone(),
+        );
+        let collector_duration_milliseconds =
+            Family::<CollectorLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "collector_duration_milliseconds",
+            "Time spent on a collector's RouterOS commands during the last scrape",
+            collector_duration_milliseconds.clone(),
+        );
         let collection_cycle_duration_milliseconds = Gauge::default();
         registry.register(
-            "mikrotik_collection_cycle_duration_milliseconds",
+            "collection_cycle_duration_milliseconds",
             "Duration of full collection cycle in milliseconds",
             collection_cycle_duration_milliseconds.clone(),
         );
+        let collection_in_flight = Gauge::default();
+        registry.register(
+            "collection_in_flight",
+            "Router scrapes currently running in the background collection loop",
+            collection_in_flight.clone(),
+        );
+        let metrics_encode_duration_milliseconds = Gauge::<f64, AtomicU64>::default();
+        registry.register(
+            "metrics_encode_duration_milliseconds",
+            "Duration of the previous /metrics registry encode in milliseconds",
+            metrics_encode_duration_milliseconds.clone(),
+        );
         let connection_pool_size = Gauge::default();
         registry.register(
-            "mikrotik_connection_pool_size",
+            "connection_pool_size",
             "Total number of connections in pool",
             connection_pool_size.clone(),
         );
         let connection_pool_active = Gauge::default();
         registry.register(
-            "mikrotik_connection_pool_active",
+            "connection_pool_active",
             "Number of active connections in pool",
             connection_pool_active.clone(),
         );
+        let connection_pool_oldest_idle_seconds = Gauge::default();
+        registry.register(
+            "connection_pool_oldest_idle_seconds",
+            "Seconds since the longest idle pooled connection was last used",
+            connection_pool_oldest_idle_seconds.clone(),
+        );
         let connection_tracking_count = Family::<ConntrackLabels, Gauge>::default();
         registry.register(
-            "mikrotik_connection_tracking_count",
+            "connection_tracking_count",
             "Number of tracked connections per source address and protocol",
             connection_tracking_count.clone(),
         );
+        let conntrack_table_entries = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "conntrack_table_entries",
+            "Connections currently in the connection tracking table",
+            conntrack_table_entries.clone(),
+        );
+        let conntrack_table_max = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "conntrack_table_max",
+            "Maximum size of the connection tracking table",
+            conntrack_table_max.clone(),
+        );
 
         // WireGuard metrics
 
         let wireguard_peer_rx_bytes = Family::<WireGuardPeerLabels, Gauge>::default();
         registry.register(
-            "mikrotik_wireguard_peer_rx_bytes",
+            "wireguard_peer_rx_bytes",
             "Bytes received from WireGuard peer",
             wireguard_peer_rx_bytes.clone(),
         );
 
         let wireguard_peer_tx_bytes = Family::<WireGuardPeerLabels, Gauge>::default();
         registry.register(
-            "mikrotik_wireguard_peer_tx_bytes",
+            "wireguard_peer_tx_bytes",
             "Bytes transmitted to WireGuard peer",
             wireguard_peer_tx_bytes.clone(),
         );
 
         let wireguard_peer_latest_handshake = Family::<WireGuardPeerLabels, Gauge>::default();
         registry.register(
-            "mikrotik_wireguard_peer_latest_handshake",
+            "wireguard_peer_latest_handshake",
             "Unix timestamp of last handshake with WireGuard peer",
             wireguard_peer_latest_handshake.clone(),
         );
 
         let wireguard_peer_info = Family::<WireGuardPeerInfoLabels, Gauge>::default();
         registry.register(
-            "mikrotik_wireguard_peer_info",
+            "wireguard_peer_info",
             "Static WireGuard peer info (value=1)",
             wireguard_peer_info.clone(),
         );
+        let wireguard_peers_active = Family::<WireGuardInterfaceLabels, Gauge>::default();
+        registry.register(
+            "wireguard_peers_active",
+            "Number of WireGuard peers with a recent handshake",
+            wireguard_peers_active.clone(),
+        );
+
+        // IPsec metrics
+
+        let ipsec_peer_state = Family::<IpsecPeerLabels, Gauge>::default();
+        registry.register(
+            "ipsec_peer_state",
+            "IPsec peer established (1=established,0=otherwise)",
+            ipsec_peer_state.clone(),
+        );
+        let ipsec_peer_rx_bytes = Family::<IpsecPeerLabels, Gauge>::default();
+        registry.register(
+            "ipsec_peer_rx_bytes",
+            "Bytes received from IPsec peer",
+            ipsec_peer_rx_bytes.clone(),
+        );
+        let ipsec_peer_tx_bytes = Family::<IpsecPeerLabels, Gauge>::default();
+        registry.register(
+            "ipsec_peer_tx_bytes",
+            "Bytes transmitted to IPsec peer",
+            ipsec_peer_tx_bytes.clone(),
+        );
+
+        // Management service metrics
+
+        let ip_service_enabled = Family::<IpServiceLabels, Gauge>::default();
+        registry.register(
+            "ip_service_enabled",
+            "Management service enabled (1=enabled,0=disabled)",
+            ip_service_enabled.clone(),
+        );
+
+        let ip_service_port = Family::<IpServiceLabels, Gauge>::default();
+        registry.register(
+            "ip_service_port",
+            "Port the management service listens on",
+            ip_service_port.clone(),
+        );
+
+        // Bonding metrics
+
+        let bonding_active_slaves = Family::<BondLabels, Gauge>::default();
+        registry.register(
+            "bonding_active_slaves",
+            "Number of bond slaves currently running",
+            bonding_active_slaves.clone(),
+        );
+
+        let bonding_slave_up = Family::<BondSlaveLabels, Gauge>::default();
+        registry.register(
+            "bonding_slave_up",
+            "Bond slave running state (1=up,0=down)",
+            bonding_slave_up.clone(),
+        );
+
+        let wifi_channel_utilization_ratio =
+            Family::<InterfaceLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "wifi_channel_utilization_ratio",
+            "Wireless channel utilization (0..1)",
+            wifi_channel_utilization_ratio.clone(),
+        );
+        let sfp_rx_power_dbm = Family::<InterfaceLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "sfp_rx_power_dbm",
+            "SFP module received optical power in dBm",
+            sfp_rx_power_dbm.clone(),
+        );
+        let sfp_tx_power_dbm = Family::<InterfaceLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "sfp_tx_power_dbm",
+            "SFP module transmitted optical power in dBm",
+            sfp_tx_power_dbm.clone(),
+        );
+        let sfp_temperature_celsius = Family::<InterfaceLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "sfp_temperature_celsius",
+            "SFP module temperature in degrees Celsius",
+            sfp_temperature_celsius.clone(),
+        );
+        let sfp_supply_voltage_volts = Family::<InterfaceLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "sfp_supply_voltage_volts",
+            "SFP module supply voltage in volts",
+            sfp_supply_voltage_volts.clone(),
+        );
+        let wireless_station_signal_dbm = Family::<WirelessStationLabels, Gauge>::default();
+        registry.register(
+            "wireless_station_signal_dbm",
+            "Signal strength of a registered wireless station in dBm",
+            wireless_station_signal_dbm.clone(),
+        );
+        let wireless_station_tx_rate_bps = Family::<WirelessStationLabels, Gauge>::default();
+        registry.register(
+            "wireless_station_tx_rate_bps",
+            "Current transmit rate towards a registered wireless station in bits per second",
+            wireless_station_tx_rate_bps.clone(),
+        );
+        let capsman_clients = Family::<ManagedDeviceLabels, Gauge>::default();
+        registry.register(
+            "capsman_clients",
+            "Wireless clients registered on a CAPsMAN-managed access point",
+            capsman_clients.clone(),
+        );
+        let capsman_interface_clients = Family::<CapsmanInterfaceLabels, Gauge>::default();
+        registry.register(
+            "capsman_interface_clients",
+            "Wireless clients registered on a CAPsMAN radio interface and SSID",
+            capsman_interface_clients.clone(),
+        );
+        let capsman_client_signal_dbm = Family::<CapsmanClientLabels, Gauge>::default();
+        registry.register(
+            "capsman_client_signal_dbm",
+            "Signal strength of a CAPsMAN-registered wireless client in dBm",
+            capsman_client_signal_dbm.clone(),
+        );
+
+        let ping_rtt_ms = Family::<PingLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "ping_rtt_ms",
+            "Average round-trip time of pings sent by the router, in milliseconds",
+            ping_rtt_ms.clone(),
+        );
+        let ping_loss_ratio = Family::<PingLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "ping_loss_ratio",
+            "Share of pings sent by the router without a reply (0..1)",
+            ping_loss_ratio.clone(),
+        );
+
+        let default_route_active = Family::<GatewayLabels, Gauge>::default();
+        registry.register(
+            "default_route_active",
+            "Whether a default route via the gateway is active (1=active, 0=standby)",
+            default_route_active.clone(),
+        );
+
+        let tunnel_running = Family::<TunnelLabels, Gauge>::default();
+        registry.register(
+            "tunnel_running",
+            "Whether an EoIP, GRE or VXLAN tunnel interface is running (1=running, 0=down)",
+            tunnel_running.clone(),
+        );
+        let vpn_tunnels_active = Family::<VpnTunnelLabels, Gauge>::default();
+        registry.register(
+            "vpn_tunnels_active",
+            "Active VPN tunnels by type (ipsec, wireguard, l2tp, sstp, ovpn, pptp)",
+            vpn_tunnels_active.clone(),
+        );
+
+        let vrrp_master = Family::<InterfaceLabels, Gauge>::default();
+        registry.register(
+            "vrrp_master",
+            "Whether the router is master of a VRRP interface (1=master, 0=backup)",
+            vrrp_master.clone(),
+        );
+
+        let bgp_session_state = Family::<BgpSessionLabels, Gauge>::default();
+        registry.register(
+            "bgp_session_state",
+            "BGP session established (1=established,0=otherwise)",
+            bgp_session_state.clone(),
+        );
+        let bgp_session_prefix_count = Family::<BgpSessionLabels, Gauge>::default();
+        registry.register(
+            "bgp_session_prefix_count",
+            "Prefixes received from a BGP peer",
+            bgp_session_prefix_count.clone(),
+        );
+        let bgp_session_uptime_seconds = Family::<BgpSessionLabels, Gauge>::default();
+        registry.register(
+            "bgp_session_uptime_seconds",
+            "Time since a BGP session was established in seconds",
+            bgp_session_uptime_seconds.clone(),
+        );
+
+        let firewall_filter_bytes = Family::<FirewallRuleLabels, Counter>::default();
+        registry.register(
+            "firewall_filter_bytes",
+            "Bytes matched by a firewall filter rule",
+            firewall_filter_bytes.clone(),
+        );
+        let firewall_filter_packets = Family::<FirewallRuleLabels, Counter>::default();
+        registry.register(
+            "firewall_filter_packets",
+            "Packets matched by a firewall filter rule",
+            firewall_filter_packets.clone(),
+        );
+
+        let container_status = Family::<ContainerStatusLabels, Gauge>::default();
+        registry.register(
+            "container_status",
+            "Container status (1 for the current status, 0 for the others)",
+            container_status.clone(),
+        );
+        let container_info = Family::<ContainerInfoLabels, Gauge>::default();
+        registry.register(
+            "container_info",
+            "Container image tag (always 1)",
+            container_info.clone(),
+        );
+
+        let watchdog_enabled = Family::<RouterLabels, Gauge>::default();
+        registry.register(
+            "watchdog_enabled",
+            "Whether the watchdog timer reboots the router when it hangs (1=enabled, 0=disabled)",
+            watchdog_enabled.clone(),
+        );
+        let unexpected_reboots = Family::<RebootLabels, Counter>::default();
+        registry.register(
+            "unexpected_reboots",
+            "Reboots the router didn't initiate, by reason (watchdog, kernel_failure, unclean_shutdown)",
+            unexpected_reboots.clone(),
+        );
+
+        let system_temperature_celsius =
+            Family::<HealthSensorLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "system_temperature_celsius",
+            "Temperature reported by a health sensor in degrees Celsius",
+            system_temperature_celsius.clone(),
+        );
+        let system_voltage = Family::<HealthSensorLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "system_voltage",
+            "Voltage reported by a health sensor in volts",
+            system_voltage.clone(),
+        );
+        let system_fan_rpm = Family::<HealthSensorLabels, Gauge<f64, AtomicU64>>::default();
+        registry.register(
+            "system_fan_rpm",
+            "Fan speed reported by a health sensor in revolutions per minute",
+            system_fan_rpm.clone(),
+        );
+
+        let queue_simple_max_limit_bps = Family::<QueueLabels, Gauge>::default();
+        registry.register(
+            "queue_simple_max_limit_bps",
+            "Configured max-limit of a simple queue in bits per second (0=unlimited)",
+            queue_simple_max_limit_bps.clone(),
+        );
+        let queue_simple_limit_at_bps = Family::<QueueLabels, Gauge>::default();
+        registry.register(
+            "queue_simple_limit_at_bps",
+            "Configured limit-at of a simple queue in bits per second (0=unlimited)",
+            queue_simple_limit_at_bps.clone(),
+        );
+        let queue_simple_bytes = Family::<QueueLabels, Counter>::default();
+        registry.register(
+            "queue_simple_bytes",
+            "Bytes passed through a simple queue",
+            queue_simple_bytes.clone(),
+        );
+        let queue_simple_packets = Family::<QueueLabels, Counter>::default();
+        registry.register(
+            "queue_simple_packets",
+            "Packets passed through a simple queue",
+            queue_simple_packets.clone(),
+        );
 
         Self {
             registry: Arc::new(Mutex::new(registry)),
@@ -188,33 +827,202 @@ impl MetricsRegistry {
             interface_tx_packets,
             interface_rx_errors,
             interface_tx_errors,
+            interface_rx_drops,
+            interface_tx_drops,
+            interface_rx_multicast,
+            interface_tx_multicast,
+            interface_rx_broadcast,
+            interface_tx_broadcast,
+            interface_flaps,
             interface_running,
+            interface_disabled,
+            interface_mtu,
+            interface_actual_mtu,
+            interface_queue_drops,
+            interface_queue_length,
             system_cpu_load,
+            system_cpu_count,
+            system_cpu_core_load,
             system_free_memory,
             system_total_memory,
+            system_free_hdd,
+            system_total_hdd,
+            system_hdd_usage_ratio,
             system_info,
+            router_info,
             system_uptime_seconds,
+            uptime_parse_failures,
+            system_time_offset_seconds,
+            router_clock_skew_seconds,
+            system_timezone_info,
+            license_level,
+            license_info,
+            license_deadline_timestamp_seconds,
             scrape_success,
             scrape_errors,
+            scrape_error_reason,
+            command_errors,
             scrape_duration_milliseconds,
+            scrape_duration_seconds,
+            scrape_duration_quantile_milliseconds,
             scrape_last_success_timestamp_seconds,
+            scrape_interval_too_short,
+            metric_stale,
+            router_reachable_since_seconds,
+            commands_per_scrape,
+            script_jobs_running,
+            userman_active_sessions,
+            ip_routes,
+            conntrack_distinct_sources,
+            ppp_sessions_established,
+            ppp_active_sessions,
+            ppp_session_uptime_seconds,
+            dhcp_leases_granted,
+            dhcp_leases,
             connection_consecutive_errors,
+            circuit_breaker_state,
+            login_method,
+            login_method_changes,
+            connection_resets,
+            connection_last_success_age_seconds,
+            collector_enabled,
+            collector_duration_milliseconds,
             collection_cycle_duration_milliseconds,
+            collection_in_flight,
+            metrics_encode_duration_milliseconds,
             connection_pool_size,
             connection_pool_active,
+            connection_pool_oldest_idle_seconds,
             connection_tracking_count,
+            conntrack_table_entries,
+            conntrack_table_max,
             wireguard_peer_rx_bytes,
             wireguard_peer_tx_bytes,
             wireguard_peer_latest_handshake,
             wireguard_peer_info,
+            wireguard_peers_active,
+            ipsec_peer_state,
+            ipsec_peer_rx_bytes,
+            ipsec_peer_tx_bytes,
+            ip_service_enabled,
+            ip_service_port,
+            bonding_active_slaves,
+            bonding_slave_up,
+            wifi_channel_utilization_ratio,
+            sfp_rx_power_dbm,
+            sfp_tx_power_dbm,
+            sfp_temperature_celsius,
+            sfp_supply_voltage_volts,
+            wireless_station_signal_dbm,
+            wireless_station_tx_rate_bps,
+            capsman_clients,
+            capsman_interface_clients,
+            capsman_client_signal_dbm,
+            ping_rtt_ms,
+            ping_loss_ratio,
+            queue_simple_max_limit_bps,
+            queue_simple_limit_at_bps,
+            queue_simple_bytes,
+            queue_simple_packets,
+            default_route_active,
+            tunnel_running,
+            vpn_tunnels_active,
+            vrrp_master,
+            bgp_session_state,
+            bgp_session_prefix_count,
+            bgp_session_uptime_seconds,
+            firewall_filter_bytes,
+            firewall_filter_packets,
+            container_status,
+            container_info,
+            watchdog_enabled,
+            unexpected_reboots,
+            system_temperature_celsius,
+            system_voltage,
+            system_fan_rpm,
+            custom_gauges: Arc::new(Mutex::new(HashMap::new())),
             prev_iface: Arc::new(Mutex::new(HashMap::new())),
+            prev_interface_queue_drops: Arc::new(Mutex::new(HashMap::new())),
+            prev_firewall_rules: Arc::new(Mutex::new(HashMap::new())),
+            prev_simple_queue_counters: Arc::new(Mutex::new(HashMap::new())),
+            reachable_streak_start: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            scrape_duration_windows: Arc::new(Mutex::new(HashMap::new())),
             prev_conntrack: Arc::new(Mutex::new(HashMap::new())),
             prev_system_info: Arc::new(Mutex::new(HashMap::new())),
+            prev_router_info: Arc::new(Mutex::new(HashMap::new())),
             prev_wireguard_peers: Arc::new(Mutex::new(HashMap::new())),
             prev_wireguard_peer_info: Arc::new(Mutex::new(HashMap::new())),
             conntrack_last_seen: Arc::new(Mutex::new(HashMap::new())),
             wireguard_peer_last_seen: Arc::new(Mutex::new(HashMap::new())),
             wireguard_peer_info_last_seen: Arc::new(Mutex::new(HashMap::new())),
+            prev_ipsec_peers: Arc::new(Mutex::new(HashMap::new())),
+            ipsec_peer_last_seen: Arc::new(Mutex::new(HashMap::new())),
+            prev_capsman_interfaces: Arc::new(Mutex::new(HashMap::new())),
+            capsman_interface_last_seen: Arc::new(Mutex::new(HashMap::new())),
+            prev_ip_services: RouterLabelSet::default(),
+            prev_bonds: RouterLabelSet::default(),
+            prev_bond_slaves: RouterLabelSet::default(),
+            prev_timezones: RouterLabelSet::default(),
+            prev_licenses: RouterLabelSet::default(),
+            prev_wireguard_interfaces: RouterLabelSet::default(),
+            prev_wifi_interfaces: RouterLabelSet::default(),
+            prev_sfp_interfaces: RouterLabelSet::default(),
+            prev_wireless_stations: RouterLabelSet::default(),
+            prev_managed_devices: RouterLabelSet::default(),
+            prev_capsman_clients: RouterLabelSet::default(),
+            prev_ping_targets: RouterLabelSet::default(),
+            prev_queues: RouterLabelSet::default(),
+            prev_default_routes: RouterLabelSet::default(),
+            prev_tunnels: RouterLabelSet::default(),
+            prev_vrrp: RouterLabelSet::default(),
+            prev_bgp_sessions: RouterLabelSet::default(),
+            prev_container_status: RouterLabelSet::default(),
+            prev_container_info: RouterLabelSet::default(),
+            prev_health_sensors: RouterLabelSet::default(),
+            prev_custom_metrics: RouterLabelSet::default(),
+            prev_vpn_tunnels: RouterLabelSet::default(),
+            prev_cpu_cores: RouterLabelSet::default(),
+            seen_command_errors: RouterLabelSet::default(),
+            seen_scrape_error_reasons: RouterLabelSet::default(),
+            seen_ppp_services: RouterLabelSet::default(),
+            prev_ppp_sessions: Arc::new(Mutex::new(HashMap::new())),
+            prev_ppp_services: RouterLabelSet::default(),
+            prev_ppp_users: Arc::new(Mutex::new(HashMap::new())),
+            ppp_user_last_seen: Arc::new(Mutex::new(HashMap::new())),
+            seen_reboot_reasons: RouterLabelSet::default(),
+            prev_reboot_events: Arc::new(Mutex::new(HashMap::new())),
+            seen_dhcp_servers: RouterLabelSet::default(),
+            prev_dhcp_leases: RouterLabelSet::default(),
+            prev_dhcp_bound_macs: Arc::new(Mutex::new(HashMap::new())),
+            prev_collectors: RouterLabelSet::default(),
+            prev_collector_durations: RouterLabelSet::default(),
+            wireguard_active_window: DEFAULT_WIREGUARD_ACTIVE_WINDOW,
+            scrape_duration_window: DEFAULT_SCRAPE_DURATION_WINDOW,
+            counter_mode: CounterMode::Delta,
         }
     }
+
+    /// Sets how recent a WireGuard handshake must be for the peer to count
+    /// as active
+    #[must_use]
+    pub fn with_wireguard_active_window(mut self, window: Duration) -> Self {
+        self.wireguard_active_window = window;
+        self
+    }
+
+    /// Sets how many recent scrapes per router the duration quantiles cover
+    ///
+    /// A window of 0 disables `mikrotik_scrape_duration_quantile_milliseconds`.
+    #[must_use]
+    pub fn with_scrape_duration_window(mut self, scrapes: usize) -> Self {
+        self.scrape_duration_window = scrapes;
+        self
+    }
+
+    /// Sets whether interface counters add deltas or mirror the router's values
+    #[must_use]
+    pub fn with_counter_mode(mut self, mode: CounterMode) -> Self {
+        self.counter_mode = mode;
+        self
+    }
 }
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Per-router tracking of dynamic label sets
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Labels exported for each router during its last scrape
+///
+/// Collectors whose entities come and go (services, bond slaves, ...) use this
+/// to find label sets that disappeared since the previous scrape, and to drop
+/// everything belonging to a router that was removed from the configuration.
+#[derive(Clone)]
+pub(super) struct RouterLabelSet<L> {
+    inner: Arc<Mutex<HashMap<String, HashSet<L>>>>,
+}
+
+impl<L> Default for RouterLabelSet<L> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash> RouterLabelSet<L> {
+    /// Records the labels seen for `router` and returns those that disappeared
+    pub(super) async fn replace(&self, router: &str, current: HashSet<L>) -> Vec<L> {
+        let mut map = self.inner.lock().await;
+        let prev = map.insert(router.to_string(), current);
+        let current = &map[router];
+        prev.map(|prev| prev.difference(current).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Adds labels seen for `router`, keeping those recorded earlier
+    ///
+    /// For counters, whose series must persist while the router is configured.
+    pub(super) async fn extend(&self, router: &str, labels: impl IntoIterator<Item = L>) {
+        let mut map = self.inner.lock().await;
+        map.entry(router.to_string()).or_default().extend(labels);
+    }
+
+    /// Forgets routers that are no longer configured and returns their labels
+    ///
+    /// Names of the dropped routers are added to `stale_routers`.
+    pub(super) async fn drop_inactive(
+        &self,
+        active_routers: &HashSet<String>,
+        stale_routers: &mut HashSet<String>,
+    ) -> Vec<L> {
+        let mut map = self.inner.lock().await;
+        let mut stale = Vec::new();
+        map.retain(|router, labels| {
+            if active_routers.contains(router) {
+                true
+            } else {
+                stale_routers.insert(router.clone());
+                stale.extend(labels.iter().cloned());
+                false
+            }
+        });
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replace_returns_disappeared_labels() {
+        let set = RouterLabelSet::<&str>::default();
+        assert!(
+            set.replace("r1", HashSet::from(["a", "b"]))
+                .await
+                .is_empty()
+        );
+
+        let stale = set.replace("r1", HashSet::from(["b", "c"])).await;
+        assert_eq!(stale, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_extend_keeps_earlier_labels() {
+        let set = RouterLabelSet::<&str>::default();
+        set.extend("r1", ["a"]).await;
+        set.extend("r1", ["b"]).await;
+
+        let mut stale_routers = HashSet::new();
+        let mut stale = set.drop_inactive(&HashSet::new(), &mut stale_routers).await;
+        stale.sort_unstable();
+        assert_eq!(stale, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_inactive_removes_unconfigured_routers() {
+        let set = RouterLabelSet::<&str>::default();
+        set.replace("r1", HashSet::from(["a"])).await;
+        set.replace("r2", HashSet::from(["b"])).await;
+
+        let mut stale_routers = HashSet::new();
+        let active = HashSet::from(["r1".to_string()]);
+        let stale = set.drop_inactive(&active, &mut stale_routers).await;
+
+        assert_eq!(stale, vec!["b"]);
+        assert!(stale_routers.contains("r2"));
+    }
+}
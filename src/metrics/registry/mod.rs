@@ -5,22 +5,37 @@
 
 mod cleanup;
 mod init;
+mod label_set;
 mod scrape;
 mod update;
 
+use crate::config::CounterMode;
 use crate::metrics::labels::{
-    ConntrackLabels, InterfaceLabels, RouterLabels, SystemInfoLabels, WireGuardPeerInfoLabels,
-    WireGuardPeerLabels,
+    AddressFamilyLabels, BgpSessionLabels, BondLabels, BondSlaveLabels, CapsmanClientLabels,
+    CapsmanInterfaceLabels, CollectorLabels, CommandLabels, ConntrackLabels, ContainerInfoLabels,
+    ContainerStatusLabels, CpuCoreLabels, CustomLabels, DhcpLeaseLabels, DhcpServerLabels,
+    FirewallRuleLabels, GatewayLabels, HealthSensorLabels, InterfaceLabels, IpServiceLabels,
+    IpsecPeerLabels, LicenseLabels, ManagedDeviceLabels, PingLabels, PppServiceLabels,
+    PppUserLabels, QuantileLabels, QueueLabels, RebootLabels, RouterInfoLabels, RouterLabels,
+    ScrapeErrorLabels, SystemInfoLabels, TimezoneLabels, TunnelLabels, VpnTunnelLabels,
+    WireGuardInterfaceLabels, WireGuardPeerInfoLabels, WireGuardPeerLabels, WirelessStationLabels,
 };
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::Histogram;
 use prometheus_client::registry::Registry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::AtomicU64;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+use self::label_set::RouterLabelSet;
+
+/// Gauge family of one custom collector
+type CustomGaugeFamily = Family<CustomLabels, Gauge<f64, AtomicU64>>;
+
 #[derive(Clone, Copy)]
 struct InterfaceSnapshot {
     rx_bytes: u64,
@@ -29,6 +44,13 @@ struct InterfaceSnapshot {
     tx_packets: u64,
     rx_errors: u64,
     tx_errors: u64,
+    rx_drops: u64,
+    tx_drops: u64,
+    rx_multicast: Option<u64>,
+    tx_multicast: Option<u64>,
+    rx_broadcast: Option<u64>,
+    tx_broadcast: Option<u64>,
+    running: bool,
 }
 
 #[derive(Clone)]
@@ -41,40 +63,212 @@ pub struct MetricsRegistry {
     interface_tx_packets: Family<InterfaceLabels, Counter>,
     interface_rx_errors: Family<InterfaceLabels, Counter>,
     interface_tx_errors: Family<InterfaceLabels, Counter>,
+    interface_rx_drops: Family<InterfaceLabels, Counter>,
+    interface_tx_drops: Family<InterfaceLabels, Counter>,
+    interface_rx_multicast: Family<InterfaceLabels, Counter>,
+    interface_tx_multicast: Family<InterfaceLabels, Counter>,
+    interface_rx_broadcast: Family<InterfaceLabels, Counter>,
+    interface_tx_broadcast: Family<InterfaceLabels, Counter>,
+    interface_flaps: Family<InterfaceLabels, Counter>,
     // gauges
     interface_running: Family<InterfaceLabels, Gauge>,
+    interface_disabled: Family<InterfaceLabels, Gauge>,
+    interface_mtu: Family<InterfaceLabels, Gauge>,
+    interface_actual_mtu: Family<InterfaceLabels, Gauge>,
+    interface_queue_drops: Family<InterfaceLabels, Counter>,
+    interface_queue_length: Family<InterfaceLabels, Gauge>,
     system_cpu_load: Family<RouterLabels, Gauge>,
+    system_cpu_count: Family<RouterLabels, Gauge>,
+    system_cpu_core_load: Family<CpuCoreLabels, Gauge>,
     system_free_memory: Family<RouterLabels, Gauge>,
     system_total_memory: Family<RouterLabels, Gauge>,
+    system_free_hdd: Family<RouterLabels, Gauge>,
+    system_total_hdd: Family<RouterLabels, Gauge>,
+    system_hdd_usage_ratio: Family<RouterLabels, Gauge<f64, AtomicU64>>,
     system_info: Family<SystemInfoLabels, Gauge>,
+    router_info: Family<RouterInfoLabels, Gauge>,
     system_uptime_seconds: Family<RouterLabels, Gauge>,
+    uptime_parse_failures: Family<RouterLabels, Counter>,
+    system_time_offset_seconds: Family<RouterLabels, Gauge>,
+    router_clock_skew_seconds: Family<RouterLabels, Gauge>,
+    system_timezone_info: Family<TimezoneLabels, Gauge>,
+    license_level: Family<RouterLabels, Gauge>,
+    license_info: Family<LicenseLabels, Gauge>,
+    license_deadline_timestamp_seconds: Family<RouterLabels, Gauge>,
     // scrape status counters
     scrape_success: Family<RouterLabels, Counter>,
     scrape_errors: Family<RouterLabels, Counter>,
+    scrape_error_reason: Family<ScrapeErrorLabels, Counter>,
+    command_errors: Family<CommandLabels, Counter>,
     // scrape timing metrics
     scrape_duration_milliseconds: Family<RouterLabels, Gauge>,
+    scrape_duration_seconds: Family<RouterLabels, Histogram, fn() -> Histogram>,
+    scrape_duration_quantile_milliseconds: Family<QuantileLabels, Gauge<f64, AtomicU64>>,
     scrape_last_success_timestamp_seconds: Family<RouterLabels, Gauge>,
+    scrape_interval_too_short: Family<RouterLabels, Gauge>,
+    metric_stale: Family<RouterLabels, Gauge>,
+    router_reachable_since_seconds: Family<RouterLabels, Gauge>,
+    commands_per_scrape: Family<RouterLabels, Gauge>,
+    script_jobs_running: Family<RouterLabels, Gauge>,
+    userman_active_sessions: Family<RouterLabels, Gauge>,
+    ip_routes: Family<AddressFamilyLabels, Gauge>,
+    conntrack_distinct_sources: Family<RouterLabels, Gauge>,
+    ppp_sessions_established: Family<PppServiceLabels, Counter>,
+    ppp_active_sessions: Family<PppServiceLabels, Gauge>,
+    ppp_session_uptime_seconds: Family<PppUserLabels, Gauge>,
+    dhcp_leases_granted: Family<DhcpServerLabels, Counter>,
+    dhcp_leases: Family<DhcpLeaseLabels, Gauge>,
     connection_consecutive_errors: Family<RouterLabels, Gauge>,
+    circuit_breaker_state: Family<RouterLabels, Gauge>,
+    login_method: Family<RouterLabels, Gauge>,
+    login_method_changes: Family<RouterLabels, Counter>,
+    connection_resets: Family<RouterLabels, Counter>,
+    connection_last_success_age_seconds: Family<RouterLabels, Gauge>,
+    collector_enabled: Family<CollectorLabels, Gauge>,
+    collector_duration_milliseconds: Family<CollectorLabels, Gauge<f64, AtomicU64>>,
     collection_cycle_duration_milliseconds: Gauge,
+    collection_in_flight: Gauge,
+    metrics_encode_duration_milliseconds: Gauge<f64, AtomicU64>,
     // connection pool metrics
     connection_pool_size: Gauge,
     connection_pool_active: Gauge,
+    connection_pool_oldest_idle_seconds: Gauge,
     // connection tracking metrics
     connection_tracking_count: Family<ConntrackLabels, Gauge>,
+    conntrack_table_entries: Family<RouterLabels, Gauge>,
+    conntrack_table_max: Family<RouterLabels, Gauge>,
     // WireGuard metrics
     wireguard_peer_rx_bytes: Family<WireGuardPeerLabels, Gauge>,
     wireguard_peer_tx_bytes: Family<WireGuardPeerLabels, Gauge>,
     wireguard_peer_latest_handshake: Family<WireGuardPeerLabels, Gauge>,
     wireguard_peer_info: Family<WireGuardPeerInfoLabels, Gauge>,
+    wireguard_peers_active: Family<WireGuardInterfaceLabels, Gauge>,
+    // IPsec metrics
+    ipsec_peer_state: Family<IpsecPeerLabels, Gauge>,
+    ipsec_peer_rx_bytes: Family<IpsecPeerLabels, Gauge>,
+    ipsec_peer_tx_bytes: Family<IpsecPeerLabels, Gauge>,
+    // management service metrics
+    ip_service_enabled: Family<IpServiceLabels, Gauge>,
+    ip_service_port: Family<IpServiceLabels, Gauge>,
+    // bonding metrics
+    bonding_active_slaves: Family<BondLabels, Gauge>,
+    bonding_slave_up: Family<BondSlaveLabels, Gauge>,
+    // wireless metrics
+    wifi_channel_utilization_ratio: Family<InterfaceLabels, Gauge<f64, AtomicU64>>,
+    sfp_rx_power_dbm: Family<InterfaceLabels, Gauge<f64, AtomicU64>>,
+    sfp_tx_power_dbm: Family<InterfaceLabels, Gauge<f64, AtomicU64>>,
+    sfp_temperature_celsius: Family<InterfaceLabels, Gauge<f64, AtomicU64>>,
+    sfp_supply_voltage_volts: Family<InterfaceLabels, Gauge<f64, AtomicU64>>,
+    wireless_station_signal_dbm: Family<WirelessStationLabels, Gauge>,
+    wireless_station_tx_rate_bps: Family<WirelessStationLabels, Gauge>,
+    capsman_clients: Family<ManagedDeviceLabels, Gauge>,
+    capsman_interface_clients: Family<CapsmanInterfaceLabels, Gauge>,
+    capsman_client_signal_dbm: Family<CapsmanClientLabels, Gauge>,
+    // ping metrics
+    ping_rtt_ms: Family<PingLabels, Gauge<f64, AtomicU64>>,
+    ping_loss_ratio: Family<PingLabels, Gauge<f64, AtomicU64>>,
+    // queue metrics
+    queue_simple_max_limit_bps: Family<QueueLabels, Gauge>,
+    queue_simple_limit_at_bps: Family<QueueLabels, Gauge>,
+    queue_simple_bytes: Family<QueueLabels, Counter>,
+    queue_simple_packets: Family<QueueLabels, Counter>,
+    // route metrics
+    default_route_active: Family<GatewayLabels, Gauge>,
+    tunnel_running: Family<TunnelLabels, Gauge>,
+    vpn_tunnels_active: Family<VpnTunnelLabels, Gauge>,
+    vrrp_master: Family<InterfaceLabels, Gauge>,
+    // bgp metrics
+    bgp_session_state: Family<BgpSessionLabels, Gauge>,
+    bgp_session_prefix_count: Family<BgpSessionLabels, Gauge>,
+    bgp_session_uptime_seconds: Family<BgpSessionLabels, Gauge>,
+    // firewall metrics
+    firewall_filter_bytes: Family<FirewallRuleLabels, Counter>,
+    firewall_filter_packets: Family<FirewallRuleLabels, Counter>,
+    // container metrics
+    container_status: Family<ContainerStatusLabels, Gauge>,
+    container_info: Family<ContainerInfoLabels, Gauge>,
+    // watchdog metrics
+    watchdog_enabled: Family<RouterLabels, Gauge>,
+    unexpected_reboots: Family<RebootLabels, Counter>,
+    // health sensor metrics
+    system_temperature_celsius: Family<HealthSensorLabels, Gauge<f64, AtomicU64>>,
+    system_voltage: Family<HealthSensorLabels, Gauge<f64, AtomicU64>>,
+    system_fan_rpm: Family<HealthSensorLabels, Gauge<f64, AtomicU64>>,
+    /// `mikrotik_custom_<name>` gauges, registered when a custom collector
+    /// first reports an entry
+    custom_gauges: Arc<Mutex<HashMap<String, CustomGaugeFamily>>>,
     prev_iface: Arc<Mutex<HashMap<InterfaceLabels, InterfaceSnapshot>>>,
+    prev_interface_queue_drops: Arc<Mutex<HashMap<InterfaceLabels, u64>>>,
+    /// Last seen (bytes, packets) per firewall rule
+    prev_firewall_rules: Arc<Mutex<HashMap<FirewallRuleLabels, (u64, u64)>>>,
+    /// Last seen (bytes, packets) per simple queue and direction
+    prev_simple_queue_counters: Arc<Mutex<HashMap<QueueLabels, (u64, u64)>>>,
+    /// Unix timestamp at which each router's current run of successful scrapes began
+    ///
+    /// A std mutex because scrape results are recorded outside async code.
+    reachable_streak_start: Arc<std::sync::Mutex<HashMap<String, u64>>>,
+    /// Most recent scrape durations per router, in milliseconds
+    scrape_duration_windows: Arc<Mutex<HashMap<String, VecDeque<f64>>>>,
     prev_conntrack: Arc<Mutex<HashMap<String, HashSet<ConntrackLabels>>>>,
     prev_system_info: Arc<Mutex<HashMap<String, SystemInfoLabels>>>,
+    prev_router_info: Arc<Mutex<HashMap<String, RouterInfoLabels>>>,
     prev_wireguard_peers: Arc<Mutex<HashMap<String, HashSet<WireGuardPeerLabels>>>>,
     prev_wireguard_peer_info:
         Arc<Mutex<HashMap<String, HashMap<WireGuardPeerLabels, WireGuardPeerInfoLabels>>>>,
     conntrack_last_seen: Arc<Mutex<HashMap<ConntrackLabels, Instant>>>,
     wireguard_peer_last_seen: Arc<Mutex<HashMap<WireGuardPeerLabels, Instant>>>,
     wireguard_peer_info_last_seen: Arc<Mutex<HashMap<WireGuardPeerInfoLabels, Instant>>>,
+    prev_ipsec_peers: Arc<Mutex<HashMap<String, HashSet<IpsecPeerLabels>>>>,
+    ipsec_peer_last_seen: Arc<Mutex<HashMap<IpsecPeerLabels, Instant>>>,
+    prev_capsman_interfaces: Arc<Mutex<HashMap<String, HashSet<CapsmanInterfaceLabels>>>>,
+    capsman_interface_last_seen: Arc<Mutex<HashMap<CapsmanInterfaceLabels, Instant>>>,
+    prev_ip_services: RouterLabelSet<IpServiceLabels>,
+    prev_bonds: RouterLabelSet<BondLabels>,
+    prev_bond_slaves: RouterLabelSet<BondSlaveLabels>,
+    prev_timezones: RouterLabelSet<TimezoneLabels>,
+    prev_licenses: RouterLabelSet<LicenseLabels>,
+    prev_wireguard_interfaces: RouterLabelSet<WireGuardInterfaceLabels>,
+    prev_wifi_interfaces: RouterLabelSet<InterfaceLabels>,
+    prev_sfp_interfaces: RouterLabelSet<InterfaceLabels>,
+    prev_wireless_stations: RouterLabelSet<WirelessStationLabels>,
+    prev_managed_devices: RouterLabelSet<ManagedDeviceLabels>,
+    prev_capsman_clients: RouterLabelSet<CapsmanClientLabels>,
+    prev_ping_targets: RouterLabelSet<PingLabels>,
+    prev_queues: RouterLabelSet<QueueLabels>,
+    prev_default_routes: RouterLabelSet<GatewayLabels>,
+    prev_tunnels: RouterLabelSet<TunnelLabels>,
+    prev_vrrp: RouterLabelSet<InterfaceLabels>,
+    prev_bgp_sessions: RouterLabelSet<BgpSessionLabels>,
+    prev_container_status: RouterLabelSet<ContainerStatusLabels>,
+    prev_container_info: RouterLabelSet<ContainerInfoLabels>,
+    prev_health_sensors: RouterLabelSet<HealthSensorLabels>,
+    /// Custom collector entries exported per router, keyed by collector name
+    prev_custom_metrics: RouterLabelSet<(String, CustomLabels)>,
+    prev_vpn_tunnels: RouterLabelSet<VpnTunnelLabels>,
+    prev_cpu_cores: RouterLabelSet<CpuCoreLabels>,
+    seen_command_errors: RouterLabelSet<CommandLabels>,
+    seen_scrape_error_reasons: RouterLabelSet<ScrapeErrorLabels>,
+    seen_ppp_services: RouterLabelSet<PppServiceLabels>,
+    /// PPP sessions per router seen in the last scrape, to tell new ones apart
+    prev_ppp_sessions: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    prev_ppp_services: RouterLabelSet<PppServiceLabels>,
+    prev_ppp_users: Arc<Mutex<HashMap<String, HashSet<PppUserLabels>>>>,
+    ppp_user_last_seen: Arc<Mutex<HashMap<PppUserLabels, Instant>>>,
+    seen_reboot_reasons: RouterLabelSet<RebootLabels>,
+    /// Unexpected reboot log entries of the previous scrape per router
+    prev_reboot_events: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    seen_dhcp_servers: RouterLabelSet<DhcpServerLabels>,
+    prev_dhcp_leases: RouterLabelSet<DhcpLeaseLabels>,
+    /// MAC addresses with a bound lease per router in the last scrape
+    prev_dhcp_bound_macs: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    prev_collectors: RouterLabelSet<CollectorLabels>,
+    prev_collector_durations: RouterLabelSet<CollectorLabels>,
+    /// Peers with a handshake within this window count as active
+    wireguard_active_window: Duration,
+    /// Scrapes per router the duration quantiles are computed over (0 disables them)
+    scrape_duration_window: usize,
+    /// How interface counters follow the router's values
+    counter_mode: CounterMode,
 }
 
 impl Default for MetricsRegistry {
@@ -86,7 +280,15 @@ impl Default for MetricsRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mikrotik::{ConnectionTrackingStats, InterfaceStats, RouterMetrics, SystemResource};
+    use crate::mikrotik::{
+        BgpSessionStats, BondSlaveStats, BondStats, CapsmanClientStats, CircuitState, ClockStats,
+        ConnectionTrackingStats, ConntrackTableStats, ContainerStats, CpuCoreStats, CustomMetric,
+        DefaultRouteStats, DhcpLeaseStats, FirewallRuleStats, HealthSensor, HealthStats,
+        InterfaceQueueStats, InterfaceStats, IpServiceStats, IpsecPeerStats, LicenseStats,
+        LoginMethod, ManagedDeviceStats, PingStats, PppSessionStats, QueueStats, RouterMetrics,
+        SfpStats, SystemResource, TunnelStats, UnexpectedReboot, VrrpStats, WifiChannelStats,
+        WireGuardInterfaceStats, WireGuardPeerStats, WirelessStationStats,
+    };
 
     fn make_router_metrics(
         router_name: &str,
@@ -96,10 +298,9 @@ mod tests {
         RouterMetrics {
             router_name: router_name.to_string(),
             interfaces,
-            system,
-            connection_tracking: Vec::new(),
-            wireguard_interfaces: Vec::new(),
-            wireguard_peers: Vec::new(),
+            system: Some(system),
+            commands_executed: 6,
+            ..Default::default()
         }
     }
 
@@ -136,7 +337,16 @@ mod tests {
             tx_packets,
             rx_errors,
             tx_errors,
+            rx_drops: 0,
+            tx_drops: 0,
             running,
+            disabled: false,
+            mtu: None,
+            actual_mtu: None,
+            rx_multicast: None,
+            tx_multicast: None,
+            rx_broadcast: None,
+            tx_broadcast: None,
         }
     }
 
@@ -144,10 +354,16 @@ mod tests {
         SystemResource {
             uptime: uptime.to_string(),
             cpu_load: 10,
+            cpu_count: None,
             free_memory: 1024 * 1024 * 512,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: version.to_string(),
             board_name: board_name.to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         }
     }
 
@@ -227,6 +443,145 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_absolute_counter_mode_mirrors_router_counters() {
+        let registry = MetricsRegistry::new().with_counter_mode(CounterMode::Absolute);
+        let labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "ether1".to_string(),
+        };
+        let scrape = |rx_bytes| {
+            make_router_metrics(
+                "router1",
+                vec![make_interface("ether1", rx_bytes, 0, 0, 0, 0, 0, true)],
+                make_system("7.10", "RB750Gr3", "1d"),
+            )
+        };
+
+        // Traffic from before the exporter started is kept
+        registry.update_metrics(&scrape(1000)).await;
+        assert_eq!(
+            registry.interface_rx_bytes.get_or_create(&labels).get(),
+            1000
+        );
+        registry.update_metrics(&scrape(1500)).await;
+        assert_eq!(
+            registry.interface_rx_bytes.get_or_create(&labels).get(),
+            1500
+        );
+
+        // The router reset its counter and counted new traffic since
+        registry.update_metrics(&scrape(200)).await;
+        assert_eq!(
+            registry.interface_rx_bytes.get_or_create(&labels).get(),
+            200
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interface_drops_are_separate_from_errors() {
+        let registry = MetricsRegistry::new();
+        let mut iface = make_interface("ether1", 1000, 2000, 10, 20, 1, 0, true);
+        iface.rx_drops = 100;
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        registry
+            .update_metrics(&make_router_metrics(
+                "router1",
+                vec![iface.clone()],
+                system.clone(),
+            ))
+            .await;
+
+        iface.rx_drops = 150;
+        iface.tx_drops = 4;
+        registry
+            .update_metrics(&make_router_metrics("router1", vec![iface], system))
+            .await;
+
+        let labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "ether1".to_string(),
+        };
+        assert_eq!(registry.interface_rx_drops.get_or_create(&labels).get(), 50);
+        assert_eq!(registry.interface_tx_drops.get_or_create(&labels).get(), 4);
+        assert_eq!(registry.interface_rx_errors.get_or_create(&labels).get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_interface_broadcast_storm_is_counted_as_delta() {
+        let registry = MetricsRegistry::new();
+        let mut iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
+        iface.rx_broadcast = Some(1_000);
+        iface.rx_multicast = Some(50);
+        let mut bridge = make_interface("bridge", 0, 0, 0, 0, 0, 0, true);
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        registry
+            .update_metrics(&make_router_metrics(
+                "router1",
+                vec![iface.clone(), bridge.clone()],
+                system.clone(),
+            ))
+            .await;
+
+        iface.rx_broadcast = Some(901_000);
+        iface.rx_multicast = Some(60);
+        bridge.rx_bytes = 10;
+        registry
+            .update_metrics(&make_router_metrics("router1", vec![iface, bridge], system))
+            .await;
+
+        let labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "ether1".to_string(),
+        };
+        assert_eq!(
+            registry.interface_rx_broadcast.get_or_create(&labels).get(),
+            900_000
+        );
+        assert_eq!(
+            registry.interface_rx_multicast.get_or_create(&labels).get(),
+            10
+        );
+
+        // Interfaces without ethernet statistics get no series
+        let bridge_labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "bridge".to_string(),
+        };
+        assert!(
+            registry
+                .interface_rx_broadcast
+                .get(&bridge_labels)
+                .is_none()
+        );
+        assert!(registry.interface_tx_broadcast.get(&labels).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interface_flaps_count_running_transitions() {
+        let registry = MetricsRegistry::new();
+        let mut iface = make_interface("sfp1", 0, 0, 0, 0, 0, 0, false);
+        let system = make_system("7.10", "CCR2004", "1d");
+        let labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "sfp1".to_string(),
+        };
+
+        // An interface that is down on the first scrape has not flapped
+        for running in [false, true, true, false, true] {
+            iface.running = running;
+            registry
+                .update_metrics(&make_router_metrics(
+                    "router1",
+                    vec![iface.clone()],
+                    system.clone(),
+                ))
+                .await;
+        }
+
+        assert_eq!(registry.interface_flaps.get_or_create(&labels).get(), 3);
+    }
+
     #[tokio::test]
     async fn test_update_metrics_counter_reset() {
         let registry = MetricsRegistry::new();
@@ -314,6 +669,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_metric_stale_tracks_last_scrape_result() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.record_scrape_success(&labels);
+        assert_eq!(registry.metric_stale.get_or_create(&labels).get(), 0);
+        registry.record_scrape_error(&labels);
+        assert_eq!(registry.metric_stale.get_or_create(&labels).get(), 1);
+        registry.record_scrape_success(&labels);
+        assert_eq!(registry.metric_stale.get_or_create(&labels).get(), 0);
+    }
+
+    #[test]
+    fn test_reachable_since_resets_on_failure() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.record_scrape_success(&labels);
+        assert_eq!(
+            registry
+                .router_reachable_since_seconds
+                .get_or_create(&labels)
+                .get(),
+            0
+        );
+        // Pretend the streak began a minute ago
+        let started = registry.reachable_streak_start.lock().unwrap()["router1"];
+        registry
+            .reachable_streak_start
+            .lock()
+            .unwrap()
+            .insert("router1".to_string(), started - 60);
+        registry.record_scrape_success(&labels);
+        assert!(
+            registry
+                .router_reachable_since_seconds
+                .get_or_create(&labels)
+                .get()
+                >= 60
+        );
+
+        registry.record_scrape_error(&labels);
+        assert_eq!(
+            registry
+                .router_reachable_since_seconds
+                .get_or_create(&labels)
+                .get(),
+            0
+        );
+        registry.record_scrape_success(&labels);
+        assert!(
+            registry
+                .router_reachable_since_seconds
+                .get_or_create(&labels)
+                .get()
+                < 60
+        );
+    }
+
     #[test]
     fn test_record_scrape_success_increments() {
         let registry = MetricsRegistry::new();
@@ -342,17 +761,103 @@ mod tests {
         assert_eq!(registry.scrape_errors.get_or_create(&labels).get(), 2);
     }
 
+    #[tokio::test]
+    async fn test_scrape_duration_quantiles_use_recent_window() {
+        let registry = MetricsRegistry::new().with_scrape_duration_window(10);
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+        let quantile = |q: &str| {
+            registry
+                .scrape_duration_quantile_milliseconds
+                .get_or_create(&QuantileLabels {
+                    router: "router1".to_string(),
+                    quantile: q.to_string(),
+                })
+                .get()
+        };
+
+        // An early outlier that falls out of the window
+        registry.record_scrape_duration(&labels, 5.0).await;
+        for ms in 1..=10 {
+            registry
+                .record_scrape_duration(&labels, f64::from(ms) / 1000.0)
+                .await;
+        }
+
+        assert!((quantile("0.5") - 5.0).abs() < 1e-9);
+        assert!((quantile("0.9") - 9.0).abs() < 1e-9);
+        assert!((quantile("0.99") - 10.0).abs() < 1e-9);
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_scrape_duration_quantile_milliseconds{"));
+    }
+
+    #[tokio::test]
+    async fn test_scrape_duration_quantiles_disabled_with_empty_window() {
+        let registry = MetricsRegistry::new().with_scrape_duration_window(0);
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.record_scrape_duration(&labels, 0.5).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_scrape_duration_quantile_milliseconds{"));
+    }
+
+    #[tokio::test]
+    async fn test_scrape_duration_histogram() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.record_scrape_duration(&labels, 0.2).await;
+        registry.record_scrape_duration(&labels, 3.0).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_scrape_duration_seconds_count{router=\"router1\"} 2"));
+        assert!(
+            encoded.contains(
+                "mikrotik_scrape_duration_seconds_bucket{le=\"0.25\",router=\"router1\"} 1"
+            )
+        );
+        assert!(
+            encoded.contains(
+                "mikrotik_scrape_duration_seconds_bucket{le=\"5.0\",router=\"router1\"} 2"
+            )
+        );
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_scrape_duration_seconds_count{"));
+    }
+
     #[test]
     fn test_update_pool_stats_sets_gauges() {
         let registry = MetricsRegistry::new();
 
-        registry.update_pool_stats(10, 5);
+        registry.update_pool_stats(10, 5, Duration::from_secs(42));
         assert_eq!(registry.connection_pool_size.get(), 10);
         assert_eq!(registry.connection_pool_active.get(), 5);
+        assert_eq!(registry.connection_pool_oldest_idle_seconds.get(), 42);
 
-        registry.update_pool_stats(20, 8);
+        registry.update_pool_stats(20, 8, Duration::from_secs(290));
         assert_eq!(registry.connection_pool_size.get(), 20);
         assert_eq!(registry.connection_pool_active.get(), 8);
+        assert_eq!(registry.connection_pool_oldest_idle_seconds.get(), 290);
+    }
+
+    #[tokio::test]
+    async fn test_encode_metrics_records_encode_duration() {
+        let registry = MetricsRegistry::new();
+        let first = registry.encode_metrics().await.unwrap();
+        assert!(first.contains("mikrotik_metrics_encode_duration_milliseconds 0.0"));
+
+        registry.encode_metrics().await.unwrap();
+        assert!(registry.metrics_encode_duration_milliseconds.get() > 0.0);
     }
 
     #[test]
@@ -366,6 +871,17 @@ mod tests {
         assert_eq!(registry.collection_cycle_duration_milliseconds.get(), 1234);
     }
 
+    #[test]
+    fn test_collection_in_flight_tracks_running_scrapes() {
+        let registry = MetricsRegistry::new();
+
+        registry.collection_started();
+        registry.collection_started();
+        assert_eq!(registry.collection_in_flight.get(), 2);
+        registry.collection_finished();
+        assert_eq!(registry.collection_in_flight.get(), 1);
+    }
+
     #[test]
     fn test_update_connection_errors_sets_gauge() {
         let registry = MetricsRegistry::new();
@@ -392,6 +908,72 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_environment_label_on_every_series() {
+        let registry = MetricsRegistry::with_environment("staging");
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
+        let metrics = make_router_metrics("router1", vec![iface], system);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        let series: Vec<&str> = encoded
+            .lines()
+            .filter(|line| line.starts_with("mikrotik_"))
+            .collect();
+        assert!(!series.is_empty());
+        for line in series {
+            assert!(
+                line.contains("env=\"staging\""),
+                "missing env label: {line}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metric_prefix_on_every_metric() {
+        let registry = MetricsRegistry::with_prefix("mikrotik_lab", None);
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
+        let mut metrics = make_router_metrics("router1", vec![iface], system);
+        metrics.custom_metrics = vec![CustomMetric {
+            collector: "graphing".to_string(),
+            labels: Vec::new(),
+            value: 1.0,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_lab_interface_rx_bytes_total{"));
+        assert!(encoded.contains("# TYPE mikrotik_lab_custom_graphing gauge"));
+        for line in encoded.lines().filter(|line| !line.starts_with('#')) {
+            assert!(
+                line.starts_with("mikrotik_lab_"),
+                "unprefixed series: {line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_circuit_breaker_state_sets_gauge() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.update_circuit_breaker_state(&labels, CircuitState::Open);
+        assert_eq!(
+            registry.circuit_breaker_state.get_or_create(&labels).get(),
+            2
+        );
+
+        registry.update_circuit_breaker_state(&labels, CircuitState::Closed);
+        assert_eq!(
+            registry.circuit_breaker_state.get_or_create(&labels).get(),
+            0
+        );
+    }
+
     #[tokio::test]
     async fn test_interface_labels_with_metrics() {
         let registry = MetricsRegistry::new();
@@ -417,6 +999,58 @@ mod tests {
         assert_eq!(registry.interface_running.get_or_create(&labels2).get(), 0);
     }
 
+    #[tokio::test]
+    async fn test_interface_disabled_distinct_from_running() {
+        let registry = MetricsRegistry::new();
+
+        let mut disabled = make_interface("ether1", 0, 0, 0, 0, 0, 0, false);
+        disabled.disabled = true;
+        let unplugged = make_interface("ether2", 0, 0, 0, 0, 0, 0, false);
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let metrics = make_router_metrics("router1", vec![disabled, unplugged], system);
+        registry.update_metrics(&metrics).await;
+
+        let labels1 = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "ether1".to_string(),
+        };
+        let labels2 = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "ether2".to_string(),
+        };
+
+        assert_eq!(registry.interface_disabled.get_or_create(&labels1).get(), 1);
+        assert_eq!(registry.interface_running.get_or_create(&labels1).get(), 0);
+        assert_eq!(registry.interface_disabled.get_or_create(&labels2).get(), 0);
+        assert_eq!(registry.interface_running.get_or_create(&labels2).get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_interface_mtu_exported_when_reported() {
+        let registry = MetricsRegistry::new();
+
+        let mut pppoe = make_interface("pppoe-out1", 0, 0, 0, 0, 0, 0, true);
+        pppoe.mtu = Some(1500);
+        pppoe.actual_mtu = Some(1480);
+        let bridge = make_interface("bridge", 0, 0, 0, 0, 0, 0, true);
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let metrics = make_router_metrics("router1", vec![pppoe, bridge], system);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_interface_mtu{router=\"router1\",interface=\"pppoe-out1\"} 1500"
+            )
+        );
+        assert!(encoded.contains(
+            "mikrotik_interface_actual_mtu{router=\"router1\",interface=\"pppoe-out1\"} 1480"
+        ));
+        assert!(
+            !encoded.contains("mikrotik_interface_mtu{router=\"router1\",interface=\"bridge\"}")
+        );
+    }
+
     #[tokio::test]
     async fn test_system_metrics_gauge_values() {
         let registry = MetricsRegistry::new();
@@ -424,10 +1058,16 @@ mod tests {
         let system = SystemResource {
             uptime: "1d2h3m4s".to_string(),
             cpu_load: 50,
+            cpu_count: Some(4),
             free_memory: 512 * 1024 * 1024,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: "7.10".to_string(),
             board_name: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         };
         let metrics = make_router_metrics("router1", vec![iface], system);
         registry.update_metrics(&metrics).await;
@@ -440,6 +1080,10 @@ mod tests {
             registry.system_cpu_load.get_or_create(&router_label).get(),
             50
         );
+        assert_eq!(
+            registry.system_cpu_count.get_or_create(&router_label).get(),
+            4
+        );
         assert_eq!(
             registry
                 .system_free_memory
@@ -454,46 +1098,1461 @@ mod tests {
                 .get(),
             1024 * 1024 * 1024
         );
+        assert_eq!(
+            registry.system_free_hdd.get_or_create(&router_label).get(),
+            64 * 1024 * 1024
+        );
+        assert_eq!(
+            registry.system_total_hdd.get_or_create(&router_label).get(),
+            128 * 1024 * 1024
+        );
+        assert!(
+            (registry
+                .system_hdd_usage_ratio
+                .get_or_create(&router_label)
+                .get()
+                - 0.5)
+                .abs()
+                < f64::EPSILON
+        );
     }
 
     #[tokio::test]
-    async fn test_connection_tracking_multi_router() {
+    async fn test_commands_per_scrape_reflects_enabled_collectors() {
         let registry = MetricsRegistry::new();
-        let iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
-        let system = make_system("7.10", "RB750Gr3", "1d2h3m4s");
+        let system = make_system("7.10", "RB750Gr3", "1d");
 
-        // First update for router1 with TCP connections
-        let mut metrics1 = make_router_metrics("router1", vec![iface.clone()], system.clone());
-        metrics1.connection_tracking = vec![
-            make_conntrack("192.168.1.1", "tcp", 100, "ipv4"),
-            make_conntrack("192.168.1.1", "udp", 50, "ipv4"),
-        ];
-        registry.update_metrics(&metrics1).await;
+        let full = make_router_metrics("full", Vec::new(), system.clone());
+        let mut minimal = make_router_metrics("minimal", Vec::new(), system);
+        minimal.commands_executed = 1;
+        registry.update_metrics(&full).await;
+        registry.update_metrics(&minimal).await;
 
-        // First update for router2 with different connections
-        let mut metrics2 = make_router_metrics("router2", vec![iface.clone()], system.clone());
-        metrics2.connection_tracking = vec![
-            make_conntrack("10.0.0.1", "tcp", 200, "ipv4"),
-            make_conntrack("10.0.0.1", "icmp", 10, "ipv4"),
+        let full_label = RouterLabels {
+            router: "full".to_string(),
+        };
+        let minimal_label = RouterLabels {
+            router: "minimal".to_string(),
+        };
+        assert!(
+            registry
+                .commands_per_scrape
+                .get_or_create(&full_label)
+                .get()
+                > registry
+                    .commands_per_scrape
+                    .get_or_create(&minimal_label)
+                    .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ip_service_metrics_flag_enabled_telnet() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.ip_services = vec![
+            IpServiceStats {
+                name: "telnet".to_string(),
+                port: 23,
+                enabled: true,
+            },
+            IpServiceStats {
+                name: "ssh".to_string(),
+                port: 2222,
+                enabled: false,
+            },
         ];
-        registry.update_metrics(&metrics2).await;
+        registry.update_metrics(&metrics).await;
 
-        // Check that both routers have their metrics
-        let labels1_tcp = ConntrackLabels {
+        let telnet = IpServiceLabels {
             router: "router1".to_string(),
-            src_address: "192.168.1.1".to_string(),
-            protocol: "tcp".to_string(),
-            ip_version: "ipv4".to_string(),
+            service: "telnet".to_string(),
         };
-        let labels1_udp = ConntrackLabels {
+        let ssh = IpServiceLabels {
             router: "router1".to_string(),
-            src_address: "192.168.1.1".to_string(),
-            protocol: "udp".to_string(),
-            ip_version: "ipv4".to_string(),
+            service: "ssh".to_string(),
         };
-        let labels2_tcp = ConntrackLabels {
-            router: "router2".to_string(),
-            src_address: "10.0.0.1".to_string(),
+        assert_eq!(registry.ip_service_enabled.get_or_create(&telnet).get(), 1);
+        assert_eq!(registry.ip_service_enabled.get_or_create(&ssh).get(), 0);
+        assert_eq!(registry.ip_service_port.get_or_create(&ssh).get(), 2222);
+
+        // A service that disappears from the router is removed from the output
+        metrics.ip_services.truncate(1);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("service=\"telnet\""));
+        assert!(!encoded.contains("service=\"ssh\""));
+    }
+
+    #[tokio::test]
+    async fn test_wireguard_peers_active_counts_recent_handshakes() {
+        let registry =
+            MetricsRegistry::new().with_wireguard_active_window(Duration::from_secs(180));
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let peer = |address: &str, latest_handshake: Option<u64>| WireGuardPeerStats {
+            interface: "wg0".to_string(),
+            name: address.to_string(),
+            allowed_address: address.to_string(),
+            public_key: None,
+            endpoint: None,
+            rx_bytes: 0,
+            tx_bytes: 0,
+            latest_handshake,
+        };
+        metrics.wireguard_interfaces = vec![
+            WireGuardInterfaceStats {
+                name: "wg0".to_string(),
+                enabled: true,
+            },
+            WireGuardInterfaceStats {
+                name: "wg1".to_string(),
+                enabled: true,
+            },
+        ];
+        metrics.wireguard_peers = vec![
+            peer("10.0.0.2/32", Some(now - 30)),
+            peer("10.0.0.3/32", Some(now - 3600)),
+            peer("10.0.0.4/32", None),
+        ];
+        registry.update_metrics(&metrics).await;
+
+        let wg0 = WireGuardInterfaceLabels {
+            router: "router1".to_string(),
+            interface: "wg0".to_string(),
+        };
+        let wg1 = WireGuardInterfaceLabels {
+            router: "router1".to_string(),
+            interface: "wg1".to_string(),
+        };
+        assert_eq!(registry.wireguard_peers_active.get_or_create(&wg0).get(), 1);
+        assert_eq!(registry.wireguard_peers_active.get_or_create(&wg1).get(), 0);
+
+        // Removed interfaces disappear from the output
+        metrics.wireguard_interfaces.truncate(1);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_wireguard_peers_active{router=\"router1\",interface=\"wg0\"} 1"
+            )
+        );
+        assert!(!encoded.contains("interface=\"wg1\""));
+    }
+
+    #[tokio::test]
+    async fn test_wireguard_peers_keyed_by_public_key() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.wireguard_peers = vec![WireGuardPeerStats {
+            interface: "wg0".to_string(),
+            name: "laptop".to_string(),
+            allowed_address: "10.0.0.2/32".to_string(),
+            public_key: Some("bWlrcm90aWs=".to_string()),
+            endpoint: None,
+            rx_bytes: 1024,
+            tx_bytes: 0,
+            latest_handshake: None,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_wireguard_peer_rx_bytes{router=\"router1\",interface=\"wg0\",public_key=\"bWlrcm90aWs=\"} 1024"
+        ));
+        assert!(!encoded.contains("allowed_address="));
+    }
+
+    #[tokio::test]
+    async fn test_command_errors_counted_per_path() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.command_errors = vec!["/interface/wifi/print".to_string()];
+        registry.update_metrics(&metrics).await;
+        registry.update_metrics(&metrics).await;
+
+        let labels = CommandLabels {
+            router: "router1".to_string(),
+            command: "/interface/wifi/print".to_string(),
+        };
+        assert_eq!(registry.command_errors.get_or_create(&labels).get(), 2);
+
+        // The series persists after the command starts succeeding
+        metrics.command_errors.clear();
+        registry.update_metrics(&metrics).await;
+        assert_eq!(registry.command_errors.get_or_create(&labels).get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ipsec_peer_reset_then_expired() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("hub", Vec::new(), system);
+        metrics.ipsec_peers = vec![IpsecPeerStats {
+            remote_address: "203.0.113.10".to_string(),
+            state: "established".to_string(),
+            uptime: "1h".to_string(),
+            rx_bytes: 2048,
+            tx_bytes: 1024,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let labels = "{router=\"hub\",peer=\"203.0.113.10\"}";
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("mikrotik_ipsec_peer_state{labels} 1")));
+        assert!(encoded.contains(&format!("mikrotik_ipsec_peer_rx_bytes{labels} 2048")));
+        assert!(encoded.contains(&format!("mikrotik_ipsec_peer_tx_bytes{labels} 1024")));
+
+        // tunnel torn down: reset to zero until the TTL expires
+        metrics.ipsec_peers.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("mikrotik_ipsec_peer_state{labels} 0")));
+        assert!(encoded.contains(&format!("mikrotik_ipsec_peer_rx_bytes{labels} 0")));
+
+        registry
+            .cleanup_expired_dynamic_labels(Duration::ZERO)
+            .await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_ipsec_peer_state{"));
+    }
+
+    #[tokio::test]
+    async fn test_capsman_clients_per_managed_device() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("controller", Vec::new(), system);
+        metrics.managed_devices = vec![
+            ManagedDeviceStats {
+                identity: "ap-lobby".to_string(),
+                clients: 12,
+            },
+            ManagedDeviceStats {
+                identity: "ap-office".to_string(),
+                clients: 3,
+            },
+        ];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_capsman_clients{router=\"controller\",managed_device=\"ap-lobby\"} 12"
+        ));
+
+        // A CAP that leaves the controller disappears from the output
+        metrics.managed_devices.truncate(1);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("managed_device=\"ap-office\""));
+    }
+
+    #[tokio::test]
+    async fn test_capsman_client_roams_then_interface_expires() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("controller", Vec::new(), system);
+        let client = |interface: &str| CapsmanClientStats {
+            interface: interface.to_string(),
+            mac_address: "AA:BB:CC:DD:EE:01".to_string(),
+            ssid: "corp".to_string(),
+            signal_strength: Some(-61),
+            tx_rate: None,
+            rx_rate: None,
+            uptime: "5m".to_string(),
+        };
+        metrics.capsman_clients = vec![client("cap1")];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_capsman_interface_clients{router=\"controller\",interface=\"cap1\",ssid=\"corp\"} 1"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_capsman_client_signal_dbm{router=\"controller\",interface=\"cap1\",ssid=\"corp\",mac=\"aa:bb:cc:dd:ee:01\"} -61"
+        ));
+
+        // Roaming moves the client's series; the old interface drops to zero
+        metrics.capsman_clients = vec![client("cap2")];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_capsman_interface_clients{router=\"controller\",interface=\"cap1\",ssid=\"corp\"} 0"
+        ));
+        assert!(!encoded.contains(
+            "mikrotik_capsman_client_signal_dbm{router=\"controller\",interface=\"cap1\""
+        ));
+        assert!(encoded.contains(
+            "mikrotik_capsman_client_signal_dbm{router=\"controller\",interface=\"cap2\""
+        ));
+
+        // A router that isn't a controller reports no clients at all
+        metrics.capsman_clients.clear();
+        registry.update_metrics(&metrics).await;
+        registry
+            .cleanup_expired_dynamic_labels(Duration::ZERO)
+            .await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_capsman_interface_clients{"));
+        assert!(!encoded.contains("mikrotik_capsman_client_signal_dbm{"));
+    }
+
+    #[test]
+    fn test_update_login_method_counts_changes() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.update_login_method(&labels, LoginMethod::New, 0);
+        assert_eq!(registry.login_method.get_or_create(&labels).get(), 0);
+        assert_eq!(
+            registry.login_method_changes.get_or_create(&labels).get(),
+            0
+        );
+
+        registry.update_login_method(&labels, LoginMethod::Legacy, 1);
+        registry.update_login_method(&labels, LoginMethod::Legacy, 1);
+        assert_eq!(registry.login_method.get_or_create(&labels).get(), 1);
+        assert_eq!(
+            registry.login_method_changes.get_or_create(&labels).get(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_update_connection_resets_catches_up() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.update_connection_resets(&labels, 2);
+        registry.update_connection_resets(&labels, 2);
+        assert_eq!(registry.connection_resets.get_or_create(&labels).get(), 2);
+
+        registry.update_connection_resets(&labels, 3);
+        assert_eq!(registry.connection_resets.get_or_create(&labels).get(), 3);
+    }
+
+    #[test]
+    fn test_update_connection_last_success_age_sets_gauge() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+
+        registry.update_connection_last_success_age(&labels, Duration::from_secs(95));
+        assert_eq!(
+            registry
+                .connection_last_success_age_seconds
+                .get_or_create(&labels)
+                .get(),
+            95
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_route_active_follows_failover() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        let route = |gateway: &str, active: bool| DefaultRouteStats {
+            gateway: gateway.to_string(),
+            active,
+        };
+        metrics.default_routes = vec![route("203.0.113.1", true), route("198.51.100.1", false)];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_default_route_active{router=\"edge\",gateway=\"203.0.113.1\"} 1"
+            )
+        );
+        assert!(
+            encoded.contains(
+                "mikrotik_default_route_active{router=\"edge\",gateway=\"198.51.100.1\"} 0"
+            )
+        );
+
+        // The primary uplink fails and its route is removed
+        metrics.default_routes = vec![route("198.51.100.1", true)];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_default_route_active{router=\"edge\",gateway=\"198.51.100.1\"} 1"
+            )
+        );
+        assert!(!encoded.contains("gateway=\"203.0.113.1\""));
+    }
+
+    #[tokio::test]
+    async fn test_cpu_core_load_per_core() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("core", Vec::new(), system);
+        let core = |core: &str, load: u64| CpuCoreStats {
+            core: core.to_string(),
+            load,
+        };
+        metrics.cpu_cores = vec![core("0", 12), core("1", 87)];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_system_cpu_core_load{router=\"core\",core=\"0\"} 12"));
+        assert!(encoded.contains("mikrotik_system_cpu_core_load{router=\"core\",core=\"1\"} 87"));
+
+        // Older RouterOS without per-core data yields no series
+        metrics.cpu_cores = Vec::new();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_system_cpu_core_load{"));
+    }
+
+    #[tokio::test]
+    async fn test_tunnel_running_flips_when_gre_goes_down() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        let tunnel = |kind: &str, name: &str, running: bool| TunnelStats {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            running,
+        };
+        metrics.tunnels = vec![
+            tunnel("gre", "gre-dc1", true),
+            tunnel("eoip", "eoip-lab", true),
+        ];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_tunnel_running{router=\"edge\",type=\"gre\",name=\"gre-dc1\"} 1"
+            )
+        );
+
+        // The GRE tunnel goes down and the EoIP tunnel is deleted
+        metrics.tunnels = vec![tunnel("gre", "gre-dc1", false)];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains(
+                "mikrotik_tunnel_running{router=\"edge\",type=\"gre\",name=\"gre-dc1\"} 0"
+            )
+        );
+        assert!(!encoded.contains("name=\"eoip-lab\""));
+    }
+
+    #[tokio::test]
+    async fn test_vrrp_master_follows_failover() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut primary = make_router_metrics("gw1", Vec::new(), system.clone());
+        let mut secondary = make_router_metrics("gw2", Vec::new(), system);
+        let vrrp = |master: bool| {
+            vec![VrrpStats {
+                interface: "vrrp-lan".to_string(),
+                master,
+            }]
+        };
+        primary.vrrp = vrrp(true);
+        secondary.vrrp = vrrp(false);
+        registry.update_metrics(&primary).await;
+        registry.update_metrics(&secondary).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_vrrp_master{router=\"gw1\",interface=\"vrrp-lan\"} 1"));
+        assert!(encoded.contains("mikrotik_vrrp_master{router=\"gw2\",interface=\"vrrp-lan\"} 0"));
+
+        // gw1 fails over to gw2
+        primary.vrrp = vrrp(false);
+        secondary.vrrp = vrrp(true);
+        registry.update_metrics(&primary).await;
+        registry.update_metrics(&secondary).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_vrrp_master{router=\"gw1\",interface=\"vrrp-lan\"} 0"));
+        assert!(encoded.contains("mikrotik_vrrp_master{router=\"gw2\",interface=\"vrrp-lan\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_bgp_session_state_and_uptime() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        let session = |state: &str| BgpSessionStats {
+            name: "isp1-1".to_string(),
+            remote_as: "64500".to_string(),
+            state: state.to_string(),
+            uptime: Some("1h2m3s".to_string()),
+            prefix_count: Some(950_000),
+        };
+        metrics.bgp_sessions = vec![session("established")];
+        registry.update_metrics(&metrics).await;
+
+        let labels = "{router=\"edge\",peer=\"isp1-1\",remote_as=\"64500\"}";
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("mikrotik_bgp_session_state{labels} 1")));
+        assert!(encoded.contains(&format!("mikrotik_bgp_session_prefix_count{labels} 950000")));
+        assert!(encoded.contains(&format!("mikrotik_bgp_session_uptime_seconds{labels} 3723")));
+
+        // session drops: uptime is no longer meaningful
+        metrics.bgp_sessions = vec![session("idle")];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&format!("mikrotik_bgp_session_state{labels} 0")));
+        assert!(!encoded.contains("mikrotik_bgp_session_uptime_seconds{"));
+
+        metrics.bgp_sessions.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_bgp_session_state{"));
+    }
+
+    #[tokio::test]
+    async fn test_container_status_and_info() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.16", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        let container = |status: &str| ContainerStats {
+            name: "pihole".to_string(),
+            tag: "pihole/pihole:latest".to_string(),
+            status: status.to_string(),
+        };
+        metrics.containers = vec![container("running")];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_container_status{router=\"edge\",container=\"pihole\",status=\"running\"} 1"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_container_status{router=\"edge\",container=\"pihole\",status=\"stopped\"} 0"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_container_info{router=\"edge\",container=\"pihole\",tag=\"pihole/pihole:latest\"} 1"
+        ));
+
+        // The container stops
+        metrics.containers = vec![container("stopped")];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_container_status{router=\"edge\",container=\"pihole\",status=\"running\"} 0"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_container_status{router=\"edge\",container=\"pihole\",status=\"stopped\"} 1"
+        ));
+
+        // The container is removed
+        metrics.containers.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("container=\"pihole\""));
+    }
+
+    #[tokio::test]
+    async fn test_wireless_station_signal_and_rate() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("6.49", "RB912", "1d");
+        let mut metrics = make_router_metrics("backhaul", Vec::new(), system);
+        metrics.wireless_stations = vec![WirelessStationStats {
+            interface: "wlan1".to_string(),
+            mac_address: "AA:BB:CC:DD:EE:01".to_string(),
+            signal_strength: Some(-65),
+            tx_rate: Some(130_000_000),
+            rx_rate: Some(117_000_000),
+            uptime: "3h12m5s".to_string(),
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_wireless_station_signal_dbm{router=\"backhaul\",interface=\"wlan1\",mac=\"aa:bb:cc:dd:ee:01\"} -65"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_wireless_station_tx_rate_bps{router=\"backhaul\",interface=\"wlan1\",mac=\"aa:bb:cc:dd:ee:01\"} 130000000"
+        ));
+
+        // The station disconnects
+        metrics.wireless_stations.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mac=\"aa:bb:cc:dd:ee:01\""));
+    }
+
+    #[tokio::test]
+    async fn test_custom_collector_gauge() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.16", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.custom_metrics = vec![CustomMetric {
+            collector: "graphing".to_string(),
+            labels: vec![("interface".to_string(), "ether1".to_string())],
+            value: 1.0,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("# TYPE mikrotik_custom_graphing gauge"));
+        assert!(
+            encoded.contains("mikrotik_custom_graphing{router=\"edge\",interface=\"ether1\"} 1")
+        );
+
+        // The entry disappears from the print output
+        metrics.custom_metrics.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("interface=\"ether1\""));
+    }
+
+    #[tokio::test]
+    async fn test_health_sensors_by_kind() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.16", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("core", Vec::new(), system);
+        let sensor = |name: &str, value: f64| HealthSensor {
+            name: name.to_string(),
+            value,
+        };
+        metrics.health = HealthStats {
+            temperatures: vec![sensor("cpu-temperature", 52.5)],
+            voltages: vec![sensor("voltage", 24.3)],
+            fans: vec![sensor("fan1-speed", 3120.0), sensor("fan2-speed", 0.0)],
+        };
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_system_temperature_celsius{router=\"core\",sensor=\"cpu-temperature\"} 52.5"
+        ));
+        assert!(
+            encoded.contains("mikrotik_system_voltage{router=\"core\",sensor=\"voltage\"} 24.3")
+        );
+        assert!(
+            encoded.contains("mikrotik_system_fan_rpm{router=\"core\",sensor=\"fan2-speed\"} 0")
+        );
+
+        // A fan that disappears is removed
+        metrics.health.fans.pop();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("sensor=\"fan1-speed\""));
+        assert!(!encoded.contains("sensor=\"fan2-speed\""));
+    }
+
+    #[tokio::test]
+    async fn test_ping_rtt_and_loss_per_target() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.ping_targets = vec![PingStats {
+            target: "192.0.2.1".to_string(),
+            rtt_ms: Some(12.5),
+            loss_ratio: 0.0,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains("mikrotik_ping_rtt_ms{router=\"edge\",target=\"192.0.2.1\"} 12.5")
+        );
+
+        // An unreachable target keeps its loss but loses the stale RTT
+        metrics.ping_targets[0].rtt_ms = None;
+        metrics.ping_targets[0].loss_ratio = 1.0;
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_ping_rtt_ms{"));
+        assert!(
+            encoded.contains("mikrotik_ping_loss_ratio{router=\"edge\",target=\"192.0.2.1\"} 1.0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simple_queue_limits_per_direction() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.simple_queues = vec![QueueStats {
+            name: "office".to_string(),
+            id: None,
+            target: "192.168.88.0/24".to_string(),
+            max_limit_upload: 10_000_000,
+            max_limit_download: 50_000_000,
+            limit_at_upload: 0,
+            limit_at_download: 0,
+            bytes_upload: 0,
+            bytes_download: 0,
+            packets_upload: 0,
+            packets_download: 0,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_queue_simple_max_limit_bps{router=\"edge\",queue=\"office\",direction=\"download\"} 50000000"
+        ));
+
+        // With INCLUDE_IDS the RouterOS id becomes a label and the old series go away
+        metrics.simple_queues[0].id = Some("*1A".to_string());
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_queue_simple_max_limit_bps{router=\"edge\",queue=\"office\",direction=\"upload\",id=\"*1A\"} 10000000"
+        ));
+        assert!(!encoded.contains("direction=\"upload\"} 10000000"));
+    }
+
+    #[tokio::test]
+    async fn test_simple_queue_counters_are_deltas() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.simple_queues = vec![QueueStats {
+            name: "office".to_string(),
+            id: None,
+            target: "192.168.88.0/24".to_string(),
+            max_limit_upload: 0,
+            max_limit_download: 0,
+            limit_at_upload: 0,
+            limit_at_download: 0,
+            bytes_upload: 1000,
+            bytes_download: 5000,
+            packets_upload: 10,
+            packets_download: 50,
+        }];
+        // The first scrape is the baseline
+        registry.update_metrics(&metrics).await;
+        metrics.simple_queues[0].bytes_download = 8000;
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_queue_simple_bytes_total{router=\"edge\",queue=\"office\",direction=\"download\"} 3000"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_queue_simple_bytes_total{router=\"edge\",queue=\"office\",direction=\"upload\"} 0"
+        ));
+
+        // A queue reset does not make the counter go backwards
+        metrics.simple_queues[0].bytes_download = 100;
+        registry.update_metrics(&metrics).await;
+        metrics.simple_queues[0].bytes_download = 600;
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_queue_simple_bytes_total{router=\"edge\",queue=\"office\",direction=\"download\"} 3500"
+        ));
+
+        // Removed queues lose their counters
+        metrics.simple_queues.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_queue_simple_bytes_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_collector_durations_per_collector() {
+        use crate::config::Collector;
+        use std::time::Duration;
+
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.collector_durations = HashMap::from([
+            (Collector::System, Duration::from_millis(4)),
+            (Collector::Conntrack, Duration::from_millis(250)),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_collector_duration_milliseconds{router=\"edge\",collector=\"conntrack\"} 250.0"
+        ));
+
+        // A collector that no longer runs drops its duration
+        metrics.collector_durations.remove(&Collector::Conntrack);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains(
+            "mikrotik_collector_duration_milliseconds{router=\"edge\",collector=\"conntrack\"}"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_interface_queue_drops_are_counted_as_deltas() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        metrics.interface_queues = vec![InterfaceQueueStats {
+            interface: "ether1".to_string(),
+            drops: 100,
+            queued_packets: 3,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        metrics.interface_queues[0].drops = 130;
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_interface_queue_drops_total{router=\"edge\",interface=\"ether1\"} 30"
+        ));
+        assert!(
+            encoded.contains(
+                "mikrotik_interface_queue_length{router=\"edge\",interface=\"ether1\"} 3"
+            )
+        );
+
+        metrics.interface_queues.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_interface_queue_length{"));
+    }
+
+    #[tokio::test]
+    async fn test_firewall_rule_counters_are_counted_as_deltas() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB5009", "1d");
+        let mut metrics = make_router_metrics("edge", Vec::new(), system);
+        let rule = |bytes: u64, packets: u64| FirewallRuleStats {
+            chain: "input".to_string(),
+            comment: "drop invalid".to_string(),
+            id: None,
+            bytes,
+            packets,
+        };
+        metrics.firewall_rules = vec![rule(1000, 10), rule(500, 5)];
+        registry.update_metrics(&metrics).await;
+
+        metrics.firewall_rules = vec![rule(1600, 16), rule(500, 5)];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_firewall_filter_bytes_total{router=\"edge\",chain=\"input\",comment=\"drop invalid\"} 600"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_firewall_filter_packets_total{router=\"edge\",chain=\"input\",comment=\"drop invalid\"} 6"
+        ));
+
+        // Counters reset on reboot do not move the exported counter backwards
+        metrics.firewall_rules = vec![rule(100, 1)];
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_firewall_filter_bytes_total{router=\"edge\",chain=\"input\",comment=\"drop invalid\"} 600"
+        ));
+
+        metrics.firewall_rules.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_firewall_filter_bytes_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_enabled_collectors_follow_router_config() {
+        use crate::config::Collector;
+
+        let registry = MetricsRegistry::new();
+        registry
+            .record_enabled_collectors("edge", [Collector::System, Collector::Conntrack])
+            .await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded
+                .contains("mikrotik_collector_enabled{router=\"edge\",collector=\"conntrack\"} 1")
+        );
+
+        registry
+            .record_enabled_collectors("edge", [Collector::System])
+            .await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("collector=\"system\""));
+        assert!(!encoded.contains("collector=\"conntrack\""));
+    }
+
+    #[tokio::test]
+    async fn test_wifi_channel_utilization() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.wifi_channels = vec![WifiChannelStats {
+            interface: "wifi1".to_string(),
+            utilization: 0.87,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let labels = InterfaceLabels {
+            router: "router1".to_string(),
+            interface: "wifi1".to_string(),
+        };
+        assert!(
+            (registry
+                .wifi_channel_utilization_ratio
+                .get_or_create(&labels)
+                .get()
+                - 0.87)
+                .abs()
+                < f64::EPSILON
+        );
+
+        metrics.wifi_channels.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_wifi_channel_utilization_ratio{"));
+    }
+
+    #[tokio::test]
+    async fn test_sfp_diagnostics() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CCR2004", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.sfp_modules = vec![SfpStats {
+            interface: "sfp-sfpplus1".to_string(),
+            rx_power_dbm: Some(-7.4),
+            tx_power_dbm: Some(-2.1),
+            temperature_celsius: Some(41.0),
+            supply_voltage: None,
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_sfp_rx_power_dbm{router=\"router1\",interface=\"sfp-sfpplus1\"} -7.4"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_sfp_temperature_celsius{router=\"router1\",interface=\"sfp-sfpplus1\"} 41"
+        ));
+        assert!(!encoded.contains("mikrotik_sfp_supply_voltage_volts{"));
+
+        metrics.sfp_modules.clear();
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_sfp_rx_power_dbm{"));
+        assert!(!encoded.contains("mikrotik_sfp_tx_power_dbm{"));
+    }
+
+    #[tokio::test]
+    async fn test_bonding_failed_slave_reduces_active_count() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        let slave = |name: &str, up: bool| BondSlaveStats {
+            name: name.to_string(),
+            up,
+        };
+        metrics.bonds = vec![BondStats {
+            name: "bond1".to_string(),
+            slaves: vec![slave("ether1", true), slave("ether2", true)],
+        }];
+        registry.update_metrics(&metrics).await;
+
+        let bond = BondLabels {
+            router: "router1".to_string(),
+            bond: "bond1".to_string(),
+        };
+        let ether2 = BondSlaveLabels {
+            router: "router1".to_string(),
+            bond: "bond1".to_string(),
+            slave: "ether2".to_string(),
+        };
+        assert_eq!(registry.bonding_active_slaves.get_or_create(&bond).get(), 2);
+
+        metrics.bonds[0].slaves[1].up = false;
+        registry.update_metrics(&metrics).await;
+
+        assert_eq!(registry.bonding_active_slaves.get_or_create(&bond).get(), 1);
+        assert_eq!(registry.bonding_slave_up.get_or_create(&ether2).get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_script_jobs_running_only_set_when_collected() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_script_jobs_running{"));
+
+        metrics.script_jobs = Some(3);
+        registry.update_metrics(&metrics).await;
+        let router_label = RouterLabels {
+            router: "router1".to_string(),
+        };
+        assert_eq!(
+            registry
+                .script_jobs_running
+                .get_or_create(&router_label)
+                .get(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_userman_sessions_omitted_without_package() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let without = make_router_metrics("edge", Vec::new(), system.clone());
+        let mut with = make_router_metrics("hotspot", Vec::new(), system);
+        with.userman_sessions = Some(42);
+        registry.update_metrics(&without).await;
+        registry.update_metrics(&with).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_userman_active_sessions{router=\"hotspot\"} 42"));
+        assert!(!encoded.contains("mikrotik_userman_active_sessions{router=\"edge\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_ip_routes_per_address_family() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.16", "CCR2216", "1d");
+        let mut metrics = make_router_metrics("border", Vec::new(), system);
+        metrics.ipv4_routes = Some(950_123);
+        metrics.ipv6_routes = Some(201_456);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded
+                .contains("mikrotik_ip_routes{router=\"border\",address_family=\"ipv4\"} 950123")
+        );
+        assert!(
+            encoded
+                .contains("mikrotik_ip_routes{router=\"border\",address_family=\"ipv6\"} 201456")
+        );
+
+        // IPv6 disabled on the router
+        metrics.ipv6_routes = None;
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("address_family=\"ipv6\""));
+    }
+
+    #[tokio::test]
+    async fn test_vpn_tunnels_active_by_type() {
+        let registry = MetricsRegistry::new();
+        let session = |id: &str, service: &str| PppSessionStats {
+            session: id.to_string(),
+            name: format!("user{id}"),
+            service: service.to_string(),
+            caller_id: String::new(),
+            address: String::new(),
+            uptime: "1m".to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("vpn", Vec::new(), make_system("7.16", "CCR2116", "1d"));
+        metrics.ipsec_active_peers = Some(3);
+        metrics.ppp_sessions = Some(vec![
+            session("*1", "l2tp"),
+            session("*2", "l2tp"),
+            session("*3", "sstp"),
+            session("*4", "pppoe"),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        let active = |kind: &str, count: u64| {
+            format!("mikrotik_vpn_tunnels_active{{router=\"vpn\",type=\"{kind}\"}} {count}")
+        };
+        assert!(encoded.contains(&active("ipsec", 3)));
+        assert!(encoded.contains(&active("l2tp", 2)));
+        assert!(encoded.contains(&active("sstp", 1)));
+        assert!(encoded.contains(&active("pptp", 0)));
+        assert!(!encoded.contains("type=\"pppoe\""));
+        // No WireGuard interfaces, so no WireGuard breakdown
+        assert!(!encoded.contains("type=\"wireguard\""));
+
+        // Types whose collector stops reporting are removed
+        metrics.ppp_sessions = None;
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(&active("ipsec", 3)));
+        assert!(!encoded.contains("type=\"l2tp\""));
+    }
+
+    #[tokio::test]
+    async fn test_ppp_sessions_established_counts_new_sessions() {
+        let registry = MetricsRegistry::new();
+        let session = |id: &str, service: &str| PppSessionStats {
+            session: id.to_string(),
+            name: format!("user{id}"),
+            service: service.to_string(),
+            caller_id: String::new(),
+            address: String::new(),
+            uptime: "1m".to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("bras", Vec::new(), make_system("7.10", "CCR2004", "1d"));
+        metrics.ppp_sessions = Some(vec![session("*1", "pppoe"), session("*2", "pppoe")]);
+        registry.update_metrics(&metrics).await;
+
+        // Failed or disabled collection must not make every session look new
+        let mut skipped = metrics.clone();
+        skipped.ppp_sessions = None;
+        registry.update_metrics(&skipped).await;
+
+        metrics.ppp_sessions = Some(vec![
+            session("*2", "pppoe"),
+            session("*3", "pppoe"),
+            session("*4", "pppoe"),
+            session("*5", "l2tp"),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let labels = |service: &str| PppServiceLabels {
+            router: "bras".to_string(),
+            service: service.to_string(),
+        };
+        assert_eq!(
+            registry
+                .ppp_sessions_established
+                .get_or_create(&labels("pppoe"))
+                .get(),
+            2
+        );
+        assert_eq!(
+            registry
+                .ppp_sessions_established
+                .get_or_create(&labels("l2tp"))
+                .get(),
+            1
+        );
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+        assert!(registry.prev_ppp_sessions.lock().await.is_empty());
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_ppp_sessions_established_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_ppp_active_sessions_and_user_uptime() {
+        let registry = MetricsRegistry::new();
+        let session = |id: &str, name: &str, service: &str, uptime: &str| PppSessionStats {
+            session: id.to_string(),
+            name: name.to_string(),
+            service: service.to_string(),
+            caller_id: String::new(),
+            address: String::new(),
+            uptime: uptime.to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("bras", Vec::new(), make_system("7.10", "CCR2004", "1d"));
+        metrics.ppp_sessions = Some(vec![
+            session("*1", "alice", "pppoe", "1h"),
+            session("*2", "bob", "pppoe", "5m"),
+            session("*3", "carol", "l2tp", "30s"),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(
+            encoded.contains("mikrotik_ppp_active_sessions{router=\"bras\",service=\"pppoe\"} 2")
+        );
+        assert!(
+            encoded.contains("mikrotik_ppp_active_sessions{router=\"bras\",service=\"l2tp\"} 1")
+        );
+        assert!(
+            encoded.contains(
+                "mikrotik_ppp_session_uptime_seconds{router=\"bras\",user=\"alice\"} 3600"
+            )
+        );
+
+        // carol disconnects: the l2tp count goes away, her uptime resets to
+        // zero until the TTL expires
+        metrics.ppp_sessions = Some(vec![
+            session("*1", "alice", "pppoe", "1h1m"),
+            session("*2", "bob", "pppoe", "6m"),
+        ]);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("service=\"l2tp\""));
+        assert!(
+            encoded
+                .contains("mikrotik_ppp_session_uptime_seconds{router=\"bras\",user=\"carol\"} 0")
+        );
+
+        metrics.ppp_sessions = Some(Vec::new());
+        registry.update_metrics(&metrics).await;
+        registry
+            .cleanup_expired_dynamic_labels(Duration::ZERO)
+            .await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_ppp_session_uptime_seconds{"));
+        assert!(!encoded.contains("mikrotik_ppp_active_sessions{"));
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_reboots_are_counted_once_per_log_entry() {
+        let registry = MetricsRegistry::new();
+        let reboot = |event: &str, reason: &str| UnexpectedReboot {
+            event: event.to_string(),
+            reason: reason.to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("edge", Vec::new(), make_system("7.16", "RB5009", "1d"));
+        metrics.watchdog_enabled = Some(true);
+        // Entry from before the exporter started
+        metrics.unexpected_reboots = Some(vec![reboot("01:00:00 power", "unclean_shutdown")]);
+        registry.update_metrics(&metrics).await;
+        registry.update_metrics(&metrics).await;
+
+        // The watchdog reboots the router; the memory log starts over
+        metrics.unexpected_reboots = Some(vec![reboot("03:12:09 watchdog", "watchdog")]);
+        registry.update_metrics(&metrics).await;
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_watchdog_enabled{router=\"edge\"} 1"));
+        assert!(
+            encoded.contains(
+                "mikrotik_unexpected_reboots_total{router=\"edge\",reason=\"watchdog\"} 1"
+            )
+        );
+        assert!(!encoded.contains("reason=\"unclean_shutdown\""));
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+        assert!(registry.prev_reboot_events.lock().await.is_empty());
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_unexpected_reboots_total{"));
+        assert!(!encoded.contains("mikrotik_watchdog_enabled{"));
+    }
+
+    #[tokio::test]
+    async fn test_dhcp_leases_granted_counts_newly_bound_macs() {
+        let registry = MetricsRegistry::new();
+        let lease = |mac: &str, server: &str, status: &str| DhcpLeaseStats {
+            server: server.to_string(),
+            mac_address: Some(mac.to_string()),
+            status: status.to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("gw", Vec::new(), make_system("7.10", "RB5009", "1d"));
+        metrics.dhcp_leases = Some(vec![lease("AA:00:00:00:00:01", "lan", "bound")]);
+        registry.update_metrics(&metrics).await;
+
+        // Failed or disabled collection must not make every lease look new
+        let mut skipped = metrics.clone();
+        skipped.dhcp_leases = None;
+        registry.update_metrics(&skipped).await;
+
+        // A burst of devices joins; waiting leases are not granted yet
+        metrics.dhcp_leases = Some(vec![
+            lease("AA:00:00:00:00:01", "lan", "bound"),
+            lease("AA:00:00:00:00:02", "lan", "bound"),
+            lease("AA:00:00:00:00:03", "lan", "bound"),
+            lease("AA:00:00:00:00:04", "guest", "bound"),
+            lease("AA:00:00:00:00:05", "guest", "waiting"),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let labels = |server: &str| DhcpServerLabels {
+            router: "gw".to_string(),
+            server: server.to_string(),
+        };
+        assert_eq!(
+            registry
+                .dhcp_leases_granted
+                .get_or_create(&labels("lan"))
+                .get(),
+            2
+        );
+        assert_eq!(
+            registry
+                .dhcp_leases_granted
+                .get_or_create(&labels("guest"))
+                .get(),
+            1
+        );
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+        assert!(registry.prev_dhcp_bound_macs.lock().await.is_empty());
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("mikrotik_dhcp_leases_granted_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_dhcp_leases_total_per_server_and_status() {
+        let registry = MetricsRegistry::new();
+        let lease = |server: &str, status: &str| DhcpLeaseStats {
+            server: server.to_string(),
+            mac_address: None,
+            status: status.to_string(),
+        };
+        let mut metrics =
+            make_router_metrics("gw", Vec::new(), make_system("7.10", "RB5009", "1d"));
+        metrics.dhcp_leases = Some(vec![
+            lease("lan", "bound"),
+            lease("lan", "bound"),
+            lease("lan", "waiting"),
+            lease("guest", "offered"),
+        ]);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains(
+            "mikrotik_dhcp_leases_total{router=\"gw\",server=\"lan\",status=\"bound\"} 2"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_dhcp_leases_total{router=\"gw\",server=\"lan\",status=\"waiting\"} 1"
+        ));
+        assert!(encoded.contains(
+            "mikrotik_dhcp_leases_total{router=\"gw\",server=\"guest\",status=\"offered\"} 1"
+        ));
+
+        // The guest DHCP server is removed
+        metrics.dhcp_leases = Some(vec![lease("lan", "bound")]);
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(!encoded.contains("server=\"guest\""));
+        assert!(!encoded.contains("status=\"waiting\""));
+    }
+
+    #[tokio::test]
+    async fn test_clock_offset_and_timezone_change() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.clock = Some(ClockStats {
+            timezone: "UTC".to_string(),
+            offset_seconds: -86_400,
+        });
+        registry.update_metrics(&metrics).await;
+
+        let router_label = RouterLabels {
+            router: "router1".to_string(),
+        };
+        assert_eq!(
+            registry
+                .system_time_offset_seconds
+                .get_or_create(&router_label)
+                .get(),
+            -86_400
+        );
+
+        metrics.clock = Some(ClockStats {
+            timezone: "Europe/Moscow".to_string(),
+            offset_seconds: 0,
+        });
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("timezone=\"Europe/Moscow\""));
+        assert!(!encoded.contains("timezone=\"UTC\""));
+    }
+
+    #[tokio::test]
+    async fn test_router_clock_skew() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.router_clock_skew_seconds = Some(-42);
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_router_clock_skew_seconds{router=\"router1\"} -42"));
+    }
+
+    #[tokio::test]
+    async fn test_conntrack_table_usage() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        let mut metrics = make_router_metrics("router1", Vec::new(), system);
+        metrics.conntrack_table = Some(ConntrackTableStats {
+            entries: 1532,
+            max_entries: Some(1_048_576),
+        });
+        registry.update_metrics(&metrics).await;
+
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_conntrack_table_entries{router=\"router1\"} 1532"));
+        assert!(encoded.contains("mikrotik_conntrack_table_max{router=\"router1\"} 1048576"));
+    }
+
+    #[tokio::test]
+    async fn test_license_level_and_chr_deadline() {
+        let registry = MetricsRegistry::new();
+        let system = make_system("7.10", "CHR", "1d");
+        let mut metrics = make_router_metrics("chr1", Vec::new(), system);
+        metrics.license = Some(LicenseStats {
+            level: "p-unlimited".to_string(),
+            level_number: Some(3),
+            deadline: Some(1_736_936_430),
+        });
+        registry.update_metrics(&metrics).await;
+
+        let router_label = RouterLabels {
+            router: "chr1".to_string(),
+        };
+        assert_eq!(registry.license_level.get_or_create(&router_label).get(), 3);
+        assert_eq!(
+            registry
+                .license_deadline_timestamp_seconds
+                .get_or_create(&router_label)
+                .get(),
+            1_736_936_430
+        );
+
+        // Falling back to free drops the deadline and the old level
+        metrics.license = Some(LicenseStats {
+            level: "free".to_string(),
+            level_number: Some(0),
+            deadline: None,
+        });
+        registry.update_metrics(&metrics).await;
+        let encoded = registry.encode_metrics().await.unwrap();
+        assert!(encoded.contains("mikrotik_license_info{router=\"chr1\",level=\"free\"} 1"));
+        assert!(!encoded.contains("level=\"p-unlimited\""));
+        assert!(!encoded.contains("mikrotik_license_deadline_timestamp_seconds{"));
+    }
+
+    #[tokio::test]
+    async fn test_connection_tracking_multi_router() {
+        let registry = MetricsRegistry::new();
+        let iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
+        let system = make_system("7.10", "RB750Gr3", "1d2h3m4s");
+
+        // First update for router1 with TCP connections
+        let mut metrics1 = make_router_metrics("router1", vec![iface.clone()], system.clone());
+        metrics1.connection_tracking = vec![
+            make_conntrack("192.168.1.1", "tcp", 100, "ipv4"),
+            make_conntrack("192.168.1.1", "udp", 50, "ipv4"),
+        ];
+        registry.update_metrics(&metrics1).await;
+
+        // First update for router2 with different connections
+        let mut metrics2 = make_router_metrics("router2", vec![iface.clone()], system.clone());
+        metrics2.connection_tracking = vec![
+            make_conntrack("10.0.0.1", "tcp", 200, "ipv4"),
+            make_conntrack("10.0.0.1", "icmp", 10, "ipv4"),
+        ];
+        registry.update_metrics(&metrics2).await;
+
+        // Check that both routers have their metrics
+        let labels1_tcp = ConntrackLabels {
+            router: "router1".to_string(),
+            src_address: "192.168.1.1".to_string(),
+            protocol: "tcp".to_string(),
+            ip_version: "ipv4".to_string(),
+        };
+        let labels1_udp = ConntrackLabels {
+            router: "router1".to_string(),
+            src_address: "192.168.1.1".to_string(),
+            protocol: "udp".to_string(),
+            ip_version: "ipv4".to_string(),
+        };
+        let labels2_tcp = ConntrackLabels {
+            router: "router2".to_string(),
+            src_address: "10.0.0.1".to_string(),
             protocol: "tcp".to_string(),
             ip_version: "ipv4".to_string(),
         };
@@ -578,10 +2637,16 @@ mod tests {
         let system_v1 = SystemResource {
             uptime: "1d".to_string(),
             cpu_load: 10,
+            cpu_count: None,
             free_memory: 512 * 1024 * 1024,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: "7.10".to_string(),
             board_name: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         };
         let metrics_v1 = make_router_metrics("router1", vec![iface.clone()], system_v1);
         registry.update_metrics(&metrics_v1).await;
@@ -590,16 +2655,23 @@ mod tests {
             router: "router1".to_string(),
             version: "7.10".to_string(),
             board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
         };
         assert_eq!(registry.system_info.get_or_create(&old_labels).get(), 1);
 
         let system_v2 = SystemResource {
             uptime: "1d".to_string(),
             cpu_load: 10,
+            cpu_count: None,
             free_memory: 512 * 1024 * 1024,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: "7.11".to_string(),
             board_name: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         };
         let metrics_v2 = make_router_metrics("router1", vec![iface], system_v2);
         registry.update_metrics(&metrics_v2).await;
@@ -608,6 +2680,7 @@ mod tests {
             router: "router1".to_string(),
             version: "7.11".to_string(),
             board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
         };
         assert_eq!(
             registry.system_info.get_or_create(&old_labels).get(),
@@ -621,6 +2694,27 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_unparseable_uptime_is_counted() {
+        let registry = MetricsRegistry::new();
+        let labels = RouterLabels {
+            router: "router1".to_string(),
+        };
+        let metrics = make_router_metrics("router1", Vec::new(), make_system("7.10", "x", "0s"));
+        registry.update_metrics(&metrics).await;
+        assert_eq!(
+            registry.uptime_parse_failures.get_or_create(&labels).get(),
+            0
+        );
+
+        let metrics = make_router_metrics("router1", Vec::new(), make_system("7.10", "x", "1y"));
+        registry.update_metrics(&metrics).await;
+        assert_eq!(
+            registry.uptime_parse_failures.get_or_create(&labels).get(),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn test_system_info_no_reset_when_unchanged() {
         let registry = MetricsRegistry::new();
@@ -629,10 +2723,16 @@ mod tests {
         let system = SystemResource {
             uptime: "1d".to_string(),
             cpu_load: 10,
+            cpu_count: None,
             free_memory: 512 * 1024 * 1024,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: "7.10".to_string(),
             board_name: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         };
         let metrics = make_router_metrics("router1", vec![iface.clone()], system.clone());
         registry.update_metrics(&metrics).await;
@@ -642,6 +2742,7 @@ mod tests {
             router: "router1".to_string(),
             version: "7.10".to_string(),
             board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
         };
         assert_eq!(
             registry.system_info.get_or_create(&labels).get(),
@@ -649,4 +2750,44 @@ mod tests {
             "system_info should stay 1 when version/board unchanged"
         );
     }
+
+    #[tokio::test]
+    async fn test_router_info_reset_on_identity_change_and_cleanup() {
+        let registry = MetricsRegistry::new();
+        let iface = make_interface("ether1", 1000, 2000, 10, 20, 0, 0, true);
+        let system = make_system("7.10", "RB750Gr3", "1d");
+        registry
+            .update_metrics(&make_router_metrics(
+                "router1",
+                vec![iface.clone()],
+                system.clone(),
+            ))
+            .await;
+
+        let renamed = SystemResource {
+            identity: "core-gw".to_string(),
+            ..system
+        };
+        registry
+            .update_metrics(&make_router_metrics("router1", vec![iface], renamed))
+            .await;
+
+        let labels = |identity: &str| RouterInfoLabels {
+            router: "router1".to_string(),
+            identity: identity.to_string(),
+            version: "7.10".to_string(),
+            board: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            serial: "HEX0123456".to_string(),
+        };
+        assert_eq!(registry.router_info.get_or_create(&labels("gw")).get(), 0);
+        assert_eq!(
+            registry.router_info.get_or_create(&labels("core-gw")).get(),
+            1
+        );
+
+        registry.cleanup_stale_routers(&HashSet::new()).await;
+
+        assert!(registry.prev_router_info.lock().await.is_empty());
+    }
 }
@@ -3,21 +3,43 @@
 
 //! Scrape and registry-level bookkeeping helpers
 
-use crate::metrics::labels::RouterLabels;
+use crate::config::Collector;
+use crate::metrics::labels::{CollectorLabels, QuantileLabels, RouterLabels, ScrapeErrorLabels};
+use crate::mikrotik::{CircuitState, LoginMethod, ScrapeErrorReason};
 use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::histogram::Histogram;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 use super::MetricsRegistry;
 
+/// Quantiles exported by `mikrotik_scrape_duration_quantile_milliseconds`
+pub(super) const SCRAPE_DURATION_QUANTILES: [(f64, &str); 3] =
+    [(0.5, "0.5"), (0.9, "0.9"), (0.99, "0.99")];
+
+/// Bucket bounds of `mikrotik_scrape_duration_seconds`, in seconds
+const SCRAPE_DURATION_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Creates a router's `mikrotik_scrape_duration_seconds` histogram
+pub(super) fn scrape_duration_histogram() -> Histogram {
+    Histogram::new(SCRAPE_DURATION_BUCKETS)
+}
+
 impl MetricsRegistry {
     pub async fn encode_metrics(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let registry = self.registry.lock().await;
+        let started = Instant::now();
         let mut buffer = String::new();
         encode(&mut buffer, &registry)?;
+        // Reported by the next encode, since this output is already written
+        self.metrics_encode_duration_milliseconds
+            .set(started.elapsed().as_secs_f64() * 1000.0);
         Ok(buffer)
     }
 
     pub fn record_scrape_success(&self, labels: &RouterLabels) {
         self.scrape_success.get_or_create(labels).inc();
+        self.metric_stale.get_or_create(labels).set(0);
         // Record timestamp of successful scrape
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -27,10 +49,54 @@ impl MetricsRegistry {
         self.scrape_last_success_timestamp_seconds
             .get_or_create(labels)
             .set(now as i64);
+        let since = {
+            let mut streaks = self
+                .reachable_streak_start
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            *streaks.entry(labels.router.clone()).or_insert(now)
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        self.router_reachable_since_seconds
+            .get_or_create(labels)
+            .set(now.saturating_sub(since) as i64);
     }
 
+    /// Record a failed scrape
+    ///
+    /// Gauges from the last successful scrape are kept as-is; `mikrotik_metric_stale`
+    /// is raised so dashboards can flag them instead of silently trusting them.
     pub fn record_scrape_error(&self, labels: &RouterLabels) {
         self.scrape_errors.get_or_create(labels).inc();
+        self.metric_stale.get_or_create(labels).set(1);
+        self.reachable_streak_start
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&labels.router);
+        self.router_reachable_since_seconds
+            .get_or_create(labels)
+            .set(0);
+    }
+
+    /// Count a failed scrape under its cause
+    pub async fn record_scrape_error_reason(&self, router: &str, reason: ScrapeErrorReason) {
+        let labels = ScrapeErrorLabels {
+            router: router.to_string(),
+            reason: reason.as_str().to_string(),
+        };
+        self.scrape_error_reason.get_or_create(&labels).inc();
+        self.seen_scrape_error_reasons
+            .extend(router, [labels])
+            .await;
+    }
+
+    /// Whether the router's last scrape succeeded, `None` before its first scrape
+    pub fn router_up(&self, router: &str) -> Option<bool> {
+        self.metric_stale
+            .get(&RouterLabels {
+                router: router.to_string(),
+            })
+            .map(|stale| stale.get() == 0)
     }
 
     /// Initialize metrics for a router to zero
@@ -43,15 +109,86 @@ impl MetricsRegistry {
         let _ = self.scrape_errors.get_or_create(labels);
         let _ = self.scrape_duration_milliseconds.get_or_create(labels);
         let _ = self.connection_consecutive_errors.get_or_create(labels);
+        let _ = self.circuit_breaker_state.get_or_create(labels);
+        let _ = self.login_method_changes.get_or_create(labels);
+        let _ = self.connection_resets.get_or_create(labels);
     }
 
-    pub fn record_scrape_duration(&self, labels: &RouterLabels, duration_secs: f64) {
+    /// Export the collectors enabled for a router, dropping ones since disabled
+    pub async fn record_enabled_collectors(
+        &self,
+        router: &str,
+        collectors: impl IntoIterator<Item = Collector>,
+    ) {
+        let current: HashSet<CollectorLabels> = collectors
+            .into_iter()
+            .map(|collector| CollectorLabels {
+                router: router.to_string(),
+                collector: collector.name().to_string(),
+            })
+            .collect();
+        for labels in &current {
+            self.collector_enabled.get_or_create(labels).set(1);
+        }
+        for stale in self.prev_collectors.replace(router, current).await {
+            self.collector_enabled.remove(&stale);
+        }
+    }
+
+    /// Record a scrape's duration, successful or not
+    pub async fn record_scrape_duration(&self, labels: &RouterLabels, duration_secs: f64) {
+        self.scrape_duration_seconds
+            .get_or_create(labels)
+            .observe(duration_secs);
         // Store as milliseconds for better precision (will be interpreted as fractional seconds)
         #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
         let millis = (duration_secs * 1000.0).round() as i64;
         self.scrape_duration_milliseconds
             .get_or_create(labels)
             .set(millis);
+        self.record_scrape_duration_quantiles(&labels.router, duration_secs * 1000.0)
+            .await;
+    }
+
+    /// Update the duration quantiles over the router's recent scrapes
+    ///
+    /// Prometheus summaries are not available in prometheus-client, so the
+    /// quantiles are computed here (nearest rank) over a sliding window.
+    async fn record_scrape_duration_quantiles(&self, router: &str, millis: f64) {
+        if self.scrape_duration_window == 0 {
+            return;
+        }
+        let mut windows = self.scrape_duration_windows.lock().await;
+        let window = windows.entry(router.to_string()).or_default();
+        if window.len() == self.scrape_duration_window {
+            window.pop_front();
+        }
+        window.push_back(millis);
+
+        let mut sorted: Vec<f64> = window.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        for (quantile, label) in SCRAPE_DURATION_QUANTILES {
+            #[allow(
+                clippy::cast_precision_loss,
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss
+            )]
+            let rank = (quantile * sorted.len() as f64).ceil() as usize;
+            let labels = QuantileLabels {
+                router: router.to_string(),
+                quantile: label.to_string(),
+            };
+            self.scrape_duration_quantile_milliseconds
+                .get_or_create(&labels)
+                .set(sorted[rank.clamp(1, sorted.len()) - 1]);
+        }
+    }
+
+    /// Flag whether the last scrape took longer than the collection interval
+    pub fn record_scrape_interval_check(&self, labels: &RouterLabels, too_short: bool) {
+        self.scrape_interval_too_short
+            .get_or_create(labels)
+            .set(i64::from(too_short));
     }
 
     pub fn record_collection_cycle_duration(&self, duration_secs: f64) {
@@ -61,17 +198,64 @@ impl MetricsRegistry {
         self.collection_cycle_duration_milliseconds.set(millis);
     }
 
+    /// Count a background scrape that acquired its concurrency permit
+    pub fn collection_started(&self) {
+        self.collection_in_flight.inc();
+    }
+
+    /// Count a background scrape as finished
+    pub fn collection_finished(&self) {
+        self.collection_in_flight.dec();
+    }
+
     pub fn update_connection_errors(&self, labels: &RouterLabels, consecutive_errors: u32) {
         self.connection_consecutive_errors
             .get_or_create(labels)
             .set(i64::from(consecutive_errors));
     }
 
-    pub fn update_pool_stats(&self, total: usize, active: usize) {
+    pub fn update_circuit_breaker_state(&self, labels: &RouterLabels, state: CircuitState) {
+        self.circuit_breaker_state
+            .get_or_create(labels)
+            .set(state.as_gauge());
+    }
+
+    /// Export the latest login method, catching the change counter up to `changes`
+    pub fn update_login_method(&self, labels: &RouterLabels, method: LoginMethod, changes: u64) {
+        self.login_method
+            .get_or_create(labels)
+            .set(method.as_gauge());
+        let counter = self.login_method_changes.get_or_create(labels);
+        let counted = counter.get();
+        if changes > counted {
+            counter.inc_by(changes - counted);
+        }
+    }
+
+    /// Catch the connection reset counter up to the pool's count
+    pub fn update_connection_resets(&self, labels: &RouterLabels, resets: u64) {
+        let counter = self.connection_resets.get_or_create(labels);
+        let counted = counter.get();
+        if resets > counted {
+            counter.inc_by(resets - counted);
+        }
+    }
+
+    /// Set the time since the router's last successful operation
+    pub fn update_connection_last_success_age(&self, labels: &RouterLabels, age: Duration) {
+        #[allow(clippy::cast_possible_wrap)]
+        self.connection_last_success_age_seconds
+            .get_or_create(labels)
+            .set(age.as_secs() as i64);
+    }
+
+    pub fn update_pool_stats(&self, total: usize, active: usize, oldest_idle: Duration) {
         #[allow(clippy::cast_possible_wrap)]
         {
             self.connection_pool_size.set(total as i64);
             self.connection_pool_active.set(active as i64);
+            self.connection_pool_oldest_idle_seconds
+                .set(oldest_idle.as_secs() as i64);
         }
     }
 
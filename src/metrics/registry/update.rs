@@ -3,16 +3,48 @@
 
 //! Metric update logic for router snapshots
 
+use crate::config::CounterMode;
 use crate::metrics::labels::{
-    ConntrackLabels, InterfaceLabels, RouterLabels, SystemInfoLabels, WireGuardInterfaceLabels,
-    WireGuardPeerInfoLabels, WireGuardPeerLabels,
+    AddressFamilyLabels, BgpSessionLabels, BondLabels, BondSlaveLabels, CapsmanClientLabels,
+    CapsmanInterfaceLabels, CollectorLabels, CommandLabels, ConntrackLabels, ContainerInfoLabels,
+    ContainerStatusLabels, CpuCoreLabels, CustomLabels, DhcpLeaseLabels, DhcpServerLabels,
+    FirewallRuleLabels, GatewayLabels, HealthSensorLabels, IdLabel, InterfaceLabels,
+    IpServiceLabels, IpsecPeerLabels, LicenseLabels, ManagedDeviceLabels, PingLabels,
+    PppServiceLabels, PppUserLabels, QueueLabels, RebootLabels, RouterInfoLabels, RouterLabels,
+    SystemInfoLabels, TimezoneLabels, TunnelLabels, VpnTunnelLabels, WireGuardInterfaceLabels,
+    WireGuardPeerInfoLabels, WireGuardPeerKeyLabel, WireGuardPeerLabels, WirelessStationLabels,
 };
-use crate::metrics::parsers::parse_uptime_to_seconds;
-use crate::mikrotik::{RouterMetrics, WireGuardPeerStats};
+use crate::metrics::parsers::{normalize_mac, parse_uptime_to_seconds, uptime_parse_failed};
+use crate::mikrotik::{
+    BgpSessionStats, BondStats, CapsmanClientStats, ClockStats, ConntrackTableStats,
+    ContainerStats, CpuCoreStats, CustomMetric, DefaultRouteStats, DhcpLeaseStats,
+    FirewallRuleStats, HealthStats, InterfaceQueueStats, IpServiceStats, IpsecPeerStats,
+    LicenseStats, ManagedDeviceStats, PingStats, PppSessionStats, QueueStats, RouterMetrics,
+    SfpStats, SystemResource, TunnelStats, UnexpectedReboot, VrrpStats, WifiChannelStats,
+    WireGuardPeerStats, WirelessStationStats,
+};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::atomic::AtomicU64;
 use std::time::Instant;
 
-use super::{InterfaceSnapshot, MetricsRegistry};
+use super::{CustomGaugeFamily, InterfaceSnapshot, MetricsRegistry};
+
+/// Container statuses always exported, whichever one is current
+const CONTAINER_STATUSES: [&str; 6] = [
+    "running",
+    "stopped",
+    "starting",
+    "stopping",
+    "extracting",
+    "error",
+];
+
+/// PPP services counted as VPN tunnels; PPPoE is an access protocol and is left out
+const PPP_VPN_SERVICES: [&str; 4] = ["l2tp", "sstp", "ovpn", "pptp"];
 
 impl MetricsRegistry {
     /// Update metrics from collected router data
@@ -44,34 +76,112 @@ impl MetricsRegistry {
                     tx_packets: iface.tx_packets,
                     rx_errors: iface.rx_errors,
                     tx_errors: iface.tx_errors,
+                    rx_drops: iface.rx_drops,
+                    tx_drops: iface.tx_drops,
+                    rx_multicast: iface.rx_multicast,
+                    tx_multicast: iface.tx_multicast,
+                    rx_broadcast: iface.rx_broadcast,
+                    tx_broadcast: iface.tx_broadcast,
+                    running: iface.running,
                 });
-                let dx_rx_bytes = iface.rx_bytes.saturating_sub(snapshot.rx_bytes);
-                let dx_tx_bytes = iface.tx_bytes.saturating_sub(snapshot.tx_bytes);
-                let dx_rx_packets = iface.rx_packets.saturating_sub(snapshot.rx_packets);
-                let dx_tx_packets = iface.tx_packets.saturating_sub(snapshot.tx_packets);
-                let dx_rx_errors = iface.rx_errors.saturating_sub(snapshot.rx_errors);
-                let dx_tx_errors = iface.tx_errors.saturating_sub(snapshot.tx_errors);
-                self.interface_rx_bytes
-                    .get_or_create(&labels)
-                    .inc_by(dx_rx_bytes);
-                self.interface_tx_bytes
-                    .get_or_create(&labels)
-                    .inc_by(dx_tx_bytes);
-                self.interface_rx_packets
-                    .get_or_create(&labels)
-                    .inc_by(dx_rx_packets);
-                self.interface_tx_packets
-                    .get_or_create(&labels)
-                    .inc_by(dx_tx_packets);
-                self.interface_rx_errors
-                    .get_or_create(&labels)
-                    .inc_by(dx_rx_errors);
-                self.interface_tx_errors
-                    .get_or_create(&labels)
-                    .inc_by(dx_tx_errors);
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_rx_bytes,
+                    &labels,
+                    iface.rx_bytes,
+                    snapshot.rx_bytes,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_tx_bytes,
+                    &labels,
+                    iface.tx_bytes,
+                    snapshot.tx_bytes,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_rx_packets,
+                    &labels,
+                    iface.rx_packets,
+                    snapshot.rx_packets,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_tx_packets,
+                    &labels,
+                    iface.tx_packets,
+                    snapshot.tx_packets,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_rx_errors,
+                    &labels,
+                    iface.rx_errors,
+                    snapshot.rx_errors,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_tx_errors,
+                    &labels,
+                    iface.tx_errors,
+                    snapshot.tx_errors,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_rx_drops,
+                    &labels,
+                    iface.rx_drops,
+                    snapshot.rx_drops,
+                );
+                advance_counter(
+                    self.counter_mode,
+                    &self.interface_tx_drops,
+                    &labels,
+                    iface.tx_drops,
+                    snapshot.tx_drops,
+                );
+                inc_optional(
+                    self.counter_mode,
+                    &self.interface_rx_multicast,
+                    &labels,
+                    iface.rx_multicast,
+                    snapshot.rx_multicast,
+                );
+                inc_optional(
+                    self.counter_mode,
+                    &self.interface_tx_multicast,
+                    &labels,
+                    iface.tx_multicast,
+                    snapshot.tx_multicast,
+                );
+                inc_optional(
+                    self.counter_mode,
+                    &self.interface_rx_broadcast,
+                    &labels,
+                    iface.rx_broadcast,
+                    snapshot.rx_broadcast,
+                );
+                inc_optional(
+                    self.counter_mode,
+                    &self.interface_tx_broadcast,
+                    &labels,
+                    iface.tx_broadcast,
+                    snapshot.tx_broadcast,
+                );
                 self.interface_running
                     .get_or_create(&labels)
                     .set(i64::from(iface.running));
+                // Only changes between scrapes are visible; a link that bounces
+                // back within one interval is missed
+                let flaps = self.interface_flaps.get_or_create(&labels);
+                if iface.running != snapshot.running {
+                    flaps.inc();
+                }
+                self.interface_disabled
+                    .get_or_create(&labels)
+                    .set(i64::from(iface.disabled));
+                set_optional(&self.interface_mtu, &labels, iface.mtu);
+                set_optional(&self.interface_actual_mtu, &labels, iface.actual_mtu);
                 prev.insert(
                     labels,
                     InterfaceSnapshot {
@@ -81,46 +191,141 @@ impl MetricsRegistry {
                         tx_packets: iface.tx_packets,
                         rx_errors: iface.rx_errors,
                         tx_errors: iface.tx_errors,
+                        rx_drops: iface.rx_drops,
+                        tx_drops: iface.tx_drops,
+                        rx_multicast: iface.rx_multicast,
+                        tx_multicast: iface.tx_multicast,
+                        rx_broadcast: iface.rx_broadcast,
+                        tx_broadcast: iface.tx_broadcast,
+                        running: iface.running,
                     },
                 );
             }
         }
 
+        self.commands_per_scrape
+            .get_or_create(&RouterLabels {
+                router: metrics.router_name.clone(),
+            })
+            .set(i64::from(metrics.commands_executed));
+
+        let mut collectors = HashSet::new();
+        for (collector, duration) in &metrics.collector_durations {
+            let labels = CollectorLabels {
+                router: metrics.router_name.clone(),
+                collector: collector.name().to_string(),
+            };
+            self.collector_duration_milliseconds
+                .get_or_create(&labels)
+                .set(duration.as_secs_f64() * 1000.0);
+            collectors.insert(labels);
+        }
+        for stale in self
+            .prev_collector_durations
+            .replace(&metrics.router_name, collectors)
+            .await
+        {
+            self.collector_duration_milliseconds.remove(&stale);
+        }
+
+        let command_errors: Vec<CommandLabels> = metrics
+            .command_errors
+            .iter()
+            .map(|command| CommandLabels {
+                router: metrics.router_name.clone(),
+                command: command.clone(),
+            })
+            .collect();
+        for labels in &command_errors {
+            self.command_errors.get_or_create(labels).inc();
+        }
+        self.seen_command_errors
+            .extend(&metrics.router_name, command_errors)
+            .await;
+
+        if let Some(jobs) = metrics.script_jobs {
+            #[allow(clippy::cast_possible_wrap)]
+            self.script_jobs_running
+                .get_or_create(&RouterLabels {
+                    router: metrics.router_name.clone(),
+                })
+                .set(jobs as i64);
+        }
+
+        if let Some(sessions) = metrics.userman_sessions {
+            #[allow(clippy::cast_possible_wrap)]
+            self.userman_active_sessions
+                .get_or_create(&RouterLabels {
+                    router: metrics.router_name.clone(),
+                })
+                .set(sessions as i64);
+        }
+
+        for (address_family, routes) in
+            [("ipv4", metrics.ipv4_routes), ("ipv6", metrics.ipv6_routes)]
+        {
+            let labels = AddressFamilyLabels {
+                router: metrics.router_name.clone(),
+                address_family: address_family.to_string(),
+            };
+            set_optional(&self.ip_routes, &labels, routes);
+        }
+
+        if let Some(sources) = metrics.conntrack_distinct_sources {
+            #[allow(clippy::cast_possible_wrap)]
+            self.conntrack_distinct_sources
+                .get_or_create(&RouterLabels {
+                    router: metrics.router_name.clone(),
+                })
+                .set(sources as i64);
+        }
+
+        if let Some(sessions) = &metrics.ppp_sessions {
+            self.update_ppp_sessions(&metrics.router_name, sessions)
+                .await;
+            self.update_ppp_active(&metrics.router_name, sessions).await;
+        }
+
+        if let Some(leases) = &metrics.dhcp_leases {
+            self.update_dhcp_leases(&metrics.router_name, leases).await;
+        }
+
         let router_label = RouterLabels {
             router: metrics.router_name.clone(),
         };
-        #[allow(clippy::cast_possible_wrap)]
-        {
-            self.system_cpu_load
+        if let Some(enabled) = metrics.watchdog_enabled {
+            self.watchdog_enabled
                 .get_or_create(&router_label)
-                .set(metrics.system.cpu_load as i64);
-            self.system_free_memory
-                .get_or_create(&router_label)
-                .set(metrics.system.free_memory as i64);
-            self.system_total_memory
-                .get_or_create(&router_label)
-                .set(metrics.system.total_memory as i64);
-            // parse uptime string to seconds
-            let uptime_secs = parse_uptime_to_seconds(&metrics.system.uptime);
-            self.system_uptime_seconds
+                .set(i64::from(enabled));
+        } else {
+            self.watchdog_enabled.remove(&router_label);
+        }
+        if let Some(reboots) = &metrics.unexpected_reboots {
+            self.update_unexpected_reboots(&metrics.router_name, reboots)
+                .await;
+        }
+
+        // System resources are absent when the system collector is disabled
+        if let Some(system) = &metrics.system {
+            self.update_system(&metrics.router_name, system).await;
+        }
+
+        if let Some(clock) = &metrics.clock {
+            self.update_clock(&metrics.router_name, clock).await;
+        }
+        if let Some(skew) = metrics.router_clock_skew_seconds {
+            self.router_clock_skew_seconds
                 .get_or_create(&router_label)
-                .set(uptime_secs as i64);
+                .set(skew);
         }
-        let info_labels = SystemInfoLabels {
-            router: metrics.router_name.clone(),
-            version: metrics.system.version.clone(),
-            board: metrics.system.board_name.clone(),
-        };
-        {
-            let mut prev = self.prev_system_info.lock().await;
-            if let Some(old) = prev.get(&metrics.router_name) {
-                if *old != info_labels {
-                    self.system_info.get_or_create(old).set(0);
-                }
-            }
-            prev.insert(metrics.router_name.clone(), info_labels.clone());
+
+        if let Some(table) = &metrics.conntrack_table {
+            self.update_conntrack_table(&metrics.router_name, table);
+        }
+
+        if let Some(license) = &metrics.license {
+            self.update_license(&metrics.router_name, license).await;
         }
-        self.system_info.get_or_create(&info_labels).set(1);
 
         // Update connection tracking metrics
         let now = Instant::now();
@@ -152,14 +357,24 @@ impl MetricsRegistry {
         }
 
         // Update WireGuard interface metrics
-        for wg_iface in &metrics.wireguard_interfaces {
-            let _wg_labels = WireGuardInterfaceLabels {
-                router: metrics.router_name.clone(),
-                interface: wg_iface.name.clone(),
-            };
-            // Note: We're no longer updating wireguard_interface_enabled metric
-            // as it duplicates information available in mikrotik_interface_running
-        }
+        // Note: We're no longer updating wireguard_interface_enabled metric
+        // as it duplicates information available in mikrotik_interface_running
+        let mut active_peers: HashMap<WireGuardInterfaceLabels, i64> = metrics
+            .wireguard_interfaces
+            .iter()
+            .map(|wg_iface| {
+                let labels = WireGuardInterfaceLabels {
+                    router: metrics.router_name.clone(),
+                    interface: wg_iface.name.clone(),
+                };
+                (labels, 0)
+            })
+            .collect();
+        let handshake_cutoff = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(self.wireguard_active_window)
+            .as_secs();
 
         // Update WireGuard peer metrics
         let mut deduped_peers = HashMap::new();
@@ -186,7 +401,10 @@ impl MetricsRegistry {
             let wg_peer_labels = WireGuardPeerLabels {
                 router: metrics.router_name.clone(),
                 interface: wg_peer.interface.clone(),
-                allowed_address: wg_peer.allowed_address.clone(),
+                peer: match &wg_peer.public_key {
+                    Some(public_key) => WireGuardPeerKeyLabel::PublicKey(public_key.clone()),
+                    None => WireGuardPeerKeyLabel::AllowedAddress(wg_peer.allowed_address.clone()),
+                },
             };
             if let Some(existing) = deduped_peers.get(&wg_peer_labels) {
                 if should_replace(existing, wg_peer) {
@@ -210,11 +428,23 @@ impl MetricsRegistry {
             let info_labels = WireGuardPeerInfoLabels {
                 router: wg_peer_labels.router.clone(),
                 interface: wg_peer_labels.interface.clone(),
-                allowed_address: wg_peer_labels.allowed_address.clone(),
+                peer: wg_peer_labels.peer.clone(),
                 name: wg_peer.name.clone(),
                 endpoint,
             };
             current_peer_info.insert(wg_peer_labels.clone(), info_labels.clone());
+            let active = active_peers
+                .entry(WireGuardInterfaceLabels {
+                    router: wg_peer_labels.router.clone(),
+                    interface: wg_peer_labels.interface.clone(),
+                })
+                .or_insert(0);
+            if wg_peer
+                .latest_handshake
+                .is_some_and(|ts| ts >= handshake_cutoff)
+            {
+                *active += 1;
+            }
             #[allow(clippy::cast_possible_wrap)]
             {
                 self.wireguard_peer_rx_bytes
@@ -280,5 +510,1165 @@ impl MetricsRegistry {
             }
             *prev_map = current_peer_info;
         }
+
+        for (labels, active) in &active_peers {
+            self.wireguard_peers_active
+                .get_or_create(labels)
+                .set(*active);
+        }
+        let wireguard_active =
+            (!active_peers.is_empty()).then(|| active_peers.values().sum::<i64>());
+        self.update_vpn_tunnels(metrics, wireguard_active).await;
+        for stale in self
+            .prev_wireguard_interfaces
+            .replace(&metrics.router_name, active_peers.into_keys().collect())
+            .await
+        {
+            self.wireguard_peers_active.remove(&stale);
+        }
+        self.update_ipsec_peers(&metrics.router_name, &metrics.ipsec_peers)
+            .await;
+
+        self.update_interface_queues(&metrics.router_name, &metrics.interface_queues)
+            .await;
+        self.update_ip_services(&metrics.router_name, &metrics.ip_services)
+            .await;
+        self.update_bonds(&metrics.router_name, &metrics.bonds)
+            .await;
+        self.update_wifi(&metrics.router_name, &metrics.wifi_channels)
+            .await;
+        self.update_sfp(&metrics.router_name, &metrics.sfp_modules)
+            .await;
+        self.update_wireless_stations(&metrics.router_name, &metrics.wireless_stations)
+            .await;
+        self.update_managed_devices(&metrics.router_name, &metrics.managed_devices)
+            .await;
+        self.update_capsman_clients(&metrics.router_name, &metrics.capsman_clients)
+            .await;
+        self.update_ping(&metrics.router_name, &metrics.ping_targets)
+            .await;
+        self.update_simple_queues(&metrics.router_name, &metrics.simple_queues)
+            .await;
+        self.update_default_routes(&metrics.router_name, &metrics.default_routes)
+            .await;
+        self.update_tunnels(&metrics.router_name, &metrics.tunnels)
+            .await;
+        self.update_vrrp(&metrics.router_name, &metrics.vrrp).await;
+        self.update_bgp_sessions(&metrics.router_name, &metrics.bgp_sessions)
+            .await;
+        self.update_containers(&metrics.router_name, &metrics.containers)
+            .await;
+        self.update_health(&metrics.router_name, &metrics.health)
+            .await;
+        self.update_custom(&metrics.router_name, &metrics.custom_metrics)
+            .await;
+        self.update_firewall_rules(&metrics.router_name, &metrics.firewall_rules)
+            .await;
+        self.update_cpu_cores(&metrics.router_name, &metrics.cpu_cores)
+            .await;
+    }
+
+    /// Update management service gauges, removing services that disappeared
+    async fn update_ip_services(&self, router_name: &str, services: &[IpServiceStats]) {
+        let mut current = HashSet::new();
+        for service in services {
+            let labels = IpServiceLabels {
+                router: router_name.to_string(),
+                service: service.name.clone(),
+            };
+            self.ip_service_enabled
+                .get_or_create(&labels)
+                .set(i64::from(service.enabled));
+            self.ip_service_port
+                .get_or_create(&labels)
+                .set(i64::from(service.port));
+            current.insert(labels);
+        }
+        for stale in self.prev_ip_services.replace(router_name, current).await {
+            self.ip_service_enabled.remove(&stale);
+            self.ip_service_port.remove(&stale);
+        }
+    }
+
+    /// Update bond and bond slave gauges, removing bonds or slaves that disappeared
+    async fn update_bonds(&self, router_name: &str, bonds: &[BondStats]) {
+        let mut current_bonds = HashSet::new();
+        let mut current_slaves = HashSet::new();
+        for bond in bonds {
+            let bond_labels = BondLabels {
+                router: router_name.to_string(),
+                bond: bond.name.clone(),
+            };
+            #[allow(clippy::cast_possible_wrap)]
+            self.bonding_active_slaves
+                .get_or_create(&bond_labels)
+                .set(bond.active_slaves() as i64);
+            current_bonds.insert(bond_labels);
+
+            for slave in &bond.slaves {
+                let slave_labels = BondSlaveLabels {
+                    router: router_name.to_string(),
+                    bond: bond.name.clone(),
+                    slave: slave.name.clone(),
+                };
+                self.bonding_slave_up
+                    .get_or_create(&slave_labels)
+                    .set(i64::from(slave.up));
+                current_slaves.insert(slave_labels);
+            }
+        }
+        for stale in self.prev_bonds.replace(router_name, current_bonds).await {
+            self.bonding_active_slaves.remove(&stale);
+        }
+        for stale in self
+            .prev_bond_slaves
+            .replace(router_name, current_slaves)
+            .await
+        {
+            self.bonding_slave_up.remove(&stale);
+        }
+    }
+
+    /// Update wireless channel utilization, removing interfaces that disappeared
+    async fn update_wifi(&self, router_name: &str, channels: &[WifiChannelStats]) {
+        let mut current = HashSet::new();
+        for channel in channels {
+            let labels = InterfaceLabels {
+                router: router_name.to_string(),
+                interface: channel.interface.clone(),
+            };
+            self.wifi_channel_utilization_ratio
+                .get_or_create(&labels)
+                .set(channel.utilization);
+            current.insert(labels);
+        }
+        for stale in self
+            .prev_wifi_interfaces
+            .replace(router_name, current)
+            .await
+        {
+            self.wifi_channel_utilization_ratio.remove(&stale);
+        }
+    }
+
+    /// Update SFP module diagnostics, removing modules that disappeared
+    async fn update_sfp(&self, router_name: &str, modules: &[SfpStats]) {
+        let mut current = HashSet::new();
+        for module in modules {
+            let labels = InterfaceLabels {
+                router: router_name.to_string(),
+                interface: module.interface.clone(),
+            };
+            set_optional_f64(&self.sfp_rx_power_dbm, &labels, module.rx_power_dbm);
+            set_optional_f64(&self.sfp_tx_power_dbm, &labels, module.tx_power_dbm);
+            set_optional_f64(
+                &self.sfp_temperature_celsius,
+                &labels,
+                module.temperature_celsius,
+            );
+            set_optional_f64(
+                &self.sfp_supply_voltage_volts,
+                &labels,
+                module.supply_voltage,
+            );
+            current.insert(labels);
+        }
+        for stale in self.prev_sfp_interfaces.replace(router_name, current).await {
+            self.sfp_rx_power_dbm.remove(&stale);
+            self.sfp_tx_power_dbm.remove(&stale);
+            self.sfp_temperature_celsius.remove(&stale);
+            self.sfp_supply_voltage_volts.remove(&stale);
+        }
+    }
+
+    /// Update signal and rate of registered wireless stations, removing
+    /// stations that disconnected
+    ///
+    /// MACs are normalized so a station keeps its series across RouterOS
+    /// versions; values a station doesn't report are left out.
+    async fn update_wireless_stations(&self, router_name: &str, stations: &[WirelessStationStats]) {
+        let mut current = HashSet::new();
+        for station in stations {
+            let labels = WirelessStationLabels {
+                router: router_name.to_string(),
+                interface: station.interface.clone(),
+                mac: normalize_mac(&station.mac_address),
+            };
+            match station.signal_strength {
+                Some(dbm) => {
+                    self.wireless_station_signal_dbm
+                        .get_or_create(&labels)
+                        .set(dbm);
+                }
+                None => {
+                    self.wireless_station_signal_dbm.remove(&labels);
+                }
+            }
+            match station.tx_rate {
+                #[allow(clippy::cast_possible_wrap)]
+                Some(bps) => {
+                    self.wireless_station_tx_rate_bps
+                        .get_or_create(&labels)
+                        .set(bps as i64);
+                }
+                None => {
+                    self.wireless_station_tx_rate_bps.remove(&labels);
+                }
+            }
+            current.insert(labels);
+        }
+        for stale in self
+            .prev_wireless_stations
+            .replace(router_name, current)
+            .await
+        {
+            self.wireless_station_signal_dbm.remove(&stale);
+            self.wireless_station_tx_rate_bps.remove(&stale);
+        }
+    }
+
+    /// Update per-CAP client counts, removing CAPs no longer managed
+    async fn update_managed_devices(&self, router_name: &str, devices: &[ManagedDeviceStats]) {
+        let mut current = HashSet::new();
+        for device in devices {
+            let labels = ManagedDeviceLabels {
+                router: router_name.to_string(),
+                managed_device: device.identity.clone(),
+            };
+            #[allow(clippy::cast_possible_wrap)]
+            self.capsman_clients
+                .get_or_create(&labels)
+                .set(device.clients as i64);
+            current.insert(labels);
+        }
+        for stale in self
+            .prev_managed_devices
+            .replace(router_name, current)
+            .await
+        {
+            self.capsman_clients.remove(&stale);
+        }
+    }
+
+    /// Update CAPsMAN client counts per interface and SSID, and client signal
+    ///
+    /// Interfaces whose clients all left are set to zero and removed once
+    /// they expire; clients that disconnected or roamed to another interface
+    /// are removed right away.
+    async fn update_capsman_clients(&self, router_name: &str, clients: &[CapsmanClientStats]) {
+        let now = Instant::now();
+        let mut counts: HashMap<CapsmanInterfaceLabels, i64> = HashMap::new();
+        let mut current_clients = HashSet::new();
+        for client in clients {
+            *counts
+                .entry(CapsmanInterfaceLabels {
+                    router: router_name.to_string(),
+                    interface: client.interface.clone(),
+                    ssid: client.ssid.clone(),
+                })
+                .or_insert(0) += 1;
+
+            let labels = CapsmanClientLabels {
+                router: router_name.to_string(),
+                interface: client.interface.clone(),
+                ssid: client.ssid.clone(),
+                mac: normalize_mac(&client.mac_address),
+            };
+            match client.signal_strength {
+                Some(dbm) => {
+                    self.capsman_client_signal_dbm
+                        .get_or_create(&labels)
+                        .set(dbm);
+                }
+                None => {
+                    self.capsman_client_signal_dbm.remove(&labels);
+                }
+            }
+            current_clients.insert(labels);
+        }
+        for stale in self
+            .prev_capsman_clients
+            .replace(router_name, current_clients)
+            .await
+        {
+            self.capsman_client_signal_dbm.remove(&stale);
+        }
+
+        let mut interface_seen = self.capsman_interface_last_seen.lock().await;
+        let mut current_interfaces = HashSet::new();
+        for (labels, count) in counts {
+            self.capsman_interface_clients
+                .get_or_create(&labels)
+                .set(count);
+            interface_seen.insert(labels.clone(), now);
+            current_interfaces.insert(labels);
+        }
+        let mut prev_interfaces = self.prev_capsman_interfaces.lock().await;
+        let prev_labels = prev_interfaces.entry(router_name.to_string()).or_default();
+        for stale in prev_labels.difference(&current_interfaces) {
+            self.capsman_interface_clients.get_or_create(stale).set(0);
+        }
+        *prev_labels = current_interfaces;
+    }
+
+    /// Count PPP sessions not seen in the previous scrape as established
+    ///
+    /// Sessions present on the first scrape of a router were established
+    /// before the exporter started and are not counted.
+    async fn update_ppp_sessions(&self, router_name: &str, sessions: &[PppSessionStats]) {
+        let current: HashSet<String> = sessions.iter().map(|s| s.session.clone()).collect();
+        let mut prev = self.prev_ppp_sessions.lock().await;
+        let mut established: HashMap<PppServiceLabels, u64> = HashMap::new();
+        if let Some(previous) = prev.get(router_name) {
+            for session in sessions {
+                if !previous.contains(&session.session) {
+                    let labels = PppServiceLabels {
+                        router: router_name.to_string(),
+                        service: session.service.clone(),
+                    };
+                    *established.entry(labels).or_default() += 1;
+                }
+            }
+        }
+        prev.insert(router_name.to_string(), current);
+        drop(prev);
+
+        for (labels, count) in &established {
+            self.ppp_sessions_established
+                .get_or_create(labels)
+                .inc_by(*count);
+        }
+        self.seen_ppp_services
+            .extend(router_name, established.into_keys())
+            .await;
+    }
+
+    /// Update active PPP session counts and per-user session uptime
+    ///
+    /// A user with several sessions reports the longest-running one.
+    /// Disconnected users are reset to zero and left for the TTL cleanup to
+    /// remove.
+    async fn update_ppp_active(&self, router_name: &str, sessions: &[PppSessionStats]) {
+        let mut active: HashMap<PppServiceLabels, i64> = HashMap::new();
+        let mut uptimes: HashMap<PppUserLabels, u64> = HashMap::new();
+        for session in sessions {
+            *active
+                .entry(PppServiceLabels {
+                    router: router_name.to_string(),
+                    service: session.service.clone(),
+                })
+                .or_default() += 1;
+            if session.name.is_empty() {
+                continue;
+            }
+            let uptime = parse_uptime_to_seconds(&session.uptime);
+            let labels = PppUserLabels {
+                router: router_name.to_string(),
+                user: session.name.clone(),
+            };
+            let longest = uptimes.entry(labels).or_default();
+            *longest = (*longest).max(uptime);
+        }
+
+        for (labels, count) in &active {
+            self.ppp_active_sessions.get_or_create(labels).set(*count);
+        }
+        for stale in self
+            .prev_ppp_services
+            .replace(router_name, active.into_keys().collect())
+            .await
+        {
+            self.ppp_active_sessions.remove(&stale);
+        }
+
+        let now = Instant::now();
+        let mut user_seen = self.ppp_user_last_seen.lock().await;
+        for (labels, uptime) in &uptimes {
+            #[allow(clippy::cast_possible_wrap)]
+            self.ppp_session_uptime_seconds
+                .get_or_create(labels)
+                .set(*uptime as i64);
+            user_seen.insert(labels.clone(), now);
+        }
+        let current: HashSet<PppUserLabels> = uptimes.into_keys().collect();
+        let mut prev_users = self.prev_ppp_users.lock().await;
+        let prev_labels = prev_users.entry(router_name.to_string()).or_default();
+        for stale in prev_labels.difference(&current) {
+            self.ppp_session_uptime_seconds.get_or_create(stale).set(0);
+        }
+        *prev_labels = current;
+    }
+
+    /// Count unexpected reboot log entries not seen in the previous scrape
+    ///
+    /// Entries present on the first scrape of a router predate the exporter
+    /// and are not counted.
+    async fn update_unexpected_reboots(&self, router_name: &str, reboots: &[UnexpectedReboot]) {
+        let current: HashSet<String> = reboots.iter().map(|r| r.event.clone()).collect();
+        let mut prev = self.prev_reboot_events.lock().await;
+        let mut counted: HashMap<RebootLabels, u64> = HashMap::new();
+        if let Some(previous) = prev.get(router_name) {
+            for reboot in reboots {
+                if !previous.contains(&reboot.event) {
+                    let labels = RebootLabels {
+                        router: router_name.to_string(),
+                        reason: reboot.reason.clone(),
+                    };
+                    *counted.entry(labels).or_default() += 1;
+                }
+            }
+        }
+        prev.insert(router_name.to_string(), current);
+        drop(prev);
+
+        for (labels, count) in &counted {
+            self.unexpected_reboots.get_or_create(labels).inc_by(*count);
+        }
+        self.seen_reboot_reasons
+            .extend(router_name, counted.into_keys())
+            .await;
+    }
+
+    /// Update DHCP lease counts per server and status, and count bound leases
+    /// whose MAC had no bound lease in the previous scrape as granted
+    ///
+    /// Leases bound on the first scrape of a router are not counted as granted.
+    async fn update_dhcp_leases(&self, router_name: &str, leases: &[DhcpLeaseStats]) {
+        let bound: Vec<(&str, &str)> = leases
+            .iter()
+            .filter(|lease| lease.is_bound())
+            .filter_map(|lease| Some((lease.server.as_str(), lease.mac_address.as_deref()?)))
+            .collect();
+        let current: HashSet<String> = bound.iter().map(|(_, mac)| mac.to_string()).collect();
+        let mut prev = self.prev_dhcp_bound_macs.lock().await;
+        let mut granted: HashMap<DhcpServerLabels, u64> = HashMap::new();
+        if let Some(previous) = prev.get(router_name) {
+            for (server, mac) in &bound {
+                if !previous.contains(*mac) {
+                    let labels = DhcpServerLabels {
+                        router: router_name.to_string(),
+                        server: (*server).to_string(),
+                    };
+                    *granted.entry(labels).or_default() += 1;
+                }
+            }
+        }
+        prev.insert(router_name.to_string(), current);
+        drop(prev);
+
+        let mut counts: HashMap<DhcpLeaseLabels, i64> = HashMap::new();
+        for lease in leases {
+            let labels = DhcpLeaseLabels {
+                router: router_name.to_string(),
+                server: lease.server.clone(),
+                status: lease.status.clone(),
+            };
+            *counts.entry(labels).or_default() += 1;
+        }
+        for (labels, count) in &counts {
+            self.dhcp_leases.get_or_create(labels).set(*count);
+        }
+        let current = counts.into_keys().collect();
+        for stale in self.prev_dhcp_leases.replace(router_name, current).await {
+            self.dhcp_leases.remove(&stale);
+        }
+
+        for (labels, count) in &granted {
+            self.dhcp_leases_granted
+                .get_or_create(labels)
+                .inc_by(*count);
+        }
+        self.seen_dhcp_servers
+            .extend(router_name, granted.into_keys())
+            .await;
+    }
+
+    /// Update which gateways carry the active default route
+    async fn update_default_routes(&self, router_name: &str, routes: &[DefaultRouteStats]) {
+        let mut current = HashSet::new();
+        for route in routes {
+            let labels = GatewayLabels {
+                router: router_name.to_string(),
+                gateway: route.gateway.clone(),
+            };
+            self.default_route_active
+                .get_or_create(&labels)
+                .set(i64::from(route.active));
+            current.insert(labels);
+        }
+        for stale in self.prev_default_routes.replace(router_name, current).await {
+            self.default_route_active.remove(&stale);
+        }
+    }
+
+    /// Update per-core CPU load, removing cores no longer reported
+    async fn update_cpu_cores(&self, router_name: &str, cores: &[CpuCoreStats]) {
+        let mut current = HashSet::new();
+        for core in cores {
+            let labels = CpuCoreLabels {
+                router: router_name.to_string(),
+                core: core.core.clone(),
+            };
+            #[allow(clippy::cast_possible_wrap)]
+            self.system_cpu_core_load
+                .get_or_create(&labels)
+                .set(core.load as i64);
+            current.insert(labels);
+        }
+        for stale in self.prev_cpu_cores.replace(router_name, current).await {
+            self.system_cpu_core_load.remove(&stale);
+        }
+    }
+
+    /// Update tunnel running gauges, removing tunnels that disappeared
+    async fn update_tunnels(&self, router_name: &str, tunnels: &[TunnelStats]) {
+        let mut current = HashSet::new();
+        for tunnel in tunnels {
+            let labels = TunnelLabels {
+                router: router_name.to_string(),
+                r#type: tunnel.kind.clone(),
+                name: tunnel.name.clone(),
+            };
+            self.tunnel_running
+                .get_or_create(&labels)
+                .set(i64::from(tunnel.running));
+            current.insert(labels);
+        }
+        for stale in self.prev_tunnels.replace(router_name, current).await {
+            self.tunnel_running.remove(&stale);
+        }
+    }
+
+    /// Update VRRP master gauges, removing VRRP interfaces that disappeared
+    async fn update_vrrp(&self, router_name: &str, vrrp: &[VrrpStats]) {
+        let mut current = HashSet::new();
+        for entry in vrrp {
+            let labels = InterfaceLabels {
+                router: router_name.to_string(),
+                interface: entry.interface.clone(),
+            };
+            self.vrrp_master
+                .get_or_create(&labels)
+                .set(i64::from(entry.master));
+            current.insert(labels);
+        }
+        for stale in self.prev_vrrp.replace(router_name, current).await {
+            self.vrrp_master.remove(&stale);
+        }
+    }
+
+    /// Update BGP session gauges, removing sessions that disappeared
+    ///
+    /// Uptime is only exported while the session is established.
+    async fn update_bgp_sessions(&self, router_name: &str, sessions: &[BgpSessionStats]) {
+        let mut current = HashSet::new();
+        for session in sessions {
+            let labels = BgpSessionLabels {
+                router: router_name.to_string(),
+                peer: session.name.clone(),
+                remote_as: session.remote_as.clone(),
+            };
+            self.bgp_session_state
+                .get_or_create(&labels)
+                .set(i64::from(session.established()));
+            set_optional(
+                &self.bgp_session_prefix_count,
+                &labels,
+                session.prefix_count,
+            );
+            let uptime = session
+                .uptime
+                .as_deref()
+                .filter(|_| session.established())
+                .map(parse_uptime_to_seconds);
+            set_optional(&self.bgp_session_uptime_seconds, &labels, uptime);
+            current.insert(labels);
+        }
+        for stale in self.prev_bgp_sessions.replace(router_name, current).await {
+            self.bgp_session_state.remove(&stale);
+            self.bgp_session_prefix_count.remove(&stale);
+            self.bgp_session_uptime_seconds.remove(&stale);
+        }
+    }
+
+    /// Update container status and info series, removing containers that
+    /// disappeared
+    ///
+    /// Every known status gets a series, so alerts can match on
+    /// `status="stopped"` being 1.
+    async fn update_containers(&self, router_name: &str, containers: &[ContainerStats]) {
+        let mut current_status = HashSet::new();
+        let mut current_info = HashSet::new();
+        for container in containers {
+            let mut statuses = CONTAINER_STATUSES.to_vec();
+            if !statuses.contains(&container.status.as_str()) {
+                statuses.push(&container.status);
+            }
+            for status in statuses {
+                let labels = ContainerStatusLabels {
+                    router: router_name.to_string(),
+                    container: container.name.clone(),
+                    status: status.to_string(),
+                };
+                self.container_status
+                    .get_or_create(&labels)
+                    .set(i64::from(status == container.status));
+                current_status.insert(labels);
+            }
+            let labels = ContainerInfoLabels {
+                router: router_name.to_string(),
+                container: container.name.clone(),
+                tag: container.tag.clone(),
+            };
+            self.container_info.get_or_create(&labels).set(1);
+            current_info.insert(labels);
+        }
+        for stale in self
+            .prev_container_status
+            .replace(router_name, current_status)
+            .await
+        {
+            self.container_status.remove(&stale);
+        }
+        for stale in self
+            .prev_container_info
+            .replace(router_name, current_info)
+            .await
+        {
+            self.container_info.remove(&stale);
+        }
+    }
+
+    /// Update IPsec peer metrics
+    ///
+    /// Like WireGuard peers, torn-down peers are reset to zero and left for
+    /// the TTL cleanup to remove.
+    async fn update_ipsec_peers(&self, router_name: &str, peers: &[IpsecPeerStats]) {
+        let now = Instant::now();
+        let mut current_peers = HashSet::new();
+        let mut peer_seen = self.ipsec_peer_last_seen.lock().await;
+        for peer in peers {
+            let labels = IpsecPeerLabels {
+                router: router_name.to_string(),
+                peer: peer.remote_address.clone(),
+            };
+            self.ipsec_peer_state
+                .get_or_create(&labels)
+                .set(i64::from(peer.established()));
+            #[allow(clippy::cast_possible_wrap)]
+            {
+                self.ipsec_peer_rx_bytes
+                    .get_or_create(&labels)
+                    .set(peer.rx_bytes as i64);
+                self.ipsec_peer_tx_bytes
+                    .get_or_create(&labels)
+                    .set(peer.tx_bytes as i64);
+            }
+            peer_seen.insert(labels.clone(), now);
+            current_peers.insert(labels);
+        }
+
+        let mut prev_peers = self.prev_ipsec_peers.lock().await;
+        let prev_labels = prev_peers.entry(router_name.to_string()).or_default();
+        for stale in prev_labels.difference(&current_peers) {
+            self.ipsec_peer_state.get_or_create(stale).set(0);
+            self.ipsec_peer_rx_bytes.get_or_create(stale).set(0);
+            self.ipsec_peer_tx_bytes.get_or_create(stale).set(0);
+        }
+        *prev_labels = current_peers;
+    }
+
+    /// Update active VPN tunnels per type from the ipsec, wireguard and ppp
+    /// collectors
+    ///
+    /// A type is only exported when its collector ran; WireGuard counts peers
+    /// with a recent handshake, and only routers with WireGuard interfaces
+    /// report it.
+    async fn update_vpn_tunnels(&self, metrics: &RouterMetrics, wireguard_active: Option<i64>) {
+        let mut active: Vec<(&str, i64)> = Vec::new();
+        if let Some(peers) = metrics.ipsec_active_peers {
+            #[allow(clippy::cast_possible_wrap)]
+            active.push(("ipsec", peers as i64));
+        }
+        if let Some(peers) = wireguard_active {
+            active.push(("wireguard", peers));
+        }
+        if let Some(sessions) = &metrics.ppp_sessions {
+            for service in PPP_VPN_SERVICES {
+                #[allow(clippy::cast_possible_wrap)]
+                let count = sessions.iter().filter(|s| s.service == service).count() as i64;
+                active.push((service, count));
+            }
+        }
+
+        let mut current = HashSet::new();
+        for (kind, count) in active {
+            let labels = VpnTunnelLabels {
+                router: metrics.router_name.clone(),
+                r#type: kind.to_string(),
+            };
+            self.vpn_tunnels_active.get_or_create(&labels).set(count);
+            current.insert(labels);
+        }
+        for stale in self
+            .prev_vpn_tunnels
+            .replace(&metrics.router_name, current)
+            .await
+        {
+            self.vpn_tunnels_active.remove(&stale);
+        }
+    }
+
+    /// Update custom collector gauges, removing entries no longer reported
+    async fn update_custom(&self, router_name: &str, metrics: &[CustomMetric]) {
+        let mut current = HashSet::new();
+        for metric in metrics {
+            let labels = CustomLabels {
+                router: router_name.to_string(),
+                labels: metric.labels.clone(),
+            };
+            self.custom_family(&metric.collector)
+                .await
+                .get_or_create(&labels)
+                .set(metric.value);
+            current.insert((metric.collector.clone(), labels));
+        }
+        let stale = self.prev_custom_metrics.replace(router_name, current).await;
+        let custom_gauges = self.custom_gauges.lock().await;
+        for (collector, labels) in &stale {
+            if let Some(family) = custom_gauges.get(collector) {
+                family.remove(labels);
+            }
+        }
+    }
+
+    /// Gauge family of a custom collector, registered on first use
+    ///
+    /// Custom collectors come from configuration that may be reloaded, so
+    /// their families can't be registered up front.
+    async fn custom_family(&self, collector: &str) -> CustomGaugeFamily {
+        let mut custom_gauges = self.custom_gauges.lock().await;
+        if let Some(family) = custom_gauges.get(collector) {
+            return family.clone();
+        }
+        let family = CustomGaugeFamily::default();
+        self.registry.lock().await.register(
+            format!("custom_{collector}"),
+            format!("Value of custom collector '{collector}'"),
+            family.clone(),
+        );
+        custom_gauges.insert(collector.to_string(), family.clone());
+        family
+    }
+
+    /// Update health sensor gauges, removing sensors no longer reported
+    async fn update_health(&self, router_name: &str, health: &HealthStats) {
+        let mut current = HashSet::new();
+        for (family, sensors) in [
+            (&self.system_temperature_celsius, &health.temperatures),
+            (&self.system_voltage, &health.voltages),
+            (&self.system_fan_rpm, &health.fans),
+        ] {
+            for sensor in sensors {
+                let labels = HealthSensorLabels {
+                    router: router_name.to_string(),
+                    sensor: sensor.name.clone(),
+                };
+                family.get_or_create(&labels).set(sensor.value);
+                current.insert(labels);
+            }
+        }
+        for stale in self.prev_health_sensors.replace(router_name, current).await {
+            self.system_temperature_celsius.remove(&stale);
+            self.system_voltage.remove(&stale);
+            self.system_fan_rpm.remove(&stale);
+        }
+    }
+
+    /// Update interface queue drops and depth, removing queues no longer reported
+    async fn update_interface_queues(&self, router_name: &str, queues: &[InterfaceQueueStats]) {
+        let mut prev = self.prev_interface_queue_drops.lock().await;
+        let mut current = HashSet::new();
+        for queue in queues {
+            let labels = InterfaceLabels {
+                router: router_name.to_string(),
+                interface: queue.interface.clone(),
+            };
+            let previous = prev.get(&labels).copied().unwrap_or(queue.drops);
+            self.interface_queue_drops
+                .get_or_create(&labels)
+                .inc_by(queue.drops.saturating_sub(previous));
+            #[allow(clippy::cast_possible_wrap)]
+            self.interface_queue_length
+                .get_or_create(&labels)
+                .set(queue.queued_packets as i64);
+            prev.insert(labels.clone(), queue.drops);
+            current.insert(labels);
+        }
+        prev.retain(|labels, _| {
+            if labels.router != router_name || current.contains(labels) {
+                return true;
+            }
+            self.interface_queue_drops.remove(labels);
+            self.interface_queue_length.remove(labels);
+            false
+        });
+    }
+
+    /// Update firewall rule counters, removing rules no longer reported
+    ///
+    /// Rules sharing a chain and comment are summed into one series.
+    async fn update_firewall_rules(&self, router_name: &str, rules: &[FirewallRuleStats]) {
+        let mut totals: HashMap<FirewallRuleLabels, (u64, u64)> = HashMap::new();
+        for rule in rules {
+            let labels = FirewallRuleLabels {
+                router: router_name.to_string(),
+                chain: rule.chain.clone(),
+                comment: rule.comment.clone(),
+                id: IdLabel(rule.id.clone()),
+            };
+            let total = totals.entry(labels).or_default();
+            total.0 = total.0.saturating_add(rule.bytes);
+            total.1 = total.1.saturating_add(rule.packets);
+        }
+
+        let mut prev = self.prev_firewall_rules.lock().await;
+        for (labels, (bytes, packets)) in &totals {
+            let (prev_bytes, prev_packets) =
+                prev.get(labels).copied().unwrap_or((*bytes, *packets));
+            self.firewall_filter_bytes
+                .get_or_create(labels)
+                .inc_by(bytes.saturating_sub(prev_bytes));
+            self.firewall_filter_packets
+                .get_or_create(labels)
+                .inc_by(packets.saturating_sub(prev_packets));
+            prev.insert(labels.clone(), (*bytes, *packets));
+        }
+        prev.retain(|labels, _| {
+            if labels.router != router_name || totals.contains_key(labels) {
+                return true;
+            }
+            self.firewall_filter_bytes.remove(labels);
+            self.firewall_filter_packets.remove(labels);
+            false
+        });
+    }
+
+    /// Update ping RTT and loss, removing targets no longer configured
+    ///
+    /// The RTT series is dropped while a target doesn't answer at all, so a
+    /// stale latency isn't reported next to 100% loss.
+    async fn update_ping(&self, router_name: &str, targets: &[PingStats]) {
+        let mut current = HashSet::new();
+        for stats in targets {
+            let labels = PingLabels {
+                router: router_name.to_string(),
+                target: stats.target.clone(),
+            };
+            match stats.rtt_ms {
+                Some(rtt) => {
+                    self.ping_rtt_ms.get_or_create(&labels).set(rtt);
+                }
+                None => {
+                    self.ping_rtt_ms.remove(&labels);
+                }
+            }
+            self.ping_loss_ratio
+                .get_or_create(&labels)
+                .set(stats.loss_ratio);
+            current.insert(labels);
+        }
+        for stale in self.prev_ping_targets.replace(router_name, current).await {
+            self.ping_rtt_ms.remove(&stale);
+            self.ping_loss_ratio.remove(&stale);
+        }
+    }
+
+    /// Update configured simple queue limits and apply counter deltas,
+    /// removing queues that were deleted
+    ///
+    /// Counters restart from zero when a queue is reset; the saturating delta
+    /// then adds nothing for that scrape.
+    async fn update_simple_queues(&self, router_name: &str, queues: &[QueueStats]) {
+        let mut prev = self.prev_simple_queue_counters.lock().await;
+        let mut current = HashSet::new();
+        for queue in queues {
+            for (direction, max_limit, limit_at, bytes, packets) in [
+                (
+                    "upload",
+                    queue.max_limit_upload,
+                    queue.limit_at_upload,
+                    queue.bytes_upload,
+                    queue.packets_upload,
+                ),
+                (
+                    "download",
+                    queue.max_limit_download,
+                    queue.limit_at_download,
+                    queue.bytes_download,
+                    queue.packets_download,
+                ),
+            ] {
+                let labels = QueueLabels {
+                    router: router_name.to_string(),
+                    queue: queue.name.clone(),
+                    direction: direction.to_string(),
+                    id: IdLabel(queue.id.clone()),
+                };
+                #[allow(clippy::cast_possible_wrap)]
+                {
+                    self.queue_simple_max_limit_bps
+                        .get_or_create(&labels)
+                        .set(max_limit as i64);
+                    self.queue_simple_limit_at_bps
+                        .get_or_create(&labels)
+                        .set(limit_at as i64);
+                }
+                let (prev_bytes, prev_packets) =
+                    prev.get(&labels).copied().unwrap_or((bytes, packets));
+                self.queue_simple_bytes
+                    .get_or_create(&labels)
+                    .inc_by(bytes.saturating_sub(prev_bytes));
+                self.queue_simple_packets
+                    .get_or_create(&labels)
+                    .inc_by(packets.saturating_sub(prev_packets));
+                prev.insert(labels.clone(), (bytes, packets));
+                current.insert(labels);
+            }
+        }
+        for stale in self.prev_queues.replace(router_name, current).await {
+            self.queue_simple_max_limit_bps.remove(&stale);
+            self.queue_simple_limit_at_bps.remove(&stale);
+            self.queue_simple_bytes.remove(&stale);
+            self.queue_simple_packets.remove(&stale);
+            prev.remove(&stale);
+        }
+    }
+
+    /// Update clock offset and timezone info, removing a previous timezone label
+    async fn update_clock(&self, router_name: &str, clock: &ClockStats) {
+        self.system_time_offset_seconds
+            .get_or_create(&RouterLabels {
+                router: router_name.to_string(),
+            })
+            .set(clock.offset_seconds);
+        let labels = TimezoneLabels {
+            router: router_name.to_string(),
+            timezone: clock.timezone.clone(),
+        };
+        self.system_timezone_info.get_or_create(&labels).set(1);
+        let current = HashSet::from([labels]);
+        for stale in self.prev_timezones.replace(router_name, current).await {
+            self.system_timezone_info.remove(&stale);
+        }
+    }
+
+    /// Update connection tracking table usage against its limit
+    fn update_conntrack_table(&self, router_name: &str, table: &ConntrackTableStats) {
+        let router_label = RouterLabels {
+            router: router_name.to_string(),
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        self.conntrack_table_entries
+            .get_or_create(&router_label)
+            .set(table.entries as i64);
+        if let Some(max) = table.max_entries {
+            #[allow(clippy::cast_possible_wrap)]
+            self.conntrack_table_max
+                .get_or_create(&router_label)
+                .set(max as i64);
+        } else {
+            self.conntrack_table_max.remove(&router_label);
+        }
+    }
+
+    /// Update the license level, its info series and the CHR renewal deadline
+    async fn update_license(&self, router_name: &str, license: &LicenseStats) {
+        let router_label = RouterLabels {
+            router: router_name.to_string(),
+        };
+        if let Some(level) = license.level_number {
+            self.license_level.get_or_create(&router_label).set(level);
+        } else {
+            self.license_level.remove(&router_label);
+        }
+        if let Some(deadline) = license.deadline {
+            self.license_deadline_timestamp_seconds
+                .get_or_create(&router_label)
+                .set(deadline);
+        } else {
+            self.license_deadline_timestamp_seconds
+                .remove(&router_label);
+        }
+        let labels = LicenseLabels {
+            router: router_name.to_string(),
+            level: license.level.clone(),
+        };
+        self.license_info.get_or_create(&labels).set(1);
+        let current = HashSet::from([labels]);
+        for stale in self.prev_licenses.replace(router_name, current).await {
+            self.license_info.remove(&stale);
+        }
+    }
+
+    /// Update system resource gauges and the version/board info series
+    async fn update_system(&self, router_name: &str, system: &SystemResource) {
+        let router_label = RouterLabels {
+            router: router_name.to_string(),
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        {
+            self.system_cpu_load
+                .get_or_create(&router_label)
+                .set(system.cpu_load as i64);
+            set_optional(&self.system_cpu_count, &router_label, system.cpu_count);
+            self.system_free_memory
+                .get_or_create(&router_label)
+                .set(system.free_memory as i64);
+            self.system_total_memory
+                .get_or_create(&router_label)
+                .set(system.total_memory as i64);
+            self.system_free_hdd
+                .get_or_create(&router_label)
+                .set(system.free_hdd_space as i64);
+            self.system_total_hdd
+                .get_or_create(&router_label)
+                .set(system.total_hdd_space as i64);
+            // parse uptime string to seconds
+            let uptime_secs = parse_uptime_to_seconds(&system.uptime);
+            if uptime_parse_failed(&system.uptime, uptime_secs) {
+                tracing::debug!(
+                    "Router {} reported an uptime that could not be parsed: {:?}",
+                    router_name,
+                    system.uptime
+                );
+                self.uptime_parse_failures
+                    .get_or_create(&router_label)
+                    .inc();
+            }
+            self.system_uptime_seconds
+                .get_or_create(&router_label)
+                .set(uptime_secs as i64);
+        }
+        if let Some(ratio) = hdd_usage_ratio(system) {
+            self.system_hdd_usage_ratio
+                .get_or_create(&router_label)
+                .set(ratio);
+        }
+        let info_labels = SystemInfoLabels {
+            router: router_name.to_string(),
+            version: system.version.clone(),
+            board: system.board_name.clone(),
+            architecture: system.architecture.clone(),
+        };
+        {
+            let mut prev = self.prev_system_info.lock().await;
+            if let Some(old) = prev.get(router_name) {
+                if *old != info_labels {
+                    self.system_info.get_or_create(old).set(0);
+                }
+            }
+            prev.insert(router_name.to_string(), info_labels.clone());
+        }
+        self.system_info.get_or_create(&info_labels).set(1);
+
+        let router_info_labels = RouterInfoLabels {
+            router: router_name.to_string(),
+            identity: system.identity.clone(),
+            version: system.version.clone(),
+            board: system.board_name.clone(),
+            architecture: system.architecture.clone(),
+            serial: system.serial_number.clone(),
+        };
+        {
+            let mut prev = self.prev_router_info.lock().await;
+            if let Some(old) = prev.get(router_name) {
+                if *old != router_info_labels {
+                    self.router_info.get_or_create(old).set(0);
+                }
+            }
+            prev.insert(router_name.to_string(), router_info_labels.clone());
+        }
+        self.router_info.get_or_create(&router_info_labels).set(1);
+    }
+}
+
+/// Used share of storage, `None` when the router reports no storage size
+#[allow(clippy::cast_precision_loss)]
+fn hdd_usage_ratio(system: &SystemResource) -> Option<f64> {
+    if system.total_hdd_space == 0 {
+        return None;
+    }
+    let used = system.total_hdd_space.saturating_sub(system.free_hdd_space);
+    Some(used as f64 / system.total_hdd_space as f64)
+}
+
+/// Sets a gauge, or removes it when the router didn't report the value
+#[allow(clippy::cast_possible_wrap)]
+fn set_optional<L: Clone + Eq + Hash>(family: &Family<L, Gauge>, labels: &L, value: Option<u64>) {
+    if let Some(value) = value {
+        family.get_or_create(labels).set(value as i64);
+    } else {
+        family.remove(labels);
+    }
+}
+
+/// Sets a float gauge, or removes it when the router didn't report the value
+fn set_optional_f64<L: Clone + Eq + Hash>(
+    family: &Family<L, Gauge<f64, AtomicU64>>,
+    labels: &L,
+    value: Option<f64>,
+) {
+    if let Some(value) = value {
+        family.get_or_create(labels).set(value);
+    } else {
+        family.remove(labels);
+    }
+}
+
+/// Brings a counter the router reports as a running total up to date
+///
+/// In delta mode the increase since the previous scrape is added. In absolute
+/// mode the series mirrors the router's value; a value below the exported one
+/// means the router reset its counter, so the series restarts from it and
+/// Prometheus sees the reset.
+fn advance_counter<L: Clone + Eq + Hash>(
+    mode: CounterMode,
+    family: &Family<L, Counter>,
+    labels: &L,
+    current: u64,
+    previous: u64,
+) {
+    match mode {
+        CounterMode::Delta => {
+            family
+                .get_or_create(labels)
+                .inc_by(current.saturating_sub(previous));
+        }
+        CounterMode::Absolute => {
+            let exported = family.get_or_create(labels).get();
+            if current < exported {
+                family.remove(labels);
+                family.get_or_create(labels).inc_by(current);
+            } else {
+                family.get_or_create(labels).inc_by(current - exported);
+            }
+        }
+    }
+}
+
+/// Like [`advance_counter`], for counters the router doesn't always report
+///
+/// In delta mode a counter seen for the first time only creates the series;
+/// a missing value leaves it untouched.
+fn inc_optional<L: Clone + Eq + Hash>(
+    mode: CounterMode,
+    family: &Family<L, Counter>,
+    labels: &L,
+    current: Option<u64>,
+    previous: Option<u64>,
+) {
+    if let Some(current) = current {
+        advance_counter(mode, family, labels, current, previous.unwrap_or(current));
     }
 }
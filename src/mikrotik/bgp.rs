@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! BGP sessions (`/routing/bgp/session` on RouterOS 7, `/routing/bgp/peer` on 6)
+
+use std::collections::HashMap;
+
+/// Properties read from `/routing/bgp/session/print`
+pub(super) const SESSION_PROPERTIES: [&str; 5] =
+    ["name", "remote.as", "established", "uptime", "prefix-count"];
+
+/// Properties read from the RouterOS 6 `/routing/bgp/peer/print`
+pub(super) const PEER_PROPERTIES: [&str; 5] =
+    ["name", "remote-as", "state", "uptime", "prefix-count"];
+
+/// State of a BGP session
+#[derive(Debug, Clone, PartialEq)]
+pub struct BgpSessionStats {
+    /// Session name, or the peer name on RouterOS 6
+    pub name: String,
+    pub remote_as: String,
+    /// Lowercase session state; RouterOS 7 only distinguishes `established`
+    /// from `idle`
+    pub state: String,
+    /// Time since the session was established, as reported by RouterOS
+    pub uptime: Option<String>,
+    /// Prefixes received from the peer
+    pub prefix_count: Option<u64>,
+}
+
+impl BgpSessionStats {
+    /// Whether the session is established
+    #[must_use]
+    pub fn established(&self) -> bool {
+        self.state == "established"
+    }
+}
+
+/// Parse `/routing/bgp/session/print` or RouterOS 6 `/routing/bgp/peer/print`
+///
+/// RouterOS 7 sessions report an `established` flag and a dotted `remote.as`,
+/// RouterOS 6 peers a `state` string and `remote-as`.
+pub(super) fn parse_bgp_sessions(sentences: &[HashMap<String, String>]) -> Vec<BgpSessionStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let state = match s.get("state") {
+                Some(state) => state.to_ascii_lowercase(),
+                None if s.get("established").is_some_and(|v| v == "true") => {
+                    "established".to_string()
+                }
+                None => "idle".to_string(),
+            };
+            Some(BgpSessionStats {
+                name: s.get("name")?.clone(),
+                remote_as: s
+                    .get("remote.as")
+                    .or_else(|| s.get("remote-as"))
+                    .cloned()
+                    .unwrap_or_default(),
+                state,
+                uptime: s.get("uptime").filter(|v| !v.is_empty()).cloned(),
+                prefix_count: s.get("prefix-count").and_then(|v| v.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_bgp_sessions_v7() {
+        let sentences = vec![
+            sentence(&[
+                ("name", "isp1-1"),
+                ("remote.as", "64500"),
+                ("established", "true"),
+                ("uptime", "3d4h5m6s"),
+                ("prefix-count", "950000"),
+            ]),
+            sentence(&[("name", "isp2-1"), ("remote.as", "64501")]),
+        ];
+
+        let sessions = parse_bgp_sessions(&sentences);
+
+        assert_eq!(
+            sessions,
+            vec![
+                BgpSessionStats {
+                    name: "isp1-1".to_string(),
+                    remote_as: "64500".to_string(),
+                    state: "established".to_string(),
+                    uptime: Some("3d4h5m6s".to_string()),
+                    prefix_count: Some(950_000),
+                },
+                BgpSessionStats {
+                    name: "isp2-1".to_string(),
+                    remote_as: "64501".to_string(),
+                    state: "idle".to_string(),
+                    uptime: None,
+                    prefix_count: None,
+                },
+            ]
+        );
+        assert!(sessions[0].established());
+        assert!(!sessions[1].established());
+    }
+
+    #[test]
+    fn test_parse_bgp_peers_v6() {
+        let sentences = vec![sentence(&[
+            ("name", "upstream"),
+            ("remote-as", "64510"),
+            ("state", "Active"),
+            ("prefix-count", "0"),
+        ])];
+
+        let sessions = parse_bgp_sessions(&sentences);
+
+        assert_eq!(sessions[0].remote_as, "64510");
+        assert_eq!(sessions[0].state, "active");
+        assert!(!sessions[0].established());
+    }
+}
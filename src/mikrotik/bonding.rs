@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Bonding (LAG/LACP) collection for MikroTik routers
+//!
+//! Bond membership comes from `/interface/bonding/print`; the state of each
+//! slave is taken from the `running` flag reported by `/interface/print`.
+
+use std::collections::HashMap;
+
+use super::types::InterfaceStats;
+
+/// A bonding interface and the state of its slaves
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondStats {
+    pub name: String,
+    pub slaves: Vec<BondSlaveStats>,
+}
+
+/// State of a single bond member
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondSlaveStats {
+    pub name: String,
+    pub up: bool,
+}
+
+impl BondStats {
+    /// Number of slaves currently up
+    #[must_use]
+    pub fn active_slaves(&self) -> usize {
+        self.slaves.iter().filter(|s| s.up).count()
+    }
+}
+
+/// Parse `/interface/bonding/print` and resolve slave state from interface stats
+///
+/// Slaves missing from `interfaces` (e.g. when the interfaces collector is
+/// disabled) are omitted rather than reported as down.
+pub(super) fn parse_bonds(
+    sentences: &[HashMap<String, String>],
+    interfaces: &[InterfaceStats],
+) -> Vec<BondStats> {
+    let running: HashMap<&str, bool> = interfaces
+        .iter()
+        .map(|i| (i.name.as_str(), i.running))
+        .collect();
+
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let name = s.get("name")?;
+            let slaves = s
+                .get("slaves")
+                .map(String::as_str)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|slave| !slave.is_empty())
+                .filter_map(|slave| {
+                    running.get(slave).map(|up| BondSlaveStats {
+                        name: slave.to_string(),
+                        up: *up,
+                    })
+                })
+                .collect();
+            Some(BondStats {
+                name: name.clone(),
+                slaves,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iface(name: &str, running: bool) -> InterfaceStats {
+        InterfaceStats {
+            name: name.to_string(),
+            rx_bytes: 0,
+            tx_bytes: 0,
+            rx_packets: 0,
+            tx_packets: 0,
+            rx_errors: 0,
+            tx_errors: 0,
+            rx_drops: 0,
+            tx_drops: 0,
+            running,
+            disabled: false,
+            mtu: None,
+            actual_mtu: None,
+            rx_multicast: None,
+            tx_multicast: None,
+            rx_broadcast: None,
+            tx_broadcast: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_bonds_with_failed_slave() {
+        let sentence: HashMap<String, String> = [
+            ("name".to_string(), "bond1".to_string()),
+            ("slaves".to_string(), "ether1,ether2".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let interfaces = vec![iface("ether1", true), iface("ether2", false)];
+
+        let bonds = parse_bonds(&[sentence], &interfaces);
+
+        assert_eq!(bonds.len(), 1);
+        assert_eq!(bonds[0].active_slaves(), 1);
+        assert_eq!(
+            bonds[0].slaves,
+            vec![
+                BondSlaveStats {
+                    name: "ether1".to_string(),
+                    up: true,
+                },
+                BondSlaveStats {
+                    name: "ether2".to_string(),
+                    up: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bonds_omits_unknown_slaves() {
+        let sentence: HashMap<String, String> = [
+            ("name".to_string(), "bond1".to_string()),
+            ("slaves".to_string(), "ether1,ether2".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        let bonds = parse_bonds(&[sentence], &[]);
+
+        assert!(bonds[0].slaves.is_empty());
+    }
+}
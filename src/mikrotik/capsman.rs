@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! CAPsMAN controller collection for MikroTik routers
+//!
+//! A controller manages remote access points (CAPs) whose radios show up as
+//! interfaces on the controller itself. `/caps-man/radio/print` maps each
+//! radio interface to the identity of the CAP it belongs to, and clients in
+//! `/caps-man/registration-table/print` are attributed to a CAP through the
+//! interface they are registered on.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::wireless::{parse_rate_bps, parse_signal_dbm};
+
+/// Properties read from `/caps-man/registration-table/print`
+pub(super) const REGISTRATION_PROPERTIES: [&str; 7] = [
+    "interface",
+    "ssid",
+    "mac-address",
+    "rx-signal",
+    "tx-rate",
+    "rx-rate",
+    "uptime",
+];
+
+/// A CAP managed by the controller
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManagedDeviceStats {
+    /// Identity of the remote CAP
+    pub identity: String,
+    /// Wireless clients registered on any of the CAP's radios
+    pub clients: u64,
+}
+
+/// A wireless client registered through the CAPsMAN controller
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapsmanClientStats {
+    /// CAP radio interface on the controller
+    pub interface: String,
+    pub mac_address: String,
+    pub ssid: String,
+    /// Signal strength received from the client in dBm, `None` when not
+    /// reported
+    pub signal_strength: Option<i64>,
+    /// Current transmit rate in bits per second
+    pub tx_rate: Option<u64>,
+    /// Current receive rate in bits per second
+    pub rx_rate: Option<u64>,
+    /// Time since the client registered, as reported by RouterOS
+    pub uptime: String,
+}
+
+/// Parse `/caps-man/registration-table/print` response
+pub(super) fn parse_capsman_clients(
+    registrations: &[HashMap<String, String>],
+) -> Vec<CapsmanClientStats> {
+    registrations
+        .iter()
+        .filter_map(|s| {
+            Some(CapsmanClientStats {
+                interface: s.get("interface")?.clone(),
+                mac_address: s.get("mac-address")?.clone(),
+                ssid: s.get("ssid").cloned().unwrap_or_default(),
+                signal_strength: s.get("rx-signal").and_then(|v| parse_signal_dbm(v)),
+                tx_rate: s.get("tx-rate").and_then(|v| parse_rate_bps(v)),
+                rx_rate: s.get("rx-rate").and_then(|v| parse_rate_bps(v)),
+                uptime: s.get("uptime").cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Count registered clients per managed CAP
+///
+/// CAPs without clients are reported with zero; clients on interfaces not
+/// bound to a known CAP are ignored.
+pub(super) fn parse_managed_devices(
+    radios: &[HashMap<String, String>],
+    registrations: &[HashMap<String, String>],
+) -> Vec<ManagedDeviceStats> {
+    let interface_owner: HashMap<&str, &str> = radios
+        .iter()
+        .filter_map(|r| {
+            let identity = r.get("remote-cap-identity")?;
+            let interface = r.get("interface")?;
+            Some((interface.as_str(), identity.as_str()))
+        })
+        .collect();
+
+    let mut clients: BTreeMap<&str, u64> = interface_owner.values().map(|id| (*id, 0)).collect();
+    for registration in registrations {
+        if let Some(identity) = registration
+            .get("interface")
+            .and_then(|i| interface_owner.get(i.as_str()))
+        {
+            *clients.entry(identity).or_insert(0) += 1;
+        }
+    }
+
+    clients
+        .into_iter()
+        .map(|(identity, clients)| ManagedDeviceStats {
+            identity: identity.to_string(),
+            clients,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_managed_devices_counts_clients_per_cap() {
+        let radios = vec![
+            sentence(&[("interface", "cap1"), ("remote-cap-identity", "ap-lobby")]),
+            sentence(&[("interface", "cap2"), ("remote-cap-identity", "ap-lobby")]),
+            sentence(&[("interface", "cap3"), ("remote-cap-identity", "ap-office")]),
+            sentence(&[("interface", "cap4"), ("remote-cap-identity", "ap-idle")]),
+        ];
+        let registrations = vec![
+            sentence(&[("interface", "cap1")]),
+            sentence(&[("interface", "cap2")]),
+            sentence(&[("interface", "cap3")]),
+            sentence(&[("interface", "unknown")]),
+        ];
+
+        let devices = parse_managed_devices(&radios, &registrations);
+
+        assert_eq!(
+            devices,
+            vec![
+                ManagedDeviceStats {
+                    identity: "ap-idle".to_string(),
+                    clients: 0,
+                },
+                ManagedDeviceStats {
+                    identity: "ap-lobby".to_string(),
+                    clients: 2,
+                },
+                ManagedDeviceStats {
+                    identity: "ap-office".to_string(),
+                    clients: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_capsman_clients() {
+        let registrations = vec![
+            sentence(&[
+                ("interface", "cap1"),
+                ("ssid", "corp"),
+                ("mac-address", "AA:BB:CC:DD:EE:01"),
+                ("rx-signal", "-61"),
+                ("tx-rate", "144.4Mbps-20MHz/2S/SGI"),
+                ("rx-rate", "130Mbps-20MHz/2S"),
+                ("uptime", "1h2m3s"),
+            ]),
+            sentence(&[("interface", "cap2"), ("mac-address", "AA:BB:CC:DD:EE:02")]),
+            sentence(&[("interface", "cap2")]),
+        ];
+
+        let clients = parse_capsman_clients(&registrations);
+
+        assert_eq!(
+            clients,
+            vec![
+                CapsmanClientStats {
+                    interface: "cap1".to_string(),
+                    mac_address: "AA:BB:CC:DD:EE:01".to_string(),
+                    ssid: "corp".to_string(),
+                    signal_strength: Some(-61),
+                    tx_rate: Some(144_400_000),
+                    rx_rate: Some(130_000_000),
+                    uptime: "1h2m3s".to_string(),
+                },
+                CapsmanClientStats {
+                    interface: "cap2".to_string(),
+                    mac_address: "AA:BB:CC:DD:EE:02".to_string(),
+                    ssid: String::new(),
+                    signal_strength: None,
+                    tx_rate: None,
+                    rx_rate: None,
+                    uptime: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_managed_devices_without_radios() {
+        let registrations = vec![sentence(&[("interface", "wlan1")])];
+        assert!(parse_managed_devices(&[], &registrations).is_empty());
+    }
+}
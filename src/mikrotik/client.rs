@@ -3,14 +3,50 @@
 
 //! High-level MikroTik client
 
-use crate::config::RouterConfig;
+use crate::config::{Collector, RouterConfig};
 use secrecy::ExposeSecret;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use super::connection::{parse_connection_tracking, parse_interfaces, parse_system};
-use super::pool::ConnectionPool;
+use super::bgp::{PEER_PROPERTIES, SESSION_PROPERTIES, parse_bgp_sessions};
+use super::bonding::parse_bonds;
+use super::capsman::{REGISTRATION_PROPERTIES, parse_capsman_clients, parse_managed_devices};
+use super::clock::{CLOCK_PROPERTIES, parse_clock};
+use super::connection::{
+    Command, Transport, is_connection_closed, is_missing_menu, merge_ethernet_counters,
+    parse_connection_tracking, parse_identity, parse_interfaces, parse_serial_number, parse_system,
+};
+use super::conntrack_table::parse_conntrack_table;
+use super::container::parse_containers;
+use super::cpu::parse_cpu_cores;
+use super::custom::{CustomMetric, parse_custom};
+use super::dhcp::parse_dhcp_leases;
+use super::firewall::parse_firewall_rules;
+use super::health::{HealthStats, parse_health};
+use super::interface_queue::parse_interface_queues;
+use super::ip_service::parse_ip_services;
+use super::ipsec::{ACTIVE_PEER_PROPERTIES, parse_ipsec_peers};
+use super::license::parse_license;
+use super::ping::{PING_COUNT, parse_ping};
+use super::pool::{ConnectionPool, PooledConnectionGuard};
+use super::ppp::{PPP_PROPERTIES, parse_ppp_sessions};
+use super::queue::parse_simple_queues;
+use super::route::{DEFAULT_DST, parse_default_routes};
+use super::sfp::{SFP_PORT_PROPERTIES, SFP_PROPERTIES, parse_sfp_monitor, parse_sfp_ports};
+use super::tunnel::{TUNNEL_TYPES, parse_tunnels};
 use super::types::RouterMetrics;
+use super::vrrp::parse_vrrp;
+use super::watchdog::{REBOOT_LOG_TOPICS, parse_unexpected_reboots, parse_watchdog};
+use super::wifi::{parse_wifi_interfaces, parse_wifi_monitor};
 use super::wireguard::{parse_wireguard_interfaces, parse_wireguard_peers};
+use super::wireless::parse_wireless_stations;
+
+/// Connection tracking properties read by `parse_connection_tracking`
+const CONNTRACK_PROPERTIES: &[&str] = &["src-address", "protocol"];
+
+/// Result of a single `RouterOS` command
+type CommandResult = Result<Vec<HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>>;
 
 /// `MikroTik` `RouterOS` API client
 ///
@@ -40,24 +76,38 @@ impl MikroTikClient {
     pub(crate) async fn collect_metrics(
         &self,
     ) -> Result<RouterMetrics, Box<dyn std::error::Error + Send + Sync>> {
-        use tokio::time::{Duration, timeout};
-
-        const COLLECTION_TIMEOUT: Duration = Duration::from_secs(30);
+        use tokio::time::timeout;
 
-        match timeout(COLLECTION_TIMEOUT, self.collect_real()).await {
+        let limit = self.pool.collection_timeout();
+        match timeout(limit, self.collect_real()).await {
             Ok(Ok(m)) => Ok(m),
             Ok(Err(e)) => {
                 tracing::error!("Router '{}' collection failed: {}", self.config.name, e);
                 Err(e)
             }
             Err(_) => {
-                let err = format!("Router '{}' collection timeout (>30s)", self.config.name);
+                let err = format!(
+                    "Router '{}' collection timeout (>{}s)",
+                    self.config.name,
+                    limit.as_secs_f64()
+                );
                 tracing::error!("{}", err);
                 Err(err.into())
             }
         }
     }
 
+    /// Transport selected by the router's `tls` settings
+    fn transport(&self) -> Transport {
+        if self.config.tls {
+            Transport::Tls {
+                verify_certificate: !self.config.tls_skip_verify,
+            }
+        } else {
+            Transport::Plain
+        }
+    }
+
     async fn collect_real(
         &self,
     ) -> Result<RouterMetrics, Box<dyn std::error::Error + Send + Sync>> {
@@ -68,19 +118,407 @@ impl MikroTikClient {
                 &self.config.address,
                 &self.config.username,
                 self.config.password.expose_secret(),
+                self.transport(),
             )
             .await?;
 
-        let conn = guard.get_mut();
-        let system_result = conn.command("/system/resource/print", &[]).await;
-        let interfaces_result = conn.command("/interface/print", &[]).await;
-        let conntrack_v4_result = conn.command("/ip/firewall/connection/print", &[]).await;
-        let conntrack_v6_result = conn.command("/ipv6/firewall/connection/print", &[]).await;
-        let wireguard_interfaces_result = conn.command("/interface/wireguard/print", &[]).await;
-        let wireguard_peers_result = conn.command("/interface/wireguard/peers/print", &[]).await;
+        let mut runner = CommandRunner {
+            guard: &mut guard,
+            config: &self.config,
+            executed: 0,
+            failed: Vec::new(),
+            durations: HashMap::new(),
+            session_lost: None,
+        };
+        let system_result = runner
+            .run(
+                Collector::System,
+                Command::new("/system/resource/print").proplist(&[
+                    "version",
+                    "uptime",
+                    "cpu-load",
+                    "cpu-count",
+                    "free-memory",
+                    "total-memory",
+                    "free-hdd-space",
+                    "total-hdd-space",
+                    "board-name",
+                    "architecture-name",
+                ]),
+            )
+            .await;
+        let identity_result = runner
+            .run(
+                Collector::System,
+                Command::new("/system/identity/print").proplist(&["name"]),
+            )
+            .await;
+        let routerboard_result = runner
+            .run(
+                Collector::System,
+                Command::new("/system/routerboard/print").proplist(&["serial-number"]),
+            )
+            .await;
+        let cpu_cores_result = runner
+            .run_optional(
+                Collector::System,
+                Command::new("/system/resource/cpu/print").proplist(&["cpu", "load"]),
+            )
+            .await;
+        let interfaces_result = runner
+            .run(
+                Collector::Interfaces,
+                Command::new("/interface/print").proplist(&[
+                    "name",
+                    "type",
+                    "running",
+                    "disabled",
+                    "mtu",
+                    "actual-mtu",
+                    "rx-byte",
+                    "tx-byte",
+                    "rx-packet",
+                    "tx-packet",
+                    "rx-error",
+                    "tx-error",
+                    "rx-drop",
+                    "tx-drop",
+                ]),
+            )
+            .await;
+        // Also lists the SFP cages the sfp collector monitors
+        let ethernet_result = runner
+            .run_optional(
+                Collector::Interfaces,
+                Command::new("/interface/ethernet/print")
+                    .stats()
+                    .proplist(&[
+                        "name",
+                        "default-name",
+                        "disabled",
+                        "sfp-shutdown-temperature",
+                        "rx-multicast",
+                        "tx-multicast",
+                        "rx-broadcast",
+                        "tx-broadcast",
+                    ]),
+            )
+            .await;
+        let conntrack_v4_result = runner
+            .run(
+                Collector::Conntrack,
+                Command::new("/ip/firewall/connection/print").proplist(CONNTRACK_PROPERTIES),
+            )
+            .await;
+        let conntrack_v6_result = runner
+            .run(
+                Collector::Conntrack,
+                Command::new("/ipv6/firewall/connection/print").proplist(CONNTRACK_PROPERTIES),
+            )
+            .await;
+        let conntrack_table_result = runner
+            .run(
+                Collector::ConntrackTable,
+                Command::new("/ip/firewall/connection/tracking/print")
+                    .proplist(&["max-entries", "total-entries"]),
+            )
+            .await;
+        let wireguard_interfaces_result = runner
+            .run(
+                Collector::Wireguard,
+                Command::new("/interface/wireguard/print").proplist(&["name", "disabled"]),
+            )
+            .await;
+        let wireguard_peers_result = runner
+            .run(
+                Collector::Wireguard,
+                Command::new("/interface/wireguard/peers/print").proplist(&[
+                    "interface",
+                    "name",
+                    "disabled",
+                    "allowed-address",
+                    "public-key",
+                    "current-endpoint-address",
+                    "endpoint",
+                    "rx",
+                    "tx",
+                    "last-handshake",
+                    "latest-handshake",
+                ]),
+            )
+            .await;
+        let wireguard_peers_received_at = unix_now();
+        // Router clock read along with the peers: handshake timestamps are
+        // taken from the exporter clock, this tells how far the router's is off
+        let wireguard_clock_result = match &wireguard_peers_result {
+            Some(Ok(_)) => {
+                runner
+                    .run(
+                        Collector::Wireguard,
+                        Command::new("/system/clock/print").proplist(&CLOCK_PROPERTIES),
+                    )
+                    .await
+            }
+            _ => None,
+        };
+        let ip_services_result = runner
+            .run(
+                Collector::IpServices,
+                Command::new("/ip/service/print").proplist(&["name", "port", "disabled"]),
+            )
+            .await;
+        let script_jobs_result = runner
+            .count(
+                Collector::ScriptJobs,
+                Command::new("/system/script/job/print"),
+            )
+            .await;
+        let clock_result = runner
+            .run(
+                Collector::Clock,
+                Command::new("/system/clock/print").proplist(&CLOCK_PROPERTIES),
+            )
+            .await;
+        let userman_sessions = runner
+            .count_optional(
+                Collector::UserManager,
+                Command::new("/user-manager/session/print").query("active", "true"),
+            )
+            .await;
+        let license_result = runner
+            .run(
+                Collector::License,
+                Command::new("/system/license/print").proplist(&["level", "nlevel", "deadline-at"]),
+            )
+            .await;
+        let ipsec_result = runner
+            .run_optional(
+                Collector::Ipsec,
+                Command::new("/ip/ipsec/active-peers/print").proplist(&ACTIVE_PEER_PROPERTIES),
+            )
+            .await;
+        let ppp_result = runner
+            .run(
+                Collector::Ppp,
+                Command::new("/ppp/active/print").proplist(&PPP_PROPERTIES),
+            )
+            .await;
+        let dhcp_result = runner
+            .run(
+                Collector::Dhcp,
+                Command::new("/ip/dhcp-server/lease/print").proplist(&[
+                    "server",
+                    "mac-address",
+                    "status",
+                ]),
+            )
+            .await;
+        let bonding_result = runner
+            .run(
+                Collector::Bonding,
+                Command::new("/interface/bonding/print").proplist(&["name", "slaves"]),
+            )
+            .await;
+        // Only routers with the wifi package (RouterOS 7) have this menu
+        let wifi_result = runner
+            .run_optional(
+                Collector::Wifi,
+                Command::new("/interface/wifi/print").proplist(&["name", "disabled"]),
+            )
+            .await;
+        let mut wifi_channels = Vec::new();
+        for interface in parse_wifi_interfaces(&wifi_result.unwrap_or_default()) {
+            let monitor = Command::new("/interface/wifi/monitor")
+                .attribute("numbers", &interface)
+                .once();
+            let sentences = optional_sentences(runner.run(Collector::Wifi, monitor).await);
+            wifi_channels.extend(parse_wifi_monitor(&interface, &sentences));
+        }
+        let mut sfp_modules = Vec::new();
+        if self.config.collector_enabled(Collector::Sfp) {
+            let sfp_ports = match &ethernet_result {
+                Some(sentences) => parse_sfp_ports(sentences),
+                None => {
+                    let print =
+                        Command::new("/interface/ethernet/print").proplist(&SFP_PORT_PROPERTIES);
+                    parse_sfp_ports(
+                        &runner
+                            .run_optional(Collector::Sfp, print)
+                            .await
+                            .unwrap_or_default(),
+                    )
+                }
+            };
+            for interface in sfp_ports {
+                let monitor = Command::new("/interface/ethernet/monitor")
+                    .attribute("numbers", &interface)
+                    .once()
+                    .proplist(&SFP_PROPERTIES);
+                let sentences = optional_sentences(runner.run(Collector::Sfp, monitor).await);
+                sfp_modules.extend(parse_sfp_monitor(&interface, &sentences));
+            }
+        }
+        let wireless_result = runner
+            .run_optional(
+                Collector::Wireless,
+                Command::new("/interface/wireless/registration-table/print").proplist(&[
+                    "interface",
+                    "mac-address",
+                    "signal-strength",
+                    "tx-rate",
+                    "rx-rate",
+                    "uptime",
+                ]),
+            )
+            .await;
+        // Only CAPsMAN controllers have these menus
+        let capsman_radios_result = runner
+            .run_optional(
+                Collector::Capsman,
+                Command::new("/caps-man/radio/print")
+                    .proplist(&["interface", "remote-cap-identity"]),
+            )
+            .await
+            .unwrap_or_default();
+        let capsman_registrations_result = runner
+            .run_optional(
+                Collector::Capsman,
+                Command::new("/caps-man/registration-table/print")
+                    .proplist(&REGISTRATION_PROPERTIES),
+            )
+            .await
+            .unwrap_or_default();
+        let interface_queues_result = runner
+            .run(
+                Collector::InterfaceQueues,
+                Command::new("/queue/interface/print").stats().proplist(&[
+                    "name",
+                    "interface",
+                    "dropped",
+                    "queued-packets",
+                ]),
+            )
+            .await;
+        let simple_queues_result = runner
+            .run(
+                Collector::Queues,
+                Command::new("/queue/simple/print").stats().proplist(&[
+                    ".id",
+                    "name",
+                    "comment",
+                    "target",
+                    "max-limit",
+                    "limit-at",
+                    "bytes",
+                    "packets",
+                ]),
+            )
+            .await;
+        let default_routes_result = runner
+            .run(
+                Collector::Routes,
+                Command::new("/ip/route/print")
+                    .query("dst-address", DEFAULT_DST)
+                    .proplist(&["dst-address", "gateway", "active"]),
+            )
+            .await;
+        let ipv4_routes_result = runner
+            .count(Collector::Routes, Command::new("/ip/route/print"))
+            .await;
+        // The ipv6 package can be disabled on RouterOS 6
+        let ipv6_routes = runner
+            .count_optional(Collector::Routes, Command::new("/ipv6/route/print"))
+            .await;
+        let mut tunnels = Vec::new();
+        for kind in TUNNEL_TYPES {
+            let print =
+                Command::new(format!("/interface/{kind}/print")).proplist(&["name", "running"]);
+            if let Some(Ok(sentences)) = runner.run(Collector::Tunnels, print).await {
+                tunnels.extend(parse_tunnels(kind, &sentences));
+            }
+        }
+        let vrrp_result = runner
+            .run(
+                Collector::Vrrp,
+                Command::new("/interface/vrrp/print").proplist(&["name", "master"]),
+            )
+            .await;
+        // RouterOS 6 has no session menu and lists BGP peers instead
+        let bgp_result = match runner
+            .run_optional(
+                Collector::Bgp,
+                Command::new("/routing/bgp/session/print").proplist(&SESSION_PROPERTIES),
+            )
+            .await
+        {
+            Some(sentences) => Some(sentences),
+            None => {
+                runner
+                    .run_optional(
+                        Collector::Bgp,
+                        Command::new("/routing/bgp/peer/print").proplist(&PEER_PROPERTIES),
+                    )
+                    .await
+            }
+        };
+        let firewall_result = runner
+            .run(
+                Collector::Firewall,
+                Command::new("/ip/firewall/filter/print")
+                    .stats()
+                    .detail()
+                    .proplist(&[".id", "chain", "comment", "bytes", "packets"]),
+            )
+            .await;
+        let containers_result = runner
+            .run_optional(
+                Collector::Container,
+                Command::new("/container/print").proplist(&["name", "tag", "status"]),
+            )
+            .await;
+        let health = collect_health(&mut runner).await;
+        let custom_metrics = collect_custom(&mut runner).await;
+        let watchdog_result = runner
+            .run(
+                Collector::Watchdog,
+                Command::new("/system/watchdog/print").proplist(&["watchdog-timer"]),
+            )
+            .await;
+        let reboot_log_result = runner
+            .run(
+                Collector::Watchdog,
+                Command::new("/log/print")
+                    .query("topics", REBOOT_LOG_TOPICS)
+                    .proplist(&["time", "message"]),
+            )
+            .await;
+        let mut ping_targets = Vec::new();
+        for target in &self.config.ping_targets {
+            let ping = Command::new("/ping")
+                .attribute("address", target)
+                .attribute("count", &PING_COUNT.to_string())
+                .attribute("interval", "200ms");
+            if let Some(Ok(sentences)) = runner.run(Collector::Ping, ping).await {
+                ping_targets.push(parse_ping(target, &sentences, PING_COUNT));
+            }
+        }
+        let commands_executed = runner.executed;
+        let command_errors = std::mem::take(&mut runner.failed);
+        let collector_durations = std::mem::take(&mut runner.durations);
+
+        // A session that could not be re-established fails the scrape, so the
+        // circuit breaker backs off instead of reconnecting on every command
+        if let Some(error) = runner.session_lost.take() {
+            self.pool
+                .record_error(&self.config.address, &self.config.username)
+                .await;
+            drop(guard);
+            return Err(error.into());
+        }
 
         // Record connection state BEFORE dropping guard to prevent race condition
-        let success = system_result.is_ok() && interfaces_result.is_ok();
+        let success = system_result.as_ref().is_none_or(Result::is_ok)
+            && interfaces_result.as_ref().is_none_or(Result::is_ok);
         if success {
             self.pool
                 .record_success(&self.config.address, &self.config.username)
@@ -95,38 +533,425 @@ impl MikroTikClient {
         drop(guard);
 
         // Now process results after connection is returned to pool with correct state
-        let system_sentences = system_result?;
-        let interfaces_sentences = interfaces_result?;
-        let mut conntrack_v4 =
-            parse_connection_tracking(&conntrack_v4_result.unwrap_or_default(), "ipv4");
-        let conntrack_v6 =
-            parse_connection_tracking(&conntrack_v6_result.unwrap_or_default(), "ipv6");
+        let system_sentences = system_result.transpose()?;
+        let interfaces_sentences = interfaces_result.transpose()?;
+        let conntrack_collected = matches!(conntrack_v4_result, Some(Ok(_)));
+        let (mut conntrack_v4, sources_v4) = parse_connection_tracking(
+            &optional_sentences(conntrack_v4_result),
+            "ipv4",
+            &self.config.conntrack_prefixes,
+        );
+        let (conntrack_v6, sources_v6) = parse_connection_tracking(
+            &optional_sentences(conntrack_v6_result),
+            "ipv6",
+            &self.config.conntrack_prefixes,
+        );
 
         // Merge IPv4 and IPv6 connection tracking data
         conntrack_v4.extend(conntrack_v6);
+        let conntrack_distinct_sources = conntrack_collected.then_some(sources_v4 + sources_v6);
 
-        let system = parse_system(&system_sentences);
-        let interfaces = parse_interfaces(&interfaces_sentences);
+        let system = system_sentences.map(|s| {
+            let mut system = parse_system(&s);
+            if let Some(identity) = identity_result
+                .and_then(Result::ok)
+                .and_then(|sentences| parse_identity(&sentences))
+            {
+                system.identity = identity;
+            }
+            if let Some(serial) = routerboard_result
+                .and_then(Result::ok)
+                .and_then(|sentences| parse_serial_number(&sentences))
+            {
+                system.serial_number = serial;
+            }
+            system
+        });
+        let mut interfaces = parse_interfaces(&interfaces_sentences.unwrap_or_default());
+        merge_ethernet_counters(&mut interfaces, &ethernet_result.unwrap_or_default());
 
         // Parse WireGuard interfaces and peers
         let wireguard_interfaces =
-            parse_wireguard_interfaces(&wireguard_interfaces_result.unwrap_or_default());
-        let wireguard_peers = parse_wireguard_peers(&wireguard_peers_result.unwrap_or_default());
+            parse_wireguard_interfaces(&optional_sentences(wireguard_interfaces_result));
+        let wireguard_peers = parse_wireguard_peers(
+            &optional_sentences(wireguard_peers_result),
+            u64::try_from(wireguard_peers_received_at).unwrap_or_default(),
+            self.config.wireguard_peer_key,
+        );
+        let router_clock_skew_seconds = wireguard_clock_result
+            .and_then(Result::ok)
+            .and_then(|sentences| parse_clock(&sentences, wireguard_peers_received_at))
+            .map(|clock| clock.offset_seconds);
+        let ip_services = parse_ip_services(&optional_sentences(ip_services_result));
+        let bonds = parse_bonds(&optional_sentences(bonding_result), &interfaces);
+        let managed_devices =
+            parse_managed_devices(&capsman_radios_result, &capsman_registrations_result);
+        let capsman_clients = parse_capsman_clients(&capsman_registrations_result);
+        let interface_queues = parse_interface_queues(&optional_sentences(interface_queues_result));
+        let simple_queues = parse_simple_queues(
+            &with_comment_prefix(
+                optional_sentences(simple_queues_result),
+                self.config.comment_prefix.as_deref(),
+            ),
+            self.config.include_ids,
+        );
+        let default_routes = parse_default_routes(&optional_sentences(default_routes_result));
+        let vrrp = parse_vrrp(&optional_sentences(vrrp_result));
+        let bgp_sessions = parse_bgp_sessions(&bgp_result.unwrap_or_default());
+        let ipsec_peers = ipsec_result.map(|sentences| parse_ipsec_peers(&sentences));
+        let ipsec_active_peers = ipsec_peers
+            .as_ref()
+            .map(|peers| peers.iter().filter(|peer| peer.established()).count() as u64);
+        let firewall_rules = parse_firewall_rules(
+            &with_comment_prefix(
+                optional_sentences(firewall_result),
+                self.config.comment_prefix.as_deref(),
+            ),
+            self.config.include_ids,
+        );
+        let containers = parse_containers(&containers_result.unwrap_or_default());
+        let wireless_stations = parse_wireless_stations(&wireless_result.unwrap_or_default());
+        let cpu_cores = parse_cpu_cores(&cpu_cores_result.unwrap_or_default());
+        let conntrack_table = conntrack_table_result
+            .and_then(Result::ok)
+            .and_then(|sentences| parse_conntrack_table(&sentences));
+        let clock = clock_result
+            .and_then(Result::ok)
+            .and_then(|sentences| parse_clock(&sentences, unix_now()));
+        let license = license_result
+            .and_then(Result::ok)
+            .and_then(|sentences| parse_license(&sentences));
+        let ppp_sessions = ppp_result
+            .and_then(Result::ok)
+            .map(|sentences| parse_ppp_sessions(&sentences));
+        let dhcp_leases = dhcp_result
+            .and_then(Result::ok)
+            .map(|sentences| parse_dhcp_leases(&sentences));
+        let script_jobs = script_jobs_result.and_then(Result::ok);
+        let ipv4_routes = ipv4_routes_result.and_then(Result::ok);
+        let watchdog_enabled = watchdog_result
+            .and_then(Result::ok)
+            .and_then(|sentences| parse_watchdog(&sentences));
+        let unexpected_reboots = reboot_log_result
+            .and_then(Result::ok)
+            .map(|sentences| parse_unexpected_reboots(&sentences));
 
         Ok(RouterMetrics {
             router_name: self.config.name.clone(),
             interfaces,
+            interface_queues,
             system,
+            cpu_cores,
             connection_tracking: conntrack_v4,
+            conntrack_distinct_sources,
+            conntrack_table,
             wireguard_interfaces,
             wireguard_peers,
+            router_clock_skew_seconds,
+            ip_services,
+            bonds,
+            script_jobs,
+            userman_sessions,
+            ipv4_routes,
+            ipv6_routes,
+            ipsec_active_peers,
+            ipsec_peers: ipsec_peers.unwrap_or_default(),
+            clock,
+            license,
+            wifi_channels,
+            sfp_modules,
+            wireless_stations,
+            managed_devices,
+            capsman_clients,
+            ping_targets,
+            ppp_sessions,
+            dhcp_leases,
+            simple_queues,
+            default_routes,
+            tunnels,
+            vrrp,
+            bgp_sessions,
+            firewall_rules,
+            containers,
+            watchdog_enabled,
+            unexpected_reboots,
+            health,
+            custom_metrics,
+            commands_executed,
+            collector_durations,
+            command_errors,
         })
     }
 }
 
+/// Runs print commands on one connection, honouring the router's collector selection
+struct CommandRunner<'a> {
+    guard: &'a mut PooledConnectionGuard,
+    config: &'a RouterConfig,
+    /// Number of commands actually sent to the router
+    executed: u32,
+    /// Paths of commands that returned an error
+    failed: Vec<String>,
+    /// Time spent on each collector's commands
+    durations: HashMap<Collector, Duration>,
+    /// Set once a reconnect has failed; remaining commands fail with it
+    /// without touching the router
+    session_lost: Option<String>,
+}
+
+impl CommandRunner<'_> {
+    /// Runs a print command if its collector is enabled for this router
+    ///
+    /// Returns `None` without touching the connection when the collector is disabled.
+    async fn run(&mut self, collector: Collector, command: Command) -> Option<CommandResult> {
+        let command = self.override_path(command);
+        let result = self.send(collector, &command).await?;
+        self.record_failure(&command, &result);
+        Some(result)
+    }
+
+    /// Like [`Self::run`], for menus missing on older RouterOS versions
+    ///
+    /// A missing menu yields `None` without being recorded as a command error.
+    async fn run_optional(
+        &mut self,
+        collector: Collector,
+        command: Command,
+    ) -> Option<Vec<HashMap<String, String>>> {
+        let command = self.override_path(command);
+        match self.send(collector, &command).await? {
+            Err(e) if is_missing_menu(e.as_ref()) => {
+                tracing::trace!(
+                    "Skipping {} on router '{}': not supported by this RouterOS version",
+                    command.path(),
+                    self.config.name
+                );
+                None
+            }
+            result => {
+                self.record_failure(&command, &result);
+                result.ok()
+            }
+        }
+    }
+
+    /// Sends a command if its collector is enabled
+    ///
+    /// A pooled connection the router has since closed fails with an IO
+    /// error; the command is then retried once on a fresh connection.
+    /// RouterOS `!trap` errors are returned as they are.
+    async fn send(&mut self, collector: Collector, command: &Command) -> Option<CommandResult> {
+        if !self.enabled(collector, command) {
+            return None;
+        }
+        if let Some(error) = &self.session_lost {
+            return Some(Err(error.clone().into()));
+        }
+        self.executed += 1;
+        let started = Instant::now();
+        let result = match self.guard.get_mut().command(command).await {
+            Err(e) if is_connection_closed(e.as_ref()) => {
+                match self.reconnect(command, e.as_ref()).await {
+                    Ok(()) => self.guard.get_mut().command(command).await,
+                    Err(e) => Err(e),
+                }
+            }
+            result => result,
+        };
+        if result.is_err() {
+            self.guard.record_dropped_reply().await;
+        }
+        *self.durations.entry(collector).or_default() += started.elapsed();
+        Some(result)
+    }
+
+    /// Runs a print command with `count-only` if its collector is enabled
+    ///
+    /// Only the number of records crosses the wire, which keeps large tables cheap.
+    async fn count(
+        &mut self,
+        collector: Collector,
+        command: Command,
+    ) -> Option<Result<u64, Box<dyn std::error::Error + Send + Sync>>> {
+        let command = self.override_path(command);
+        let result = self.send_count(collector, &command).await?;
+        self.record_failure(&command, &result);
+        Some(result)
+    }
+
+    /// Like [`Self::count`], for menus provided by optional packages
+    ///
+    /// A missing menu means the package isn't installed; it yields `None`
+    /// without being recorded as a command error.
+    async fn count_optional(&mut self, collector: Collector, command: Command) -> Option<u64> {
+        let command = self.override_path(command);
+        match self.send_count(collector, &command).await? {
+            Err(e) if is_missing_menu(e.as_ref()) => {
+                tracing::trace!(
+                    "Skipping {} on router '{}': package not installed",
+                    command.path(),
+                    self.config.name
+                );
+                None
+            }
+            result => {
+                self.record_failure(&command, &result);
+                result.ok()
+            }
+        }
+    }
+
+    async fn send_count(
+        &mut self,
+        collector: Collector,
+        command: &Command,
+    ) -> Option<Result<u64, Box<dyn std::error::Error + Send + Sync>>> {
+        if !self.enabled(collector, command) {
+            return None;
+        }
+        if let Some(error) = &self.session_lost {
+            return Some(Err(error.clone().into()));
+        }
+        self.executed += 1;
+        let started = Instant::now();
+        let result = match self.guard.get_mut().command_count(command).await {
+            Err(e) if is_connection_closed(e.as_ref()) => {
+                match self.reconnect(command, e.as_ref()).await {
+                    Ok(()) => self.guard.get_mut().command_count(command).await,
+                    Err(e) => Err(e),
+                }
+            }
+            result => result,
+        };
+        if result.is_err() {
+            self.guard.record_dropped_reply().await;
+        }
+        *self.durations.entry(collector).or_default() += started.elapsed();
+        Some(result)
+    }
+
+    /// Applies the router's command path override, if any
+    fn override_path(&self, command: Command) -> Command {
+        match self.config.command_paths.get(command.path()) {
+            Some(path) => command.with_path(path.clone()),
+            None => command,
+        }
+    }
+
+    fn enabled(&self, collector: Collector, command: &Command) -> bool {
+        let enabled = self.config.collector_enabled(collector);
+        if !enabled {
+            tracing::trace!(
+                "Skipping {} on router '{}' (collector '{}' disabled)",
+                command.path(),
+                self.config.name,
+                collector.name()
+            );
+        }
+        enabled
+    }
+
+    fn record_failure<T>(
+        &mut self,
+        command: &Command,
+        result: &Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) {
+        if let Err(e) = result {
+            tracing::debug!(
+                "Command {} failed on router '{}': {}",
+                command.path(),
+                self.config.name,
+                e
+            );
+            self.failed.push(command.path().to_string());
+        }
+    }
+
+    /// Reconnects after the router closed the session so the command can be retried once
+    ///
+    /// A failed reconnect marks the session lost, so the router gets a single
+    /// reconnect attempt per scrape.
+    async fn reconnect(
+        &mut self,
+        command: &Command,
+        error: &(dyn std::error::Error + Send + Sync),
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tracing::debug!(
+            "Router '{}' closed the connection ({}), reconnecting for {}",
+            self.config.name,
+            error,
+            command.path()
+        );
+        let result = self
+            .guard
+            .reconnect(
+                &self.config.address,
+                &self.config.username,
+                self.config.password.expose_secret(),
+            )
+            .await;
+        if let Err(e) = &result {
+            self.session_lost = Some(format!(
+                "Router '{}' session lost, reconnect failed: {}",
+                self.config.name, e
+            ));
+        }
+        result
+    }
+}
+
+/// Current Unix time in seconds
+fn unix_now() -> i64 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    i64::try_from(secs).unwrap_or(i64::MAX)
+}
+
+/// Keeps only entries whose comment starts with `prefix`, if one is configured
+fn with_comment_prefix(
+    sentences: Vec<HashMap<String, String>>,
+    prefix: Option<&str>,
+) -> Vec<HashMap<String, String>> {
+    let Some(prefix) = prefix else {
+        return sentences;
+    };
+    sentences
+        .into_iter()
+        .filter(|s| s.get("comment").is_some_and(|c| c.starts_with(prefix)))
+        .collect()
+}
+
+/// Sentences from an optional collector, treating disabled or failed commands as empty
+fn optional_sentences(result: Option<CommandResult>) -> Vec<HashMap<String, String>> {
+    result.and_then(Result::ok).unwrap_or_default()
+}
+
+/// Reads board sensors; CHR and boards without sensors report none
+async fn collect_health(runner: &mut CommandRunner<'_>) -> HealthStats {
+    let sentences = runner
+        .run_optional(Collector::Health, Command::new("/system/health/print"))
+        .await;
+    parse_health(&sentences.unwrap_or_default())
+}
+
+/// Runs the router's custom collectors, requesting only their properties
+async fn collect_custom(runner: &mut CommandRunner<'_>) -> Vec<CustomMetric> {
+    let config = runner.config;
+    let mut metrics = Vec::new();
+    for collector in &config.custom_collectors {
+        let command = Command::new(collector.command.as_str()).proplist(&collector.properties());
+        let sentences = runner.run_optional(Collector::Custom, command).await;
+        metrics.extend(parse_custom(collector, &sentences.unwrap_or_default()));
+    }
+    metrics
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_mikrotik_client_creation() {
@@ -135,6 +960,7 @@ mod tests {
             address: "192.168.1.1:8728".to_string(),
             username: "admin".to_string(),
             password: "password".to_string().into(),
+            ..Default::default()
         };
 
         let pool = Arc::new(ConnectionPool::new());
@@ -144,6 +970,28 @@ mod tests {
         assert_eq!(client.config.address, "192.168.1.1:8728");
     }
 
+    #[test]
+    fn test_with_comment_prefix_keeps_only_prefixed_entries() {
+        let entry = |name: &str, comment: Option<&str>| {
+            let mut s = HashMap::from([("name".to_string(), name.to_string())]);
+            if let Some(comment) = comment {
+                s.insert("comment".to_string(), comment.to_string());
+            }
+            s
+        };
+        let sentences = vec![
+            entry("customer-a", Some("mon: customer A")),
+            entry("internal", Some("backup link")),
+            entry("uncommented", None),
+        ];
+
+        let kept = with_comment_prefix(sentences.clone(), Some("mon:"));
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0]["name"], "customer-a");
+
+        assert_eq!(with_comment_prefix(sentences, None).len(), 3);
+    }
+
     #[tokio::test]
     async fn test_collect_metrics_returns_error_on_failure() {
         let config = RouterConfig {
@@ -151,6 +999,7 @@ mod tests {
             address: "invalid:address".to_string(),
             username: "admin".to_string(),
             password: "password".to_string().into(),
+            ..Default::default()
         };
 
         let pool = Arc::new(ConnectionPool::new());
@@ -160,4 +1009,540 @@ mod tests {
         let result = client.collect_metrics().await;
         assert!(result.is_err());
     }
+
+    async fn read_sentence(stream: &mut tokio::net::TcpStream) -> Option<Vec<String>> {
+        use tokio::io::AsyncReadExt;
+
+        let mut words = Vec::new();
+        loop {
+            // Words are shorter than 0x4000 bytes: one or two length bytes
+            let mut len = stream.read_u8().await.ok()? as usize;
+            if len == 0 {
+                return Some(words);
+            }
+            if len & 0x80 != 0 {
+                len = ((len & 0x3f) << 8) | stream.read_u8().await.ok()? as usize;
+            }
+            let mut buf = vec![0u8; len];
+            stream.read_exact(&mut buf).await.ok()?;
+            words.push(String::from_utf8(buf).ok()?);
+        }
+    }
+
+    async fn write_words(stream: &mut tokio::net::TcpStream, words: &[&str]) {
+        use tokio::io::AsyncWriteExt;
+
+        let mut out = Vec::new();
+        for word in words {
+            out.extend(crate::mikrotik::encode_length(word.len()));
+            out.extend(word.as_bytes());
+        }
+        stream.write_all(&out).await.unwrap();
+    }
+
+    /// Minimal RouterOS API server that answers the login and exactly one
+    /// command per connection, then closes it
+    async fn spawn_close_after_command_router() -> String {
+        spawn_close_after_command_router_refusing(0..0).await.0
+    }
+
+    /// Like [`spawn_close_after_command_router`], but hangs up before the
+    /// login on the connections numbered in `refused` (the first is 1)
+    ///
+    /// Also returns the number of connections accepted so far.
+    async fn spawn_close_after_command_router_refusing(
+        refused: std::ops::Range<usize>,
+    ) -> (String, Arc<AtomicUsize>) {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let number = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                if refused.contains(&number) {
+                    continue;
+                }
+                tokio::spawn(async move {
+                    read_sentence(&mut stream).await?;
+                    write_words(&mut stream, &["!done", ""]).await;
+                    let command = read_sentence(&mut stream).await?;
+                    match command.first().map(String::as_str) {
+                        Some("/system/resource/print") => {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=version=7.10", "=cpu-load=5", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                        Some("/system/identity/print") => {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=name=hardened-gw", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                        Some("/interface/ethernet/print") => {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=name=sfp1", "=type=ether", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                        Some("/interface/ethernet/monitor") => {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=sfp-rx-power=-7.4dBm", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                        Some("/interface/print") => {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=name=ether1", "=type=ether", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                        Some("/system/script/job/print") => {
+                            write_words(&mut stream, &["!done", "=ret=2", ""]).await;
+                        }
+                        Some("/interface/wireguard/peers/print") => {
+                            let peer = [
+                                "!re",
+                                "=interface=wg0",
+                                "=allowed-address=10.0.0.2/32",
+                                "=last-handshake=1m",
+                                "",
+                                "!done",
+                                "",
+                            ];
+                            write_words(&mut stream, &peer).await;
+                        }
+                        // A router whose clock was never set
+                        Some("/system/clock/print") => {
+                            let clock = [
+                                "!re",
+                                "=date=2020-01-01",
+                                "=time=00:00:00",
+                                "=gmt-offset=+00:00",
+                                "",
+                                "!done",
+                                "",
+                            ];
+                            write_words(&mut stream, &clock).await;
+                        }
+                        Some("/ip/route/print") if command.iter().any(|w| w == "=count-only=") => {
+                            write_words(&mut stream, &["!done", "=ret=950123", ""]).await;
+                        }
+                        Some(
+                            "/user-manager/session/print"
+                            | "/system/resource/cpu/print"
+                            | "/ipv6/route/print"
+                            | "/interface/wifi/print",
+                        ) => {
+                            let trap = "=message=no such command prefix";
+                            write_words(&mut stream, &["!trap", trap, "", "!done", ""]).await;
+                        }
+                        _ => write_words(&mut stream, &["!done", ""]).await,
+                    }
+                    Some(())
+                });
+            }
+        });
+        (addr, accepted)
+    }
+
+    #[tokio::test]
+    async fn test_collect_metrics_reconnects_when_router_closes_after_each_command() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "hardened".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::System, Collector::Interfaces]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config.clone(), pool.clone());
+        let metrics = client.collect_metrics().await.unwrap();
+
+        let system = metrics.system.unwrap();
+        assert_eq!(system.version, "7.10");
+        assert_eq!(system.identity, "hardened-gw");
+        assert_eq!(system.serial_number, "unknown");
+        assert_eq!(metrics.interfaces.len(), 1);
+        // Per-core load is unsupported by this router and is not an error
+        assert!(metrics.cpu_cores.is_empty());
+        assert!(metrics.command_errors.is_empty());
+        assert_eq!(metrics.commands_executed, 6);
+        assert_eq!(metrics.collector_durations.len(), 2);
+        // Sessions closed between commands are routine, not resets
+        assert_eq!(
+            pool.get_connection_resets(&config.address, &config.username)
+                .await,
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wireguard_handshakes_ignore_router_clock_skew() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "skewed".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Wireguard].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let before = unix_now();
+        let metrics = client.collect_metrics().await.unwrap();
+        let after = unix_now();
+
+        // The clock is read along with the peers
+        assert_eq!(metrics.commands_executed, 3);
+        let skew = metrics.router_clock_skew_seconds.unwrap();
+        assert!((1_577_836_800 - after..=1_577_836_800 - before).contains(&skew));
+        let handshake = metrics.wireguard_peers[0].latest_handshake.unwrap();
+        let handshake = i64::try_from(handshake).unwrap();
+        assert!((before - 60..=after - 60).contains(&handshake));
+    }
+
+    #[tokio::test]
+    async fn test_failed_reconnect_is_attempted_once_per_scrape() {
+        // One session is served, every later connection is refused
+        let (address, accepted) = spawn_close_after_command_router_refusing(2..usize::MAX).await;
+        let config = RouterConfig {
+            name: "down".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config.clone(), pool.clone());
+        let err = client.collect_metrics().await.unwrap_err();
+
+        assert!(err.to_string().contains("session lost"), "{err}");
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+        // The lost session counts against the circuit breaker
+        assert_eq!(
+            pool.get_connection_state(&config.address, &config.username)
+                .await,
+            Some((1, true))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refused_relogin_fails_scrape_on_per_command_closing_router() {
+        // The second reconnect is refused; later ones would be served again
+        let (address, accepted) = spawn_close_after_command_router_refusing(3..4).await;
+        let config = RouterConfig {
+            name: "hardened".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::System, Collector::Interfaces]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let err = client.collect_metrics().await.unwrap_err();
+
+        assert!(err.to_string().contains("session lost"), "{err}");
+        // No command after the refused reconnect opened another connection
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
+
+    /// RouterOS API server that accepts the login, then closes the
+    /// connection on the first command
+    async fn spawn_close_on_command_router() -> String {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    read_sentence(&mut stream).await?;
+                    write_words(&mut stream, &["!done", ""]).await;
+                    read_sentence(&mut stream).await
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_closed_connection_is_retried_only_once() {
+        let address = spawn_close_on_command_router().await;
+        let config = RouterConfig {
+            name: "flapping".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Interfaces].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config.clone(), pool.clone());
+        let result = client.collect_metrics().await;
+
+        assert!(result.is_err());
+        // One reconnect per command: the retry on the fresh connection fails
+        // too and is not retried again
+        assert_eq!(
+            pool.get_connection_resets(&config.address, &config.username)
+                .await,
+            Some(0)
+        );
+    }
+
+    /// RouterOS API server that drops its first connection in the middle of
+    /// an `/interface/print` record and serves later connections normally
+    async fn spawn_drop_mid_reply_router() -> String {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let mut first = true;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let drop_reply = std::mem::take(&mut first);
+                tokio::spawn(async move {
+                    read_sentence(&mut stream).await?;
+                    write_words(&mut stream, &["!done", ""]).await;
+                    loop {
+                        let command = read_sentence(&mut stream).await?;
+                        if command.first().map(String::as_str) != Some("/interface/print") {
+                            write_words(&mut stream, &["!done", ""]).await;
+                        } else if drop_reply {
+                            // No sentence terminator: the record is cut short
+                            write_words(&mut stream, &["!re", "=name=ether1"]).await;
+                            return Some(());
+                        } else {
+                            write_words(
+                                &mut stream,
+                                &["!re", "=name=ether1", "=type=ether", "", "!done", ""],
+                            )
+                            .await;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_connection_dropped_mid_reply_counts_as_reset() {
+        let address = spawn_drop_mid_reply_router().await;
+        let config = RouterConfig {
+            name: "flaky".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Interfaces].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config.clone(), pool.clone());
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert_eq!(metrics.interfaces.len(), 1);
+        assert!(metrics.command_errors.is_empty());
+        assert_eq!(
+            pool.get_connection_resets(&config.address, &config.username)
+                .await,
+            Some(1)
+        );
+    }
+
+    /// RouterOS API server that accepts the login, then never answers
+    async fn spawn_silent_router() -> String {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    read_sentence(&mut stream).await?;
+                    write_words(&mut stream, &["!done", ""]).await;
+                    read_sentence(&mut stream).await?;
+                    std::future::pending::<Option<()>>().await
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_collection_timeout_reports_configured_value() {
+        let address = spawn_silent_router().await;
+        let config = RouterConfig {
+            name: "slow".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::System].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(
+            ConnectionPool::new()
+                .with_timeouts(Duration::from_secs(5), Duration::from_secs(60))
+                .with_collection_timeout(Duration::from_millis(500)),
+        );
+        let client = MikroTikClient::with_pool(config, pool);
+        let err = client.collect_metrics().await.unwrap_err();
+
+        assert_eq!(err.to_string(), "Router 'slow' collection timeout (>0.5s)");
+    }
+
+    #[tokio::test]
+    async fn test_command_path_override_replaces_default_path() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "custom".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Interfaces].into_iter().collect(),
+            command_paths: [(
+                "/interface/print".to_string(),
+                "/interface/ethernet/print".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert_eq!(metrics.interfaces.len(), 1);
+        assert_eq!(metrics.interfaces[0].name, "sfp1");
+    }
+
+    #[tokio::test]
+    async fn test_sfp_reuses_ethernet_print_of_interfaces_collector() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "switch".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Interfaces, Collector::Sfp]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert_eq!(metrics.sfp_modules.len(), 1);
+        assert_eq!(metrics.sfp_modules[0].interface, "sfp1");
+        // Interface and ethernet prints plus a single monitor
+        assert_eq!(metrics.commands_executed, 3);
+    }
+
+    #[tokio::test]
+    async fn test_script_jobs_are_counted_without_listing() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "scripts".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::ScriptJobs].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert_eq!(metrics.script_jobs, Some(2));
+        assert!(metrics.command_errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_route_counts_without_ipv6_package() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "full-table".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Routes].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert_eq!(metrics.ipv4_routes, Some(950_123));
+        assert!(metrics.ipv6_routes.is_none());
+        assert!(metrics.command_errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_wifi_package_is_not_an_error() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "no-wifi".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::Wifi].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert!(metrics.wifi_channels.is_empty());
+        assert!(metrics.command_errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_user_manager_package_is_not_an_error() {
+        let address = spawn_close_after_command_router().await;
+        let config = RouterConfig {
+            name: "no-userman".to_string(),
+            address,
+            username: "admin".to_string(),
+            password: "password".to_string().into(),
+            collectors: [Collector::UserManager].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let pool = Arc::new(ConnectionPool::new());
+        let client = MikroTikClient::with_pool(config, pool);
+        let metrics = client.collect_metrics().await.unwrap();
+
+        assert!(metrics.userman_sessions.is_none());
+        assert!(metrics.command_errors.is_empty());
+    }
 }
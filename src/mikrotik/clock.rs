@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! System clock collection for MikroTik routers
+//!
+//! Parses `/system/clock/print` into the router's timezone and the offset of
+//! its clock from the exporter's clock. RouterOS reports local wall-clock time
+//! plus a GMT offset, so the router's UTC time is `date + time - gmt-offset`.
+
+use std::collections::HashMap;
+
+/// Properties of `/system/clock/print` read by `parse_clock`
+pub(super) const CLOCK_PROPERTIES: [&str; 4] = ["date", "time", "gmt-offset", "time-zone-name"];
+
+/// Router clock state relative to the exporter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockStats {
+    pub timezone: String,
+    /// Router UTC time minus exporter UTC time, in seconds
+    pub offset_seconds: i64,
+}
+
+/// Parse `/system/clock/print` response
+///
+/// `now` is the exporter's current Unix time. Returns `None` when the date or
+/// time cannot be parsed.
+pub(super) fn parse_clock(sentences: &[HashMap<String, String>], now: i64) -> Option<ClockStats> {
+    let s = sentences.iter().find(|s| s.contains_key("time"))?;
+    let days = parse_date(s.get("date")?)?;
+    let time = parse_time(s.get("time")?)?;
+    let gmt_offset = s
+        .get("gmt-offset")
+        .and_then(|v| parse_gmt_offset(v))
+        .unwrap_or(0);
+    let router_utc = days * 86_400 + time - gmt_offset;
+
+    Some(ClockStats {
+        timezone: s
+            .get("time-zone-name")
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string()),
+        offset_seconds: router_utc - now,
+    })
+}
+
+/// Days since the Unix epoch for `2024-01-15` (v7) or `jan/15/2024` (v6) dates
+pub(super) fn parse_date(date: &str) -> Option<i64> {
+    let (year, month, day) = if let Some((month, rest)) = date.split_once('/') {
+        let (day, year) = rest.split_once('/')?;
+        let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))?;
+        (
+            year.parse().ok()?,
+            u32::try_from(month).ok()? + 1,
+            day.parse().ok()?,
+        )
+    } else {
+        let mut parts = date.splitn(3, '-');
+        (
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        )
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Seconds since midnight for `HH:MM:SS`
+pub(super) fn parse_time(time: &str) -> Option<i64> {
+    let mut parts = time.splitn(3, ':');
+    let h: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let s: i64 = parts.next()?.parse().ok()?;
+    Some(h * 3600 + m * 60 + s)
+}
+
+/// GMT offset in seconds, from `+03:00` (v7) or plain seconds (v6)
+fn parse_gmt_offset(offset: &str) -> Option<i64> {
+    let Some((hours, minutes)) = offset.split_once(':') else {
+        return offset.trim_start_matches('+').parse().ok();
+    };
+    let negative = hours.starts_with('-');
+    let hours: i64 = hours.trim_start_matches(['+', '-']).parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    let seconds = hours * 3600 + minutes * 60;
+    Some(if negative { -seconds } else { seconds })
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(date: &str, time: &str, offset: &str) -> Vec<HashMap<String, String>> {
+        vec![
+            [
+                ("date", date),
+                ("time", time),
+                ("gmt-offset", offset),
+                ("time-zone-name", "Europe/Moscow"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ]
+    }
+
+    // 2024-01-15T11:32:05Z
+    const NOW: i64 = 1_705_318_325;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2024, 1, 15), 19_737);
+    }
+
+    #[test]
+    fn test_parse_clock_in_sync_v7_format() {
+        let stats = parse_clock(&clock("2024-01-15", "14:32:05", "+03:00"), NOW).unwrap();
+        assert_eq!(stats.offset_seconds, 0);
+        assert_eq!(stats.timezone, "Europe/Moscow");
+    }
+
+    #[test]
+    fn test_parse_clock_v6_format() {
+        let stats = parse_clock(&clock("jan/15/2024", "14:32:05", "10800"), NOW).unwrap();
+        assert_eq!(stats.offset_seconds, 0);
+    }
+
+    #[test]
+    fn test_parse_clock_wrong_clock_has_large_offset() {
+        let stats = parse_clock(&clock("2023-01-15", "14:32:05", "+03:00"), NOW).unwrap();
+        assert_eq!(stats.offset_seconds, -365 * 86_400);
+    }
+
+    #[test]
+    fn test_parse_gmt_offset_negative() {
+        assert_eq!(parse_gmt_offset("-05:30"), Some(-19_800));
+    }
+
+    #[test]
+    fn test_parse_clock_invalid_date() {
+        assert!(parse_clock(&clock("garbage", "14:32:05", "+00:00"), NOW).is_none());
+    }
+}
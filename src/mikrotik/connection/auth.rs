@@ -5,14 +5,34 @@
 
 use md5::compute as md5_compute;
 
-use super::RouterOsConnection;
+use super::{RouterOsConnection, is_connection_closed};
+
+/// API login method a router accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginMethod {
+    /// Plain name and password (RouterOS 6.43+)
+    New,
+    /// MD5 challenge-response (pre-6.43)
+    Legacy,
+}
+
+impl LoginMethod {
+    /// Numeric value exported as `mikrotik_login_method`
+    #[must_use]
+    pub fn as_gauge(self) -> i64 {
+        match self {
+            Self::New => 0,
+            Self::Legacy => 1,
+        }
+    }
+}
 
 impl RouterOsConnection {
     pub(crate) async fn login(
         &mut self,
         username: &str,
         password: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<LoginMethod, Box<dyn std::error::Error + Send + Sync>> {
         tracing::trace!("Attempting login for user: {}", username);
         // Try new login method first (RouterOS 6.43+)
         let login_result = self
@@ -24,13 +44,13 @@ impl RouterOsConnection {
             .await;
 
         match login_result {
-            Ok(sentences) => {
+            Ok(reply) => {
                 tracing::trace!(
                     "New login method response received, {} sentences",
-                    sentences.len()
+                    reply.sentences.len()
                 );
                 // Check for error messages
-                for s in &sentences {
+                for s in &reply.sentences {
                     if let Some(msg) = s.get("message") {
                         if msg.contains("failure") || msg.contains("invalid") {
                             tracing::trace!("Login failed with message: {}", msg);
@@ -40,8 +60,10 @@ impl RouterOsConnection {
                     }
                 }
                 tracing::debug!("Login successful (new method)");
-                return Ok(());
+                return Ok(LoginMethod::New);
             }
+            // A dropped connection can't be retried with the legacy method
+            Err(e) if is_connection_closed(e.as_ref()) => return Err(e),
             Err(e) => {
                 tracing::debug!("New login method failed, trying legacy method: {}", e);
             }
@@ -49,16 +71,14 @@ impl RouterOsConnection {
 
         // Fallback to legacy challenge-response method (pre-6.43)
         tracing::trace!("Requesting challenge for legacy login");
-        let sentences = self.raw_command(vec!["/login".to_string()]).await?;
-        let mut challenge_hex = None;
-        for s in sentences {
-            if let Some(ret) = s.get("ret") {
-                challenge_hex = Some(ret.clone());
-            }
-        }
-        let challenge_hex = challenge_hex.ok_or("No challenge 'ret' received")?;
+        let reply = self.raw_command(vec!["/login".to_string()]).await?;
+        let challenge_hex = reply
+            .done
+            .get("ret")
+            .or_else(|| reply.sentences.iter().find_map(|s| s.get("ret")))
+            .ok_or("No challenge 'ret' received")?;
         tracing::trace!("Challenge received, length: {}", challenge_hex.len());
-        let challenge = hex::decode(&challenge_hex)?;
+        let challenge = hex::decode(challenge_hex)?;
 
         // Build MD5 hash of 0 + password + challenge
         let mut data = Vec::with_capacity(1 + password.len() + challenge.len());
@@ -69,7 +89,7 @@ impl RouterOsConnection {
         let mut response = String::from("00");
         response.push_str(&hex::encode(digest.0));
 
-        let login_sentences = self
+        let login_reply = self
             .raw_command(vec![
                 "/login".to_string(),
                 format!("=name={}", username),
@@ -77,12 +97,12 @@ impl RouterOsConnection {
             ])
             .await?;
         // If no !trap assume success
-        for s in &login_sentences {
+        for s in &login_reply.sentences {
             if s.contains_key("message") {
                 tracing::warn!("Login message: {:?}", s.get("message"));
             }
         }
         tracing::debug!("Login successful (legacy method)");
-        Ok(())
+        Ok(LoginMethod::Legacy)
     }
 }
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Builder for RouterOS API commands
+
+/// A RouterOS API command path together with its attribute and query words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Command {
+    path: String,
+    args: Vec<String>,
+}
+
+impl Command {
+    /// Creates a command for the given menu path, e.g. `/interface/print`
+    pub(crate) fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Requests detail mode (`=detail=`) from print commands
+    ///
+    /// Some menus (firewall rules among them) only return comments and
+    /// rarely used properties reliably in detail mode.
+    pub(crate) fn detail(self) -> Self {
+        self.flag("detail")
+    }
+
+    /// Requests statistics (`=stats=`) from print commands
+    ///
+    /// Firewall and queue menus only report byte and packet counters with it.
+    pub(crate) fn stats(self) -> Self {
+        self.flag("stats")
+    }
+
+    /// Requests a single reply (`=once=`) from monitor commands
+    pub(crate) fn once(self) -> Self {
+        self.flag("once")
+    }
+
+    /// Requests only the number of matching records (`=count-only=`)
+    ///
+    /// The count comes back as `ret` on the `!done` reply instead of records.
+    pub(crate) fn count_only(self) -> Self {
+        self.flag("count-only")
+    }
+
+    /// Adds a `=name=value` attribute, e.g. `=numbers=wifi1`
+    pub(crate) fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.args.push(format!("={name}={value}"));
+        self
+    }
+
+    /// Restricts the reply to the given properties (`=.proplist=`)
+    ///
+    /// Large tables such as connection tracking and firewall rules are much
+    /// cheaper to transfer and parse when only the parsed fields are sent.
+    /// Properties a menu doesn't have are left out of the reply.
+    pub(crate) fn proplist(self, properties: &[&str]) -> Self {
+        self.attribute(".proplist", &properties.join(","))
+    }
+
+    /// Adds a `?name=value` query word so only matching records are returned
+    pub(crate) fn query(mut self, name: &str, value: &str) -> Self {
+        self.args.push(format!("?{name}={value}"));
+        self
+    }
+
+    fn flag(mut self, name: &str) -> Self {
+        let word = format!("={name}=");
+        if !self.args.contains(&word) {
+            self.args.push(word);
+        }
+        self
+    }
+
+    /// Replaces the menu path, keeping attributes and queries
+    pub(crate) fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Menu path of the command
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Words to send for this command: the path followed by its attributes and queries
+    pub(crate) fn words(&self) -> Vec<String> {
+        let mut words = Vec::with_capacity(1 + self.args.len());
+        words.push(self.path.clone());
+        words.extend(self.args.iter().cloned());
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_command_words() {
+        let command = Command::new("/interface/print");
+        assert_eq!(command.words(), vec!["/interface/print"]);
+        assert_eq!(command.path(), "/interface/print");
+    }
+
+    #[test]
+    fn test_detail_and_stats_flags() {
+        let command = Command::new("/ip/firewall/filter/print").stats().detail();
+        assert_eq!(
+            command.words(),
+            vec!["/ip/firewall/filter/print", "=stats=", "=detail="]
+        );
+    }
+
+    #[test]
+    fn test_monitor_command_with_attribute() {
+        let command = Command::new("/interface/wifi/monitor")
+            .attribute("numbers", "wifi1")
+            .once();
+        assert_eq!(
+            command.words(),
+            vec!["/interface/wifi/monitor", "=numbers=wifi1", "=once="]
+        );
+    }
+
+    #[test]
+    fn test_print_with_query() {
+        let command = Command::new("/ip/route/print").query("dst-address", "0.0.0.0/0");
+        assert_eq!(
+            command.words(),
+            vec!["/ip/route/print", "?dst-address=0.0.0.0/0"]
+        );
+    }
+
+    #[test]
+    fn test_proplist_word() {
+        let command = Command::new("/interface/print").proplist(&["name", "rx-byte", "running"]);
+        assert_eq!(
+            command.words(),
+            vec!["/interface/print", "=.proplist=name,rx-byte,running"]
+        );
+    }
+
+    #[test]
+    fn test_count_only_flag() {
+        let command = Command::new("/ip/route/print").count_only();
+        assert_eq!(command.words(), vec!["/ip/route/print", "=count-only="]);
+    }
+
+    #[test]
+    fn test_flags_are_not_duplicated() {
+        let command = Command::new("/ip/firewall/filter/print").detail().detail();
+        assert_eq!(command.words().len(), 2);
+    }
+}
@@ -4,147 +4,267 @@
 //! Low-level RouterOS API connection handling
 
 mod auth;
+mod command;
 mod parse;
 mod protocol;
+mod tls;
 
 use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::Poll;
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 
-pub(crate) use parse::{parse_connection_tracking, parse_interfaces, parse_system};
+pub use auth::LoginMethod;
+pub(crate) use command::Command;
+pub(crate) use parse::{
+    merge_ethernet_counters, parse_connection_tracking, parse_identity, parse_interfaces,
+    parse_serial_number, parse_system,
+};
 pub use protocol::encode_length;
 use protocol::read_length;
+use tls::Stream;
 
-/// Connection timeout (5 seconds)
+/// Default connection timeout (5 seconds)
 const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Read operation timeout (30 seconds)
+/// Default read operation timeout (30 seconds)
 const READ_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Time limits for establishing an API session and for waiting on a reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Timeouts {
+    /// TCP connect and TLS handshake, each
+    pub(crate) connect: Duration,
+    /// Whole reply to a single command
+    pub(crate) read: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: CONNECTION_TIMEOUT,
+            read: READ_TIMEOUT,
+        }
+    }
+}
+
+/// Records of a command reply together with the attributes of its `!done` sentence
+struct Reply {
+    sentences: Vec<HashMap<String, String>>,
+    /// Carries `ret` for `count-only` queries and the legacy login challenge
+    done: HashMap<String, String>,
+}
+
+/// How the API session is carried to the router
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Transport {
+    /// Plain API service (port 8728)
+    #[default]
+    Plain,
+    /// api-ssl service (port 8729)
+    Tls { verify_certificate: bool },
+}
+
 /// Low-level RouterOS API connection
 pub(super) struct RouterOsConnection {
-    stream: TcpStream,
+    stream: Stream,
+    read_timeout: Duration,
+    /// Set when the router dropped the connection in the middle of a reply
+    dropped_reply: bool,
 }
 
 impl RouterOsConnection {
     pub(super) async fn connect(
         addr: &str,
+        transport: Transport,
+        timeouts: Timeouts,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let timed_out = || {
+            format!(
+                "Connection timeout: {addr} did not answer within {} seconds",
+                timeouts.connect.as_secs_f64()
+            )
+        };
         tracing::trace!("Attempting TCP connection to: {}", addr);
-        let stream = timeout(CONNECTION_TIMEOUT, TcpStream::connect(addr)).await??;
+        let tcp = timeout(timeouts.connect, TcpStream::connect(addr))
+            .await
+            .map_err(|_| timed_out())??;
         tracing::trace!("TCP connection established to: {}", addr);
-        Ok(Self { stream })
+        let stream = match transport {
+            Transport::Plain => Stream::Plain(tcp),
+            Transport::Tls { verify_certificate } => {
+                let stream = timeout(
+                    timeouts.connect,
+                    tls::handshake(addr, tcp, verify_certificate),
+                )
+                .await
+                .map_err(|_| timed_out())??;
+                tracing::trace!("TLS session established to: {}", addr);
+                stream
+            }
+        };
+        Ok(Self {
+            stream,
+            read_timeout: timeouts.read,
+            dropped_reply: false,
+        })
+    }
+
+    /// Non-blocking check whether the peer has closed the connection
+    ///
+    /// Some hardened configurations close the API session after every command,
+    /// so a pooled connection may be dead by the time it is reused. Unsolicited
+    /// data also marks the connection unusable, since the stream is out of sync.
+    ///
+    /// The check reads through the session stream, so TLS records that carry
+    /// no data, such as session tickets, are handled by rustls and a
+    /// `close_notify` reads as end of stream.
+    pub(super) async fn is_closed(&mut self) -> bool {
+        let mut buf = [0u8; 1];
+        let mut buf = ReadBuf::new(&mut buf);
+        std::future::poll_fn(|cx| {
+            Poll::Ready(
+                Pin::new(&mut self.stream)
+                    .poll_read(cx, &mut buf)
+                    .is_ready(),
+            )
+        })
+        .await
     }
 
     pub(super) async fn command(
         &mut self,
-        path: &str,
-        args: &[&str],
+        command: &Command,
     ) -> Result<Vec<HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut words: Vec<String> = Vec::with_capacity(1 + args.len());
-        words.push(path.to_string());
-        for a in args {
-            words.push((*a).to_string());
-        }
-        self.raw_command(words).await
+        Ok(self.raw_command(command.words()).await?.sentences)
+    }
+
+    /// Runs a `count-only` command and returns the `ret` value of its `!done` reply
+    pub(super) async fn command_count(
+        &mut self,
+        command: &Command,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let command = command.clone().count_only();
+        let reply = self.raw_command(command.words()).await?;
+        let ret = reply
+            .done
+            .get("ret")
+            .ok_or_else(|| format!("No count returned for {}", command.path()))?;
+        Ok(ret.parse()?)
     }
 
     async fn raw_command(
         &mut self,
         words: Vec<String>,
-    ) -> Result<Vec<HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Reply, Box<dyn std::error::Error + Send + Sync>> {
         self.send_words(&words).await?;
-        self.read_sentences().await
+        self.read_reply().await
     }
 
+    /// Writes a sentence in one go, so it travels in a single TLS record
     async fn send_words(
         &mut self,
         words: &[String],
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut sentence = Vec::new();
         for w in words {
-            self.write_word(w).await?;
+            sentence.extend(encode_length(w.len()));
+            sentence.extend(w.as_bytes());
         }
         // zero length word terminator
-        self.stream.write_all(&[0]).await?;
+        sentence.push(0);
+        self.stream.write_all(&sentence).await?;
+        self.stream.flush().await?;
         Ok(())
     }
 
-    async fn write_word(
-        &mut self,
-        word: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let bytes = word.as_bytes();
-        self.stream.write_all(&encode_length(bytes.len())).await?;
-        self.stream.write_all(bytes).await?;
-        Ok(())
+    /// Whether the router dropped the connection in the middle of a reply
+    /// since the last call
+    ///
+    /// A connection closed before the reply started, e.g. between commands,
+    /// is not reported.
+    pub(super) fn take_dropped_reply(&mut self) -> bool {
+        std::mem::take(&mut self.dropped_reply)
     }
 
-    async fn read_sentences(
-        &mut self,
-    ) -> Result<Vec<HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>> {
-        // Wrap the entire read operation in a timeout to prevent hanging on slow/dead connections
-        timeout(READ_TIMEOUT, async {
+    async fn read_reply(&mut self) -> Result<Reply, Box<dyn std::error::Error + Send + Sync>> {
+        let read_timeout = self.read_timeout;
+        let mut started = false;
+        let read = async {
             let mut sentences: Vec<HashMap<String, String>> = Vec::new();
-            let mut current: Option<HashMap<String, String>> = None;
+            let mut trap: Option<String> = None;
             loop {
                 let word = self.read_word().await?;
+                started = true;
                 if word.is_empty() {
                     continue;
                 }
                 tracing::trace!("Received word: {}", word);
-                if word == "!done" {
-                    if let Some(s) = current.take() {
-                        sentences.push(s);
+                // Attributes of every reply sentence, `!done` included, are read
+                // up to its terminator so the next command starts in sync
+                let attributes = self.read_attributes().await?;
+                match word.as_str() {
+                    // A record without fields carries nothing for the parsers
+                    "!re" if !attributes.is_empty() => sentences.push(attributes),
+                    "!trap" => {
+                        tracing::trace!("Trap received: {:?}", attributes);
+                        trap = Some(
+                            attributes
+                                .get("message")
+                                .cloned()
+                                .unwrap_or_else(|| "trap".to_string()),
+                        );
                     }
-                    tracing::trace!("Command complete, {} sentences received", sentences.len());
-                    break;
-                }
-                if word == "!trap" {
-                    tracing::trace!("Trap received, reading trap details");
-                    // collect trap details
-                    let mut trap = HashMap::new();
-                    loop {
-                        let w = self.read_word().await?;
-                        if w.is_empty() {
-                            continue;
-                        }
-                        if let Some(stripped) = w.strip_prefix('=') {
-                            if let Some((k, v)) = stripped.split_once('=') {
-                                trap.insert(k.to_string(), v.to_string());
-                            }
-                            continue;
-                        }
-                        if w.starts_with('!') || w == "!done" {
-                            break;
+                    "!done" => {
+                        if let Some(msg) = trap {
+                            return Err(format!("RouterOS trap: {msg}").into());
                         }
+                        tracing::trace!("Command complete, {} sentences received", sentences.len());
+                        return Ok(Reply {
+                            sentences,
+                            done: attributes,
+                        });
                     }
-                    let msg = trap
-                        .get("message")
-                        .cloned()
-                        .unwrap_or_else(|| "trap".to_string());
-                    return Err(format!("RouterOS trap: {msg}").into());
-                }
-                if word == "!re" {
-                    if let Some(s) = current.take() {
-                        sentences.push(s);
-                    }
-                    current = Some(HashMap::new());
-                    continue;
+                    // ignore other headers
+                    _ => {}
                 }
-                if let Some(stripped) = word.strip_prefix('=') {
-                    let tgt = current.get_or_insert(HashMap::new());
-                    if let Some((k, v)) = stripped.split_once('=') {
-                        tgt.insert(k.to_string(), v.to_string());
-                    }
-                }
-                // ignore other headers
             }
-            Ok(sentences)
-        })
-        .await
-        .map_err(|_| "Read timeout: RouterOS did not respond within 30 seconds")?
+        };
+        // Wrap the entire read operation in a timeout to prevent hanging on slow/dead connections
+        let reply: Result<Reply, Box<dyn std::error::Error + Send + Sync>> =
+            timeout(read_timeout, read).await.map_err(|_| {
+                format!(
+                    "Read timeout: RouterOS did not respond within {} seconds",
+                    read_timeout.as_secs_f64()
+                )
+            })?;
+        if let Err(e) = &reply
+            && started
+            && is_connection_closed(e.as_ref())
+        {
+            self.dropped_reply = true;
+        }
+        reply
+    }
+
+    /// Reads `=key=value` words up to the end of the current sentence
+    async fn read_attributes(
+        &mut self,
+    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut attributes = HashMap::new();
+        loop {
+            let word = self.read_word().await?;
+            if word.is_empty() {
+                return Ok(attributes);
+            }
+            tracing::trace!("Received word: {}", word);
+            if let Some((k, v)) = word.strip_prefix('=').and_then(|w| w.split_once('=')) {
+                attributes.insert(k.to_string(), v.to_string());
+            }
+        }
     }
 
     async fn read_word(&mut self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
@@ -157,3 +277,158 @@ impl RouterOsConnection {
         Ok(String::from_utf8_lossy(&buf).into())
     }
 }
+
+/// Whether an error means the router closed the connection mid-session
+pub(super) fn is_connection_closed(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<std::io::Error>().is_some_and(|e| {
+        matches!(
+            e.kind(),
+            std::io::ErrorKind::UnexpectedEof
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+        )
+    })
+}
+
+/// Whether an error is RouterOS rejecting an unknown menu, e.g. because the
+/// package providing it isn't installed
+pub(super) fn is_missing_menu(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.to_string().contains("no such command")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_connection_closed_matches_eof_and_reset() {
+        for kind in [
+            std::io::ErrorKind::UnexpectedEof,
+            std::io::ErrorKind::BrokenPipe,
+            std::io::ErrorKind::ConnectionReset,
+        ] {
+            let err: Box<dyn std::error::Error + Send + Sync> = std::io::Error::from(kind).into();
+            assert!(is_connection_closed(err.as_ref()), "{kind:?}");
+        }
+    }
+
+    /// Serves one canned reply per received sentence
+    async fn spawn_router(replies: Vec<Vec<&'static str>>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            for reply in replies {
+                // Test words are always shorter than 0x80 bytes
+                loop {
+                    let len = stream.read_u8().await.unwrap() as usize;
+                    if len == 0 {
+                        break;
+                    }
+                    let mut buf = vec![0u8; len];
+                    stream.read_exact(&mut buf).await.unwrap();
+                }
+                let mut out = Vec::new();
+                for word in reply {
+                    out.extend(encode_length(word.len()));
+                    out.extend(word.as_bytes());
+                }
+                stream.write_all(&out).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_command_count_reads_ret_and_stays_in_sync() {
+        let addr = spawn_router(vec![
+            vec!["!done", "=ret=42", ""],
+            vec!["!re", "", "!re", "=name=ether1", "", "!done", ""],
+        ])
+        .await;
+        let mut conn = RouterOsConnection::connect(&addr, Transport::Plain, Timeouts::default())
+            .await
+            .unwrap();
+
+        let count = conn
+            .command_count(&Command::new("/ip/route/print"))
+            .await
+            .unwrap();
+        assert_eq!(count, 42);
+
+        // The `ret` trailer must not leak into the next reply; empty records are dropped
+        let sentences = conn
+            .command(&Command::new("/interface/print"))
+            .await
+            .unwrap();
+        assert_eq!(sentences.len(), 1);
+        assert_eq!(sentences[0].get("name").map(String::as_str), Some("ether1"));
+        assert!(!sentences[0].contains_key("ret"));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_reports_configured_value() {
+        // The kernel completes the handshake; nobody ever answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let timeouts = Timeouts {
+            read: Duration::from_millis(100),
+            ..Timeouts::default()
+        };
+        let mut conn = RouterOsConnection::connect(&addr, Transport::Plain, timeouts)
+            .await
+            .unwrap();
+
+        let err = conn
+            .command(&Command::new("/interface/print"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Read timeout: RouterOS did not respond within 0.1 seconds"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trap_is_reported_after_done() {
+        let addr = spawn_router(vec![
+            vec!["!trap", "=message=no such command", "", "!done", ""],
+            vec!["!done", "=ret=3", ""],
+        ])
+        .await;
+        let mut conn = RouterOsConnection::connect(&addr, Transport::Plain, Timeouts::default())
+            .await
+            .unwrap();
+
+        let err = conn
+            .command(&Command::new("/bogus/print"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "RouterOS trap: no such command");
+        let count = conn
+            .command_count(&Command::new("/ip/route/print"))
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_is_connection_closed_ignores_other_errors() {
+        let timeout: Box<dyn std::error::Error + Send + Sync> = "Read timeout".into();
+        let refused: Box<dyn std::error::Error + Send + Sync> =
+            std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+        assert!(!is_connection_closed(timeout.as_ref()));
+        assert!(!is_connection_closed(refused.as_ref()));
+    }
+
+    #[test]
+    fn test_is_missing_menu() {
+        let missing: Box<dyn std::error::Error + Send + Sync> =
+            "RouterOS trap: no such command prefix".into();
+        let denied: Box<dyn std::error::Error + Send + Sync> =
+            "RouterOS trap: not enough permissions".into();
+        assert!(is_missing_menu(missing.as_ref()));
+        assert!(!is_missing_menu(denied.as_ref()));
+    }
+}
@@ -3,6 +3,7 @@
 
 //! RouterOS response parsing helpers
 
+use crate::config::IpPrefix;
 use crate::mikrotik::types::{ConnectionTrackingStats, InterfaceStats, SystemResource};
 use std::collections::HashMap;
 
@@ -19,6 +20,7 @@ pub(crate) fn parse_system(sentences: &[HashMap<String, String>]) -> SystemResou
             .get("cpu-load")
             .and_then(|v| v.parse().ok())
             .unwrap_or(0),
+        cpu_count: first.get("cpu-count").and_then(|v| v.parse().ok()),
         free_memory: first
             .get("free-memory")
             .and_then(|v| v.parse().ok())
@@ -27,6 +29,14 @@ pub(crate) fn parse_system(sentences: &[HashMap<String, String>]) -> SystemResou
             .get("total-memory")
             .and_then(|v| v.parse().ok())
             .unwrap_or(0),
+        free_hdd_space: first
+            .get("free-hdd-space")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
+        total_hdd_space: first
+            .get("total-hdd-space")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
         version: first
             .get("version")
             .cloned()
@@ -35,9 +45,33 @@ pub(crate) fn parse_system(sentences: &[HashMap<String, String>]) -> SystemResou
             .get("board-name")
             .cloned()
             .unwrap_or_else(|| "unknown".to_string()),
+        architecture: first
+            .get("architecture-name")
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string()),
+        identity: "unknown".to_string(),
+        serial_number: "unknown".to_string(),
     }
 }
 
+/// Router name from `/system/identity/print`
+pub(crate) fn parse_identity(sentences: &[HashMap<String, String>]) -> Option<String> {
+    sentences
+        .iter()
+        .find_map(|s| s.get("name"))
+        .filter(|name| !name.is_empty())
+        .cloned()
+}
+
+/// Serial number from `/system/routerboard/print`; CHR reports none
+pub(crate) fn parse_serial_number(sentences: &[HashMap<String, String>]) -> Option<String> {
+    sentences
+        .iter()
+        .find_map(|s| s.get("serial-number"))
+        .filter(|serial| !serial.is_empty())
+        .cloned()
+}
+
 pub(crate) fn parse_interfaces(sentences: &[HashMap<String, String>]) -> Vec<InterfaceStats> {
     let mut out = Vec::new();
     for s in sentences {
@@ -53,26 +87,76 @@ pub(crate) fn parse_interfaces(sentences: &[HashMap<String, String>]) -> Vec<Int
                 tx_packets: s.get("tx-packet").and_then(|v| v.parse().ok()).unwrap_or(0),
                 rx_errors: s.get("rx-error").and_then(|v| v.parse().ok()).unwrap_or(0),
                 tx_errors: s.get("tx-error").and_then(|v| v.parse().ok()).unwrap_or(0),
+                rx_drops: s.get("rx-drop").and_then(|v| v.parse().ok()).unwrap_or(0),
+                tx_drops: s.get("tx-drop").and_then(|v| v.parse().ok()).unwrap_or(0),
                 running: s.get("running").is_some_and(|v| v == "true"),
+                disabled: s.get("disabled").is_some_and(|v| v == "true"),
+                mtu: s.get("mtu").and_then(|v| v.parse().ok()),
+                actual_mtu: s.get("actual-mtu").and_then(|v| v.parse().ok()),
+                rx_multicast: s.get("rx-multicast").and_then(|v| v.parse().ok()),
+                tx_multicast: s.get("tx-multicast").and_then(|v| v.parse().ok()),
+                rx_broadcast: s.get("rx-broadcast").and_then(|v| v.parse().ok()),
+                tx_broadcast: s.get("tx-broadcast").and_then(|v| v.parse().ok()),
             });
         }
     }
     out
 }
 
+/// Fill multicast and broadcast counters from `/interface/ethernet/print stats`
+///
+/// `/interface/print` doesn't report them, so they are matched by name.
+pub(crate) fn merge_ethernet_counters(
+    interfaces: &mut [InterfaceStats],
+    sentences: &[HashMap<String, String>],
+) {
+    for s in sentences {
+        let Some(iface) = s
+            .get("name")
+            .and_then(|name| interfaces.iter_mut().find(|i| &i.name == name))
+        else {
+            continue;
+        };
+        let counter = |key: &str| s.get(key).and_then(|v| v.parse().ok());
+        iface.rx_multicast = counter("rx-multicast").or(iface.rx_multicast);
+        iface.tx_multicast = counter("tx-multicast").or(iface.tx_multicast);
+        iface.rx_broadcast = counter("rx-broadcast").or(iface.rx_broadcast);
+        iface.tx_broadcast = counter("tx-broadcast").or(iface.tx_broadcast);
+    }
+}
+
+/// Source address bucket for connections outside the configured prefixes
+const OTHER_SOURCES: &str = "other";
+
 /// Parse connection tracking entries and aggregate by source address and protocol
+///
+/// When `prefixes` is non-empty, sources outside all of them are aggregated
+/// under `other` to bound label cardinality.
+///
+/// Also returns the number of distinct source addresses, counted before any
+/// aggregation.
 pub(crate) fn parse_connection_tracking(
     sentences: &[HashMap<String, String>],
     ip_version: &str,
-) -> Vec<ConnectionTrackingStats> {
-    use std::collections::HashMap;
+    prefixes: &[IpPrefix],
+) -> (Vec<ConnectionTrackingStats>, u64) {
+    use std::collections::{HashMap, HashSet};
 
     // Aggregate connections by (src_address, protocol)
     let mut aggregated: HashMap<(String, String), u64> = HashMap::new();
+    let mut sources = HashSet::new();
 
     for s in sentences {
         if let Some(src) = s.get("src-address") {
-            let src_ip = extract_src_ip(src);
+            let mut src_ip = extract_src_ip(src);
+            sources.insert(src_ip.clone());
+            if !prefixes.is_empty()
+                && !src_ip
+                    .parse()
+                    .is_ok_and(|ip| prefixes.iter().any(|p| p.contains(ip)))
+            {
+                src_ip = OTHER_SOURCES.to_string();
+            }
             let protocol = s
                 .get("protocol")
                 .cloned()
@@ -83,7 +167,7 @@ pub(crate) fn parse_connection_tracking(
     }
 
     // Convert to Vec<ConnectionTrackingStats>
-    aggregated
+    let stats = aggregated
         .into_iter()
         .map(|((src_address, protocol), count)| ConnectionTrackingStats {
             src_address,
@@ -91,35 +175,32 @@ pub(crate) fn parse_connection_tracking(
             connection_count: count,
             ip_version: ip_version.to_string(),
         })
-        .collect()
+        .collect();
+    (stats, sources.len() as u64)
 }
 
 /// Extract the source IP address from a RouterOS connection tracking entry.
 ///
 /// Handles IPv4 with port (`192.168.1.1:12345`), IPv6 with brackets
-/// (`[::1]:12345`), and bare IPs without ports.
+/// (`[::1]:12345`), and bare IPs without ports. IPv6 addresses lose their
+/// `%zone` suffix and are printed in canonical lowercase form, so one source
+/// always maps to the same label.
 #[must_use]
 fn extract_src_ip(src: &str) -> String {
-    if let Ok(socket) = src.parse::<std::net::SocketAddr>() {
-        return socket.ip().to_string();
-    }
-
-    if let Some(stripped) = src.strip_prefix('[') {
-        if let Some((ip, _port)) = stripped.split_once(":]") {
-            return ip.to_string();
-        }
-        if let Some((ip, _rest)) = stripped.split_once(']') {
-            return ip.to_string();
-        }
-    }
-
-    if let Some((ip, _port)) = src.rsplit_once(':') {
-        if ip.parse::<std::net::IpAddr>().is_ok() || ip.contains('.') {
-            return ip.to_string();
-        }
-    }
-
-    src.to_string()
+    let src = src.trim();
+    let host = match src.strip_prefix('[') {
+        Some(stripped) => stripped.split_once(']').map_or(stripped, |(ip, _port)| ip),
+        // A single colon separates an IPv4 address from its port; bare IPv6
+        // addresses have several
+        None => match src.rsplit_once(':') {
+            Some((ip, _port)) if !ip.contains(':') => ip,
+            _ => src,
+        },
+    };
+    let host = host.split_once('%').map_or(host, |(ip, _zone)| ip);
+
+    host.parse::<std::net::IpAddr>()
+        .map_or_else(|_| host.to_string(), |ip| ip.to_string())
 }
 
 #[cfg(test)]
@@ -132,18 +213,26 @@ mod tests {
         data.insert("version".to_string(), "7.10".to_string());
         data.insert("uptime".to_string(), "1w2d3h4m5s".to_string());
         data.insert("cpu-load".to_string(), "25".to_string());
+        data.insert("cpu-count".to_string(), "4".to_string());
         data.insert("free-memory".to_string(), "524288000".to_string());
         data.insert("total-memory".to_string(), "1073741824".to_string());
+        data.insert("free-hdd-space".to_string(), "98304000".to_string());
+        data.insert("total-hdd-space".to_string(), "134217728".to_string());
         data.insert("board-name".to_string(), "RB750Gr3".to_string());
+        data.insert("architecture-name".to_string(), "mipsbe".to_string());
 
         let result = parse_system(&[data]);
 
         assert_eq!(result.version, "7.10");
         assert_eq!(result.uptime, "1w2d3h4m5s");
         assert_eq!(result.cpu_load, 25);
+        assert_eq!(result.cpu_count, Some(4));
         assert_eq!(result.free_memory, 524288000);
         assert_eq!(result.total_memory, 1073741824);
+        assert_eq!(result.free_hdd_space, 98304000);
+        assert_eq!(result.total_hdd_space, 134217728);
         assert_eq!(result.board_name, "RB750Gr3");
+        assert_eq!(result.architecture, "mipsbe");
     }
 
     #[test]
@@ -152,7 +241,29 @@ mod tests {
         assert_eq!(result.version, "unknown");
         assert_eq!(result.uptime, "0s");
         assert_eq!(result.cpu_load, 0);
+        assert_eq!(result.cpu_count, None);
         assert_eq!(result.board_name, "unknown");
+        assert_eq!(result.architecture, "unknown");
+        assert_eq!(result.identity, "unknown");
+        assert_eq!(result.serial_number, "unknown");
+    }
+
+    #[test]
+    fn test_parse_identity_and_serial_number() {
+        let identity = HashMap::from([("name".to_string(), "core-gw".to_string())]);
+        let routerboard = HashMap::from([
+            ("routerboard".to_string(), "true".to_string()),
+            ("serial-number".to_string(), "HEX0123456".to_string()),
+        ]);
+        let chr = HashMap::from([("routerboard".to_string(), "false".to_string())]);
+
+        assert_eq!(parse_identity(&[identity]).as_deref(), Some("core-gw"));
+        assert_eq!(
+            parse_serial_number(&[routerboard]).as_deref(),
+            Some("HEX0123456")
+        );
+        assert!(parse_serial_number(&[chr]).is_none());
+        assert!(parse_identity(&[]).is_none());
     }
 
     #[test]
@@ -178,7 +289,11 @@ mod tests {
         iface1.insert("tx-packet".to_string(), "20".to_string());
         iface1.insert("rx-error".to_string(), "0".to_string());
         iface1.insert("tx-error".to_string(), "0".to_string());
+        iface1.insert("rx-drop".to_string(), "7".to_string());
+        iface1.insert("tx-drop".to_string(), "3".to_string());
         iface1.insert("running".to_string(), "true".to_string());
+        iface1.insert("mtu".to_string(), "1500".to_string());
+        iface1.insert("actual-mtu".to_string(), "1492".to_string());
 
         let result = parse_interfaces(&[iface1]);
 
@@ -186,7 +301,41 @@ mod tests {
         assert_eq!(result[0].name, "ether1");
         assert_eq!(result[0].rx_bytes, 1000);
         assert_eq!(result[0].tx_bytes, 2000);
+        assert_eq!(result[0].rx_errors, 0);
+        assert_eq!(result[0].rx_drops, 7);
+        assert_eq!(result[0].tx_drops, 3);
         assert!(result[0].running);
+        assert_eq!(result[0].mtu, Some(1500));
+        assert_eq!(result[0].actual_mtu, Some(1492));
+    }
+
+    #[test]
+    fn test_merge_ethernet_counters() {
+        let mut iface = HashMap::new();
+        iface.insert("name".to_string(), "ether1".to_string());
+        iface.insert("type".to_string(), "ether".to_string());
+        let mut bridge = HashMap::new();
+        bridge.insert("name".to_string(), "bridge".to_string());
+        bridge.insert("type".to_string(), "bridge".to_string());
+        let mut interfaces = parse_interfaces(&[iface, bridge]);
+
+        let mut stats = HashMap::new();
+        stats.insert("name".to_string(), "ether1".to_string());
+        stats.insert("rx-multicast".to_string(), "120".to_string());
+        stats.insert("tx-multicast".to_string(), "30".to_string());
+        stats.insert("rx-broadcast".to_string(), "9000".to_string());
+        stats.insert("tx-broadcast".to_string(), "12".to_string());
+        let mut unknown = HashMap::new();
+        unknown.insert("name".to_string(), "sfp1".to_string());
+        unknown.insert("rx-broadcast".to_string(), "1".to_string());
+        merge_ethernet_counters(&mut interfaces, &[stats, unknown]);
+
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].rx_multicast, Some(120));
+        assert_eq!(interfaces[0].tx_multicast, Some(30));
+        assert_eq!(interfaces[0].rx_broadcast, Some(9000));
+        assert_eq!(interfaces[0].tx_broadcast, Some(12));
+        assert_eq!(interfaces[1].rx_broadcast, None);
     }
 
     #[test]
@@ -225,6 +374,21 @@ mod tests {
         assert!(!result[0].running);
     }
 
+    #[test]
+    fn test_parse_interfaces_disabled() {
+        let mut iface = HashMap::new();
+        iface.insert("name".to_string(), "ether2".to_string());
+        iface.insert("type".to_string(), "ether".to_string());
+        iface.insert("running".to_string(), "false".to_string());
+        iface.insert("disabled".to_string(), "true".to_string());
+
+        let result = parse_interfaces(&[iface]);
+
+        assert_eq!(result.len(), 1);
+        assert!(!result[0].running);
+        assert!(result[0].disabled);
+    }
+
     #[test]
     fn test_parse_interfaces_filters_peers() {
         let mut peer = HashMap::new();
@@ -263,7 +427,7 @@ mod tests {
 
     #[test]
     fn test_parse_connection_tracking_empty() {
-        let result = parse_connection_tracking(&[], "ipv4");
+        let (result, _) = parse_connection_tracking(&[], "ipv4", &[]);
         assert_eq!(result.len(), 0);
     }
 
@@ -274,7 +438,7 @@ mod tests {
         conn.insert("dst-address".to_string(), "8.8.8.8:53".to_string());
         conn.insert("protocol".to_string(), "udp".to_string());
 
-        let result = parse_connection_tracking(&[conn], "ipv4");
+        let (result, _) = parse_connection_tracking(&[conn], "ipv4", &[]);
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].src_address, "192.168.1.100");
@@ -293,7 +457,7 @@ mod tests {
         conn2.insert("src-address".to_string(), "192.168.1.100:12346".to_string());
         conn2.insert("protocol".to_string(), "tcp".to_string());
 
-        let result = parse_connection_tracking(&[conn1, conn2], "ipv4");
+        let (result, _) = parse_connection_tracking(&[conn1, conn2], "ipv4", &[]);
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].src_address, "192.168.1.100");
@@ -311,7 +475,7 @@ mod tests {
         udp_conn.insert("src-address".to_string(), "192.168.1.100:12346".to_string());
         udp_conn.insert("protocol".to_string(), "udp".to_string());
 
-        let result = parse_connection_tracking(&[tcp_conn, udp_conn], "ipv4");
+        let (result, _) = parse_connection_tracking(&[tcp_conn, udp_conn], "ipv4", &[]);
 
         assert_eq!(result.len(), 2);
         let tcp = result.iter().find(|r| r.protocol == "tcp").unwrap();
@@ -325,7 +489,7 @@ mod tests {
         let mut conn = HashMap::new();
         conn.insert("protocol".to_string(), "tcp".to_string());
 
-        let result = parse_connection_tracking(&[conn], "ipv4");
+        let (result, _) = parse_connection_tracking(&[conn], "ipv4", &[]);
 
         assert_eq!(result.len(), 0);
     }
@@ -335,7 +499,7 @@ mod tests {
         let mut conn = HashMap::new();
         conn.insert("src-address".to_string(), "192.168.1.100:12345".to_string());
 
-        let result = parse_connection_tracking(&[conn], "ipv4");
+        let (result, _) = parse_connection_tracking(&[conn], "ipv4", &[]);
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].src_address, "192.168.1.100");
@@ -350,11 +514,57 @@ mod tests {
         conn.insert("src-address".to_string(), "[::1]:12345".to_string());
         conn.insert("protocol".to_string(), "tcp".to_string());
 
-        let result = parse_connection_tracking(&[conn], "ipv6");
+        let (result, _) = parse_connection_tracking(&[conn], "ipv6", &[]);
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].src_address, "::1");
         assert_eq!(result[0].protocol, "tcp");
         assert_eq!(result[0].ip_version, "ipv6");
     }
+
+    #[test]
+    fn test_extract_src_ip_ipv6() {
+        // Bracketed with port
+        assert_eq!(extract_src_ip("[2001:DB8::1]:443"), "2001:db8::1");
+        assert_eq!(extract_src_ip("[::ffff:1.2.3.4]:80"), "::ffff:1.2.3.4");
+        // Bare, in non-canonical form
+        assert_eq!(extract_src_ip("2001:0DB8:0:0:0:0:0:1"), "2001:db8::1");
+        // Zone id
+        assert_eq!(extract_src_ip("[fe80::1%ether1]:546"), "fe80::1");
+        assert_eq!(extract_src_ip("FE80::1%bridge"), "fe80::1");
+    }
+
+    #[test]
+    fn test_extract_src_ip_ipv4() {
+        assert_eq!(extract_src_ip("192.168.1.100:12345"), "192.168.1.100");
+        assert_eq!(extract_src_ip("10.0.0.1"), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_parse_connection_tracking_prefix_allow_list() {
+        let conn = |src: &str| {
+            let mut conn = HashMap::new();
+            conn.insert("src-address".to_string(), src.to_string());
+            conn.insert("protocol".to_string(), "tcp".to_string());
+            conn
+        };
+        let sentences = [
+            conn("10.1.2.3:1000"),
+            conn("10.1.2.3:1001"),
+            conn("203.0.113.5:443"),
+            conn("198.51.100.7:443"),
+        ];
+        let prefixes = ["10.0.0.0/8".parse().unwrap()];
+
+        let (mut result, distinct_sources) =
+            parse_connection_tracking(&sentences, "ipv4", &prefixes);
+        result.sort_by(|a, b| a.src_address.cmp(&b.src_address));
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].src_address, "10.1.2.3");
+        assert_eq!(result[0].connection_count, 2);
+        assert_eq!(result[1].src_address, "other");
+        assert_eq!(result[1].connection_count, 2);
+        assert_eq!(distinct_sources, 3);
+    }
 }
@@ -3,8 +3,7 @@
 
 //! RouterOS wire protocol helpers
 
-use tokio::io::AsyncReadExt;
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 // RouterOS protocol length encoding - intentional truncation is part of the wire format
 #[allow(clippy::cast_possible_truncation)]
@@ -38,7 +37,7 @@ pub fn encode_length(len: usize) -> Vec<u8> {
 }
 
 pub(super) async fn read_length(
-    stream: &mut TcpStream,
+    stream: &mut (impl AsyncRead + Unpin),
 ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     let first = stream.read_u8().await?;
     let len = if first & 0x80 == 0 {
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Connection tracking table usage (`/ip/firewall/connection/tracking/print`)
+//!
+//! Once the table holds `max-entries` connections, new connections are
+//! dropped, so the fill level is worth alerting on well before that.
+
+use std::collections::HashMap;
+
+/// Current size and limit of the connection tracking table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConntrackTableStats {
+    /// Connections currently tracked
+    pub entries: u64,
+    /// Table size limit, `None` when the router doesn't report it
+    pub max_entries: Option<u64>,
+}
+
+/// Parse `/ip/firewall/connection/tracking/print` response
+pub(super) fn parse_conntrack_table(
+    sentences: &[HashMap<String, String>],
+) -> Option<ConntrackTableStats> {
+    let s = sentences.iter().find(|s| s.contains_key("total-entries"))?;
+    Some(ConntrackTableStats {
+        entries: s.get("total-entries")?.parse().ok()?,
+        max_entries: s.get("max-entries").and_then(|v| v.parse().ok()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_conntrack_table() {
+        let sentences = vec![sentence(&[
+            ("enabled", "auto"),
+            ("total-entries", "1532"),
+            ("max-entries", "1048576"),
+        ])];
+
+        assert_eq!(
+            parse_conntrack_table(&sentences),
+            Some(ConntrackTableStats {
+                entries: 1532,
+                max_entries: Some(1_048_576),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_conntrack_table_without_counters() {
+        assert!(parse_conntrack_table(&[sentence(&[("enabled", "no")])]).is_none());
+        let without_max = vec![sentence(&[("total-entries", "7")])];
+        assert_eq!(
+            parse_conntrack_table(&without_max).unwrap().max_entries,
+            None
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! RouterOS v7 containers (`/container`)
+
+use std::collections::HashMap;
+
+/// State of a container
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerStats {
+    /// Container name, or its image tag on RouterOS versions without names
+    pub name: String,
+    /// Image tag, e.g. `pihole/pihole:latest`
+    pub tag: String,
+    /// Lowercase status reported by RouterOS (`running`, `stopped`, ...)
+    pub status: String,
+}
+
+/// Parse `/container/print` response
+pub(super) fn parse_containers(sentences: &[HashMap<String, String>]) -> Vec<ContainerStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let tag = s.get("tag").cloned().unwrap_or_default();
+            let name = s
+                .get("name")
+                .filter(|name| !name.is_empty())
+                .cloned()
+                .or_else(|| Some(tag.clone()).filter(|tag| !tag.is_empty()))?;
+            Some(ContainerStats {
+                name,
+                tag,
+                status: s
+                    .get("status")
+                    .map_or_else(|| "unknown".to_string(), |v| v.to_ascii_lowercase()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_containers() {
+        let sentences = vec![
+            sentence(&[
+                (".id", "*1"),
+                ("name", "pihole"),
+                ("tag", "pihole/pihole:latest"),
+                ("status", "running"),
+            ]),
+            sentence(&[
+                (".id", "*2"),
+                ("tag", "library/alpine:3.20"),
+                ("status", "stopped"),
+            ]),
+            sentence(&[(".id", "*3"), ("status", "extracting")]),
+        ];
+
+        let containers = parse_containers(&sentences);
+
+        assert_eq!(
+            containers,
+            vec![
+                ContainerStats {
+                    name: "pihole".to_string(),
+                    tag: "pihole/pihole:latest".to_string(),
+                    status: "running".to_string(),
+                },
+                ContainerStats {
+                    name: "library/alpine:3.20".to_string(),
+                    tag: "library/alpine:3.20".to_string(),
+                    status: "stopped".to_string(),
+                },
+            ]
+        );
+    }
+}
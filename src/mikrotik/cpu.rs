@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Per-core CPU load (`/system/resource/cpu`)
+
+use std::collections::HashMap;
+
+/// Load of a single CPU core
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuCoreStats {
+    /// Core index, e.g. `0` for `cpu0`
+    pub core: String,
+    /// Load percentage
+    pub load: u64,
+}
+
+/// Parse `/system/resource/cpu/print` response
+pub(super) fn parse_cpu_cores(sentences: &[HashMap<String, String>]) -> Vec<CpuCoreStats> {
+    sentences
+        .iter()
+        .enumerate()
+        .filter_map(|(index, s)| {
+            let load = s.get("load")?.parse().ok()?;
+            let core = s
+                .get("cpu")
+                .map(|cpu| cpu.strip_prefix("cpu").unwrap_or(cpu).to_string())
+                .unwrap_or_else(|| index.to_string());
+            Some(CpuCoreStats { core, load })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_cpu_cores() {
+        let sentences = vec![
+            sentence(&[("cpu", "cpu0"), ("load", "12"), ("irq", "3"), ("disk", "0")]),
+            sentence(&[("cpu", "cpu1"), ("load", "87")]),
+            sentence(&[("load", "5")]),
+            sentence(&[("cpu", "cpu3")]),
+        ];
+
+        let cores = parse_cpu_cores(&sentences);
+
+        assert_eq!(
+            cores,
+            vec![
+                CpuCoreStats {
+                    core: "0".to_string(),
+                    load: 12,
+                },
+                CpuCoreStats {
+                    core: "1".to_string(),
+                    load: 87,
+                },
+                CpuCoreStats {
+                    core: "2".to_string(),
+                    load: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_cpu_cores_empty() {
+        assert!(parse_cpu_cores(&[]).is_empty());
+    }
+}
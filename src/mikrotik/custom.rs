@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Values of user-defined custom collectors
+
+use std::collections::HashMap;
+
+use crate::config::{CustomCollector, label_name};
+
+/// One entry of a custom collector's print command
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomMetric {
+    /// Name of the custom collector that produced the entry
+    pub collector: String,
+    /// Label names and values, in the collector's label order
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+/// Parse the print response of `collector`
+///
+/// Missing label properties become empty labels; entries whose value isn't a
+/// number or a boolean are skipped.
+pub(super) fn parse_custom(
+    collector: &CustomCollector,
+    sentences: &[HashMap<String, String>],
+) -> Vec<CustomMetric> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let value = parse_value(s.get(&collector.value)?)?;
+            let labels = collector
+                .labels
+                .iter()
+                .map(|property| {
+                    (
+                        label_name(property),
+                        s.get(property).cloned().unwrap_or_default(),
+                    )
+                })
+                .collect();
+            Some(CustomMetric {
+                collector: collector.name.clone(),
+                labels,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Numeric value of a property; RouterOS booleans become 1 and 0
+fn parse_value(value: &str) -> Option<f64> {
+    match value.trim() {
+        "true" | "yes" => Some(1.0),
+        "false" | "no" => Some(0.0),
+        value => value.parse().ok().filter(|v: &f64| v.is_finite()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let collector = CustomCollector {
+            name: "graphing".to_string(),
+            command: "/tool/graphing/interface/print".to_string(),
+            value: "store-on-disk".to_string(),
+            labels: vec!["interface".to_string(), "allow-address".to_string()],
+        };
+        let sentences = vec![
+            sentence(&[
+                ("interface", "ether1"),
+                ("allow-address", "0.0.0.0/0"),
+                ("store-on-disk", "true"),
+            ]),
+            sentence(&[("interface", "ether2"), ("store-on-disk", "12.5")]),
+            sentence(&[("interface", "ether3"), ("store-on-disk", "maybe")]),
+            sentence(&[("interface", "ether4")]),
+        ];
+
+        let metrics = parse_custom(&collector, &sentences);
+
+        assert_eq!(
+            metrics,
+            vec![
+                CustomMetric {
+                    collector: "graphing".to_string(),
+                    labels: vec![
+                        ("interface".to_string(), "ether1".to_string()),
+                        ("allow_address".to_string(), "0.0.0.0/0".to_string()),
+                    ],
+                    value: 1.0,
+                },
+                CustomMetric {
+                    collector: "graphing".to_string(),
+                    labels: vec![
+                        ("interface".to_string(), "ether2".to_string()),
+                        ("allow_address".to_string(), String::new()),
+                    ],
+                    value: 12.5,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! DHCP server leases (`/ip/dhcp-server/lease`)
+
+use std::collections::HashMap;
+
+/// A DHCP server lease
+#[derive(Debug, Clone, PartialEq)]
+pub struct DhcpLeaseStats {
+    /// DHCP server the lease belongs to (`all` for static leases on every server)
+    pub server: String,
+    pub mac_address: Option<String>,
+    /// Lease status, e.g. `bound`, `waiting` or `offered`
+    pub status: String,
+}
+
+impl DhcpLeaseStats {
+    /// Whether the lease is currently granted to a client
+    #[must_use]
+    pub fn is_bound(&self) -> bool {
+        self.status == "bound"
+    }
+}
+
+/// Parse `/ip/dhcp-server/lease/print` response
+pub(super) fn parse_dhcp_leases(sentences: &[HashMap<String, String>]) -> Vec<DhcpLeaseStats> {
+    sentences
+        .iter()
+        .map(|s| DhcpLeaseStats {
+            server: s
+                .get("server")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            mac_address: s.get("mac-address").cloned(),
+            status: s
+                .get("status")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_dhcp_leases() {
+        let sentences = vec![
+            sentence(&[
+                ("address", "192.168.88.10"),
+                ("mac-address", "AA:BB:CC:DD:EE:01"),
+                ("server", "lan"),
+                ("status", "bound"),
+            ]),
+            sentence(&[("address", "192.168.88.20"), ("server", "lan")]),
+        ];
+
+        let leases = parse_dhcp_leases(&sentences);
+
+        assert_eq!(
+            leases,
+            vec![
+                DhcpLeaseStats {
+                    server: "lan".to_string(),
+                    mac_address: Some("AA:BB:CC:DD:EE:01".to_string()),
+                    status: "bound".to_string(),
+                },
+                DhcpLeaseStats {
+                    server: "lan".to_string(),
+                    mac_address: None,
+                    status: "unknown".to_string(),
+                },
+            ]
+        );
+        assert!(leases[0].is_bound());
+        assert!(!leases[1].is_bound());
+    }
+}
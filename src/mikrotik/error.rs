@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Classification of failed scrapes
+//!
+//! Errors travel as `Box<dyn Error>`; the few cases that need to be told
+//! apart reliably (login failures, an open circuit breaker) get their own
+//! types, the rest is recognized from I/O error kinds.
+
+use std::error::Error;
+use std::fmt;
+
+use super::connection::is_connection_closed;
+
+/// The router rejected the login
+#[derive(Debug)]
+pub(crate) struct LoginError(pub(crate) Box<dyn Error + Send + Sync>);
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for LoginError {}
+
+/// The pool skipped the connection attempt because of earlier errors
+#[derive(Debug)]
+pub(crate) struct BackoffError {
+    pub(crate) addr: String,
+    pub(crate) consecutive_errors: u32,
+}
+
+impl fmt::Display for BackoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Connection to {} temporarily disabled due to {} consecutive errors",
+            self.addr, self.consecutive_errors
+        )
+    }
+}
+
+impl Error for BackoffError {}
+
+/// Why a scrape failed, as reported by `mikrotik_scrape_error_reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrapeErrorReason {
+    /// The router actively refused the TCP connection
+    ConnectRefused,
+    /// No route to the router, e.g. because a VPN tunnel is down
+    Unreachable,
+    /// The router dropped an established connection, possibly mid-response
+    ConnectionReset,
+    /// Connecting, reading or the whole collection timed out
+    Timeout,
+    /// The router rejected the credentials
+    Auth,
+    /// The attempt was skipped by backoff or an open circuit breaker
+    Backoff,
+    /// The router this one depends on failed its last scrape
+    DependencyDown,
+    Other,
+}
+
+impl ScrapeErrorReason {
+    /// Classifies a scrape error
+    #[must_use]
+    pub fn classify(err: &(dyn Error + Send + Sync + 'static)) -> Self {
+        if let Some(login) = err.downcast_ref::<LoginError>() {
+            // A login that timed out or lost the connection is not an auth problem
+            return match Self::classify(login.0.as_ref()) {
+                Self::Other => Self::Auth,
+                reason => reason,
+            };
+        }
+        if err.is::<BackoffError>() {
+            return Self::Backoff;
+        }
+        if err.is::<tokio::time::error::Elapsed>() {
+            return Self::Timeout;
+        }
+        if is_connection_closed(err) {
+            return Self::ConnectionReset;
+        }
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::ConnectionRefused => return Self::ConnectRefused,
+                std::io::ErrorKind::HostUnreachable | std::io::ErrorKind::NetworkUnreachable => {
+                    return Self::Unreachable;
+                }
+                std::io::ErrorKind::TimedOut => return Self::Timeout,
+                _ => {}
+            }
+        }
+        // Read and collection timeouts are reported as plain messages
+        if err.to_string().to_ascii_lowercase().contains("timeout") {
+            return Self::Timeout;
+        }
+        Self::Other
+    }
+
+    /// Value of the `reason` label
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ConnectRefused => "connect_refused",
+            Self::Unreachable => "unreachable",
+            Self::ConnectionReset => "connection_reset",
+            Self::Timeout => "timeout",
+            Self::Auth => "auth",
+            Self::Backoff => "backoff",
+            Self::DependencyDown => "dependency_down",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(err: Box<dyn Error + Send + Sync>) -> ScrapeErrorReason {
+        ScrapeErrorReason::classify(err.as_ref())
+    }
+
+    #[test]
+    fn test_classify_io_errors() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        let unreachable = std::io::Error::from(std::io::ErrorKind::HostUnreachable);
+        assert_eq!(classify(refused.into()), ScrapeErrorReason::ConnectRefused);
+        assert_eq!(classify(unreachable.into()), ScrapeErrorReason::Unreachable);
+    }
+
+    #[test]
+    fn test_classify_connection_drop_is_not_auth() {
+        let eof = std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(classify(eof.into()), ScrapeErrorReason::ConnectionReset);
+
+        // The router hung up while the login reply was being read
+        let eof = std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+        let dropped_login = LoginError(eof.into());
+        assert_eq!(
+            classify(Box::new(dropped_login)),
+            ScrapeErrorReason::ConnectionReset
+        );
+    }
+
+    #[test]
+    fn test_classify_typed_and_message_errors() {
+        let login = LoginError("Login failed: invalid user name or password".into());
+        assert_eq!(classify(Box::new(login)), ScrapeErrorReason::Auth);
+        let slow_login = LoginError("Read timeout: RouterOS did not respond".into());
+        assert_eq!(classify(Box::new(slow_login)), ScrapeErrorReason::Timeout);
+
+        let backoff = BackoffError {
+            addr: "10.0.0.1:8728".to_string(),
+            consecutive_errors: 5,
+        };
+        assert_eq!(classify(Box::new(backoff)), ScrapeErrorReason::Backoff);
+
+        let read_timeout = "Read timeout: RouterOS did not respond within 30 seconds";
+        assert_eq!(classify(read_timeout.into()), ScrapeErrorReason::Timeout);
+        assert_eq!(
+            classify("RouterOS trap: failure".into()),
+            ScrapeErrorReason::Other
+        );
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Firewall filter rule counters (`/ip/firewall/filter`)
+
+use std::collections::HashMap;
+
+/// Hit counters of a firewall filter rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirewallRuleStats {
+    pub chain: String,
+    /// Rule comment, or the rule's position in the filter list when it has none
+    pub comment: String,
+    /// RouterOS `.id`, only kept when ids are exported as labels
+    pub id: Option<String>,
+    pub bytes: u64,
+    pub packets: u64,
+}
+
+/// Parse `/ip/firewall/filter/print stats detail` response
+pub(super) fn parse_firewall_rules(
+    sentences: &[HashMap<String, String>],
+    include_ids: bool,
+) -> Vec<FirewallRuleStats> {
+    sentences
+        .iter()
+        .enumerate()
+        .filter_map(|(index, s)| {
+            Some(FirewallRuleStats {
+                chain: s.get("chain")?.clone(),
+                comment: s
+                    .get("comment")
+                    .cloned()
+                    .unwrap_or_else(|| index.to_string()),
+                id: s.get(".id").filter(|_| include_ids).cloned(),
+                bytes: s.get("bytes").and_then(|v| v.parse().ok()).unwrap_or(0),
+                packets: s.get("packets").and_then(|v| v.parse().ok()).unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_firewall_rules() {
+        let sentences = vec![
+            sentence(&[
+                (".id", "*1"),
+                ("chain", "input"),
+                ("action", "drop"),
+                ("comment", "drop invalid"),
+                ("bytes", "1024"),
+                ("packets", "8"),
+            ]),
+            sentence(&[(".id", "*2"), ("chain", "forward"), ("bytes", "64")]),
+            sentence(&[(".id", "*3"), ("comment", "no chain")]),
+        ];
+
+        let rules = parse_firewall_rules(&sentences, false);
+
+        assert_eq!(
+            rules,
+            vec![
+                FirewallRuleStats {
+                    chain: "input".to_string(),
+                    comment: "drop invalid".to_string(),
+                    id: None,
+                    bytes: 1024,
+                    packets: 8,
+                },
+                FirewallRuleStats {
+                    chain: "forward".to_string(),
+                    comment: "1".to_string(),
+                    id: None,
+                    bytes: 64,
+                    packets: 0,
+                },
+            ]
+        );
+
+        let rules = parse_firewall_rules(&sentences, true);
+        assert_eq!(rules[0].id.as_deref(), Some("*1"));
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Board health sensors (`/system/health`)
+//!
+//! RouterOS v7 returns one `name`/`value`/`type` row per sensor, while v6
+//! returns a single sentence with one attribute per sensor.
+
+use std::collections::HashMap;
+
+/// A single sensor reading
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSensor {
+    /// Sensor name, e.g. `cpu-temperature` or `fan1-speed`
+    pub name: String,
+    pub value: f64,
+}
+
+/// Temperature, voltage and fan readings of a router
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthStats {
+    /// Temperatures in degrees Celsius
+    pub temperatures: Vec<HealthSensor>,
+    /// Voltages in volts
+    pub voltages: Vec<HealthSensor>,
+    /// Fan speeds in revolutions per minute
+    pub fans: Vec<HealthSensor>,
+}
+
+/// Parse `/system/health/print` response in either shape
+///
+/// Sensors that aren't numeric, like `psu1-state`, and sensors of other
+/// kinds, like power or current, are skipped.
+pub(super) fn parse_health(sentences: &[HashMap<String, String>]) -> HealthStats {
+    let mut health = HealthStats::default();
+    for s in sentences {
+        if let (Some(name), Some(value)) = (s.get("name"), s.get("value")) {
+            health.push(name, s.get("type").map(String::as_str), value);
+        } else {
+            for (name, value) in s {
+                health.push(name, None, value);
+            }
+        }
+    }
+    for sensors in [
+        &mut health.temperatures,
+        &mut health.voltages,
+        &mut health.fans,
+    ] {
+        sensors.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    health
+}
+
+impl HealthStats {
+    /// Files a reading by its unit, or by its name when the unit is unknown
+    fn push(&mut self, name: &str, unit: Option<&str>, value: &str) {
+        let Ok(value) = value.parse::<f64>() else {
+            return;
+        };
+        let sensors = match unit {
+            Some("C") => &mut self.temperatures,
+            Some("V") => &mut self.voltages,
+            Some("RPM") => &mut self.fans,
+            Some(_) => return,
+            None if name.contains("temperature") => &mut self.temperatures,
+            None if name.contains("voltage") => &mut self.voltages,
+            None if name.starts_with("fan") && name.ends_with("speed") => &mut self.fans,
+            None => return,
+        };
+        sensors.push(HealthSensor {
+            name: name.to_string(),
+            value,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    fn sensor(name: &str, value: f64) -> HealthSensor {
+        HealthSensor {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_parse_health_v7_rows() {
+        let sentences = vec![
+            sentence(&[("name", "voltage"), ("value", "24.3"), ("type", "V")]),
+            sentence(&[("name", "cpu-temperature"), ("value", "52"), ("type", "C")]),
+            sentence(&[("name", "fan1-speed"), ("value", "3120"), ("type", "RPM")]),
+            sentence(&[
+                ("name", "power-consumption"),
+                ("value", "18.2"),
+                ("type", "W"),
+            ]),
+            sentence(&[("name", "psu1-state"), ("value", "ok"), ("type", "")]),
+        ];
+
+        let health = parse_health(&sentences);
+
+        assert_eq!(health.temperatures, [sensor("cpu-temperature", 52.0)]);
+        assert_eq!(health.voltages, [sensor("voltage", 24.3)]);
+        assert_eq!(health.fans, [sensor("fan1-speed", 3120.0)]);
+    }
+
+    #[test]
+    fn test_parse_health_v6_flat_sentence() {
+        let sentences = vec![sentence(&[
+            ("voltage", "12.1"),
+            ("temperature", "41"),
+            ("cpu-temperature", "47"),
+            ("fan1-speed", "2800"),
+            ("fan-mode", "auto"),
+            ("board-temperature1", "n/a"),
+        ])];
+
+        let health = parse_health(&sentences);
+
+        assert_eq!(
+            health.temperatures,
+            [sensor("cpu-temperature", 47.0), sensor("temperature", 41.0)]
+        );
+        assert_eq!(health.voltages, [sensor("voltage", 12.1)]);
+        assert_eq!(health.fans, [sensor("fan1-speed", 2800.0)]);
+        assert_eq!(parse_health(&[]), HealthStats::default());
+    }
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Interface queue (`/queue/interface`) statistics for MikroTik routers
+//!
+//! Every interface has a default transmit queue, so these apply even without
+//! any simple or tree queues configured. Counters are only reported when the
+//! menu is printed with `stats`.
+
+use std::collections::HashMap;
+
+/// Drops and current depth of an interface's transmit queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceQueueStats {
+    pub interface: String,
+    /// Packets dropped by the queue since the counters were reset
+    pub drops: u64,
+    /// Packets currently waiting in the queue
+    pub queued_packets: u64,
+}
+
+/// Parse `/queue/interface/print stats` response
+pub(super) fn parse_interface_queues(
+    sentences: &[HashMap<String, String>],
+) -> Vec<InterfaceQueueStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let interface = s.get("interface").or_else(|| s.get("name"))?;
+            Some(InterfaceQueueStats {
+                interface: interface.clone(),
+                drops: s.get("dropped").and_then(|v| v.parse().ok()).unwrap_or(0),
+                queued_packets: s
+                    .get("queued-packets")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_interface_queues() {
+        let sentences = vec![
+            sentence(&[
+                ("interface", "ether1"),
+                ("queue", "only-hardware-queue"),
+                ("dropped", "1234"),
+                ("queued-packets", "7"),
+            ]),
+            sentence(&[("interface", "ether2"), ("queue", "ethernet-default")]),
+            sentence(&[("dropped", "5")]),
+        ];
+
+        let queues = parse_interface_queues(&sentences);
+
+        assert_eq!(
+            queues,
+            vec![
+                InterfaceQueueStats {
+                    interface: "ether1".to_string(),
+                    drops: 1234,
+                    queued_packets: 7,
+                },
+                InterfaceQueueStats {
+                    interface: "ether2".to_string(),
+                    drops: 0,
+                    queued_packets: 0,
+                },
+            ]
+        );
+    }
+}
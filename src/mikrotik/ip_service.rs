@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Management service (`/ip/service`) collection for MikroTik routers
+//!
+//! Reports which management services (api, ssh, telnet, winbox, www, ...)
+//! are enabled and on which port, for security auditing.
+
+use std::collections::HashMap;
+
+/// State of a RouterOS management service
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpServiceStats {
+    pub name: String,
+    pub port: u16,
+    pub enabled: bool,
+}
+
+/// Parse `/ip/service/print` response
+pub(super) fn parse_ip_services(sentences: &[HashMap<String, String>]) -> Vec<IpServiceStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let name = s.get("name")?;
+            Some(IpServiceStats {
+                name: name.clone(),
+                port: s.get("port").and_then(|v| v.parse().ok()).unwrap_or(0),
+                enabled: s.get("disabled").is_none_or(|v| v != "true"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_ip_services() {
+        let sentences = vec![
+            sentence(&[("name", "telnet"), ("port", "23"), ("disabled", "false")]),
+            sentence(&[("name", "www"), ("port", "80"), ("disabled", "true")]),
+            sentence(&[("port", "8728")]),
+        ];
+
+        let services = parse_ip_services(&sentences);
+
+        assert_eq!(services.len(), 2);
+        assert_eq!(
+            services[0],
+            IpServiceStats {
+                name: "telnet".to_string(),
+                port: 23,
+                enabled: true,
+            }
+        );
+        assert!(!services[1].enabled);
+        assert_eq!(services[1].port, 80);
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! IPsec peers (`/ip/ipsec/active-peers`)
+
+use std::collections::HashMap;
+
+/// Properties read from `/ip/ipsec/active-peers/print`
+pub(super) const ACTIVE_PEER_PROPERTIES: [&str; 5] =
+    ["remote-address", "state", "uptime", "rx-bytes", "tx-bytes"];
+
+/// An active IPsec peer
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpsecPeerStats {
+    pub remote_address: String,
+    /// Lowercase peer state, e.g. `established` or `message-1-sent`
+    pub state: String,
+    /// Time since the peer was established, as reported by RouterOS
+    pub uptime: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+impl IpsecPeerStats {
+    /// Whether phase 1 with the peer is established
+    #[must_use]
+    pub fn established(&self) -> bool {
+        self.state == "established"
+    }
+}
+
+/// Parse `/ip/ipsec/active-peers/print` response
+pub(super) fn parse_ipsec_peers(sentences: &[HashMap<String, String>]) -> Vec<IpsecPeerStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            Some(IpsecPeerStats {
+                remote_address: s.get("remote-address")?.clone(),
+                state: s
+                    .get("state")
+                    .map(|v| v.to_ascii_lowercase())
+                    .unwrap_or_default(),
+                uptime: s.get("uptime").cloned().unwrap_or_default(),
+                rx_bytes: s.get("rx-bytes").and_then(|v| v.parse().ok()).unwrap_or(0),
+                tx_bytes: s.get("tx-bytes").and_then(|v| v.parse().ok()).unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_ipsec_peers() {
+        let sentences = vec![
+            sentence(&[
+                ("remote-address", "203.0.113.10"),
+                ("state", "established"),
+                ("uptime", "2h3m"),
+                ("rx-bytes", "123456"),
+                ("tx-bytes", "654321"),
+            ]),
+            sentence(&[
+                ("remote-address", "198.51.100.7"),
+                ("state", "message-1-sent"),
+            ]),
+            sentence(&[("state", "established")]),
+        ];
+
+        let peers = parse_ipsec_peers(&sentences);
+
+        assert_eq!(
+            peers,
+            vec![
+                IpsecPeerStats {
+                    remote_address: "203.0.113.10".to_string(),
+                    state: "established".to_string(),
+                    uptime: "2h3m".to_string(),
+                    rx_bytes: 123_456,
+                    tx_bytes: 654_321,
+                },
+                IpsecPeerStats {
+                    remote_address: "198.51.100.7".to_string(),
+                    state: "message-1-sent".to_string(),
+                    uptime: String::new(),
+                    rx_bytes: 0,
+                    tx_bytes: 0,
+                },
+            ]
+        );
+        assert!(peers[0].established());
+        assert!(!peers[1].established());
+    }
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! RouterOS license level (`/system/license/print`)
+//!
+//! RouterBOARDs report a numeric level from 0 to 6 (`nlevel` on v7, `level`
+//! on older versions). CHR reports a named level (`free`, `p1`, `p10` or
+//! `p-unlimited`) and, for paid licenses, a `deadline-at` by which the license
+//! must be renewed before it falls back to `free`.
+
+use std::collections::HashMap;
+
+use super::clock::{parse_date, parse_time};
+
+/// License level of a router
+#[derive(Debug, Clone, PartialEq)]
+pub struct LicenseStats {
+    /// Level as reported by the router, e.g. `6` or `p-unlimited`
+    pub level: String,
+    /// Numeric level: 0-6 on RouterBOARDs; 0 (free), 1 (p1), 2 (p10) or
+    /// 3 (p-unlimited) on CHR. `None` for levels not known to the exporter
+    pub level_number: Option<i64>,
+    /// CHR renewal deadline as Unix time, taken as UTC
+    pub deadline: Option<i64>,
+}
+
+/// Parse `/system/license/print` response
+pub(super) fn parse_license(sentences: &[HashMap<String, String>]) -> Option<LicenseStats> {
+    let s = sentences
+        .iter()
+        .find(|s| s.contains_key("nlevel") || s.contains_key("level"))?;
+    let level = s
+        .get("nlevel")
+        .or_else(|| s.get("level"))?
+        .trim()
+        .to_string();
+    let level_number = level.parse().ok().or_else(|| chr_level_number(&level));
+
+    Some(LicenseStats {
+        deadline: s.get("deadline-at").and_then(|v| parse_deadline(v)),
+        level,
+        level_number,
+    })
+}
+
+/// Position of a CHR level in the free < p1 < p10 < p-unlimited order
+fn chr_level_number(level: &str) -> Option<i64> {
+    match level {
+        "free" => Some(0),
+        "p1" => Some(1),
+        "p10" => Some(2),
+        "p-unlimited" => Some(3),
+        _ => None,
+    }
+}
+
+/// Unix time from `2025-01-15 10:20:30` (v7) or `jan/15/2025 10:20:30` (v6)
+fn parse_deadline(value: &str) -> Option<i64> {
+    let (date, time) = value.trim().split_once(' ').unwrap_or((value, "00:00:00"));
+    Some(parse_date(date)? * 86_400 + parse_time(time)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_license_routerboard() {
+        let sentences = vec![sentence(&[("software-id", "ABCD-1234"), ("nlevel", "4")])];
+
+        let license = parse_license(&sentences).unwrap();
+
+        assert_eq!(license.level, "4");
+        assert_eq!(license.level_number, Some(4));
+        assert!(license.deadline.is_none());
+    }
+
+    #[test]
+    fn test_parse_license_chr_levels() {
+        let paid = vec![sentence(&[
+            ("system-id", "abcdef"),
+            ("level", "p-unlimited"),
+            ("deadline-at", "2025-01-15 10:20:30"),
+        ])];
+        let free = vec![sentence(&[("system-id", "abcdef"), ("level", "free")])];
+
+        let paid = parse_license(&paid).unwrap();
+        let free = parse_license(&free).unwrap();
+
+        assert_eq!(paid.level_number, Some(3));
+        assert_eq!(paid.deadline, Some(1_736_936_430));
+        assert_eq!(free.level_number, Some(0));
+    }
+
+    #[test]
+    fn test_parse_license_v6_deadline_and_unknown_level() {
+        let sentences = vec![sentence(&[
+            ("level", "trial"),
+            ("deadline-at", "jan/15/2025 10:20:30"),
+        ])];
+
+        let license = parse_license(&sentences).unwrap();
+
+        assert!(license.level_number.is_none());
+        assert_eq!(license.deadline, Some(1_736_936_430));
+        assert!(parse_license(&[sentence(&[("software-id", "x")])]).is_none());
+    }
+}
@@ -6,21 +6,128 @@
 //! Implements connection to MikroTik routers via RouterOS API,
 //! authentication, and collection of system/interface metrics.
 
+mod bgp;
+mod bonding;
+mod capsman;
 mod client;
+mod clock;
 mod connection;
+mod conntrack_table;
+mod container;
+mod cpu;
+mod custom;
+mod dhcp;
+mod error;
+mod firewall;
+mod health;
+mod interface_queue;
+mod ip_service;
+mod ipsec;
+mod license;
+mod ping;
 mod pool;
+mod ppp;
+mod queue;
+mod route;
+mod sfp;
+mod tunnel;
 pub(crate) mod types;
+mod vrrp;
+mod watchdog;
+mod wifi;
 pub(crate) mod wireguard;
+mod wireless;
 
 /// Client for MikroTik RouterOS API
 pub(crate) use client::MikroTikClient;
 
+/// Classification of failed scrapes
+pub use error::ScrapeErrorReason;
+
 /// Connection pool for routers
-pub use pool::ConnectionPool;
+pub use pool::{CircuitState, ConnectionPool};
+
+/// API login method accepted by a router
+pub use connection::LoginMethod;
 
 /// Types for router metrics and statistics
 pub use types::{ConnectionTrackingStats, InterfaceStats, RouterMetrics, SystemResource};
 
+/// BGP session state
+pub use bgp::BgpSessionStats;
+
+/// Bonding interface and slave state
+pub use bonding::{BondSlaveStats, BondStats};
+
+/// CAPs managed by a CAPsMAN controller
+pub use capsman::{CapsmanClientStats, ManagedDeviceStats};
+
+/// Router clock state
+pub use clock::ClockStats;
+
+/// Per-core CPU load
+pub use cpu::CpuCoreStats;
+
+/// Connection tracking table size and limit
+pub use conntrack_table::ConntrackTableStats;
+
+/// Firewall filter rule counters
+pub use firewall::FirewallRuleStats;
+
+/// RouterOS v7 container state
+pub use container::ContainerStats;
+
+/// Entries of user-defined custom collectors
+pub use custom::CustomMetric;
+
+/// Reboots RouterOS didn't initiate
+pub use watchdog::UnexpectedReboot;
+
+/// Board temperature, voltage and fan sensors
+pub use health::{HealthSensor, HealthStats};
+
+/// Interface transmit queue drops and depth
+pub use interface_queue::InterfaceQueueStats;
+
+/// Management service state
+pub use ip_service::IpServiceStats;
+
+/// Active IPsec peers
+pub use ipsec::IpsecPeerStats;
+
+/// RouterOS license level
+pub use license::LicenseStats;
+
+/// Reachability of ping targets
+pub use ping::PingStats;
+
+/// Active PPP sessions
+pub use ppp::PppSessionStats;
+
+/// Simple queue limits
+pub use queue::QueueStats;
+
+/// Default route state per gateway
+pub use route::DefaultRouteStats;
+
+/// DHCP server leases
+pub use dhcp::DhcpLeaseStats;
+
+/// SFP module diagnostics
+pub use sfp::SfpStats;
+
+/// Overlay tunnel state
+pub use tunnel::TunnelStats;
+
+/// VRRP interface state
+pub use vrrp::VrrpStats;
+
+/// Wireless channel utilization
+pub use wifi::WifiChannelStats;
+
+/// Stations registered to legacy wireless interfaces
+pub use wireless::WirelessStationStats;
+
 /// Types for WireGuard metrics and statistics
 pub use wireguard::{WireGuardInterfaceStats, WireGuardPeerStats};
 
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Reachability of configured targets via RouterOS `/ping`
+//!
+//! `/ping` streams one `!re` per echo request until its `count` is reached.
+//! Replies carry a `time` field while lost requests report a `status` such as
+//! `timeout` instead, so the round-trip time is averaged over replies only.
+
+use std::collections::HashMap;
+
+/// Echo requests sent to each target per scrape
+pub(super) const PING_COUNT: u32 = 3;
+
+/// Round-trip time and loss towards a ping target
+#[derive(Debug, Clone, PartialEq)]
+pub struct PingStats {
+    pub target: String,
+    /// Average round-trip time in milliseconds, `None` when nothing replied
+    pub rtt_ms: Option<f64>,
+    /// Share of echo requests without a reply (0..1)
+    pub loss_ratio: f64,
+}
+
+/// Parse the `!re` stream of a `/ping` sent `count` times to `target`
+pub(super) fn parse_ping(
+    target: &str,
+    sentences: &[HashMap<String, String>],
+    count: u32,
+) -> PingStats {
+    let rtts: Vec<f64> = sentences
+        .iter()
+        .filter_map(|s| s.get("time").and_then(|t| parse_rtt_ms(t)))
+        .collect();
+    let received = u32::try_from(rtts.len()).unwrap_or(u32::MAX).min(count);
+    let rtt_ms = (!rtts.is_empty()).then(|| rtts.iter().sum::<f64>() / rtts.len() as f64);
+
+    PingStats {
+        target: target.to_string(),
+        rtt_ms,
+        loss_ratio: if count == 0 {
+            0.0
+        } else {
+            f64::from(count - received) / f64::from(count)
+        },
+    }
+}
+
+/// Milliseconds from RouterOS durations such as `12ms`, `1ms234us` or `350us`
+fn parse_rtt_ms(value: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let factor = match &rest[..unit_len] {
+            "s" => 1000.0,
+            "ms" => 1.0,
+            "us" => 0.001,
+            _ => return None,
+        };
+        total += number * factor;
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rtt_ms_formats() {
+        assert_eq!(parse_rtt_ms("12ms"), Some(12.0));
+        assert_eq!(parse_rtt_ms("1ms500us"), Some(1.5));
+        assert_eq!(parse_rtt_ms("1s2ms"), Some(1002.0));
+        assert!(parse_rtt_ms("").is_none());
+        assert!(parse_rtt_ms("fast").is_none());
+    }
+
+    #[test]
+    fn test_parse_ping_averages_replies() {
+        let sentences = vec![
+            sentence(&[("seq", "0"), ("time", "10ms")]),
+            sentence(&[("seq", "1"), ("status", "timeout")]),
+            sentence(&[("seq", "2"), ("time", "20ms")]),
+        ];
+
+        let stats = parse_ping("192.0.2.1", &sentences, 3);
+
+        assert_eq!(stats.target, "192.0.2.1");
+        assert_eq!(stats.rtt_ms, Some(15.0));
+        assert!((stats.loss_ratio - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_ping_unreachable() {
+        let sentences = vec![sentence(&[("seq", "0"), ("status", "timeout")])];
+
+        let stats = parse_ping("192.0.2.1", &sentences, 3);
+
+        assert!(stats.rtt_ms.is_none());
+        assert!((stats.loss_ratio - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -9,7 +9,8 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::{Mutex, mpsc};
 
-use super::connection::RouterOsConnection;
+use super::connection::{LoginMethod, RouterOsConnection, Timeouts, Transport};
+use super::error::{BackoffError, LoginError};
 
 /// Connection pool configuration constants
 mod timeouts {
@@ -20,6 +21,10 @@ mod timeouts {
 
     /// Maximum backoff duration (5 minutes)
     pub const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+    /// Default limit for a router's whole scrape: the default connect
+    /// timeout plus the default read timeout
+    pub const COLLECTION_TIMEOUT: Duration = Duration::from_secs(35);
 }
 
 /// Backoff strategy configuration
@@ -29,11 +34,11 @@ mod backoff {
     /// Minimum consecutive errors before backoff applies
     pub const MIN_ERRORS_FOR_BACKOFF: u32 = 3;
 
-    /// Error threshold for long backoff period (1 hour)
-    pub const LONG_BACKOFF_ERROR_THRESHOLD: u32 = 10;
+    /// Consecutive errors after which the circuit breaker opens
+    pub const CIRCUIT_BREAKER_ERROR_THRESHOLD: u32 = 10;
 
-    /// Long backoff duration after many consecutive errors (1 hour)
-    pub const LONG_BACKOFF_DURATION: Duration = Duration::from_secs(3600);
+    /// Default time the circuit stays open before a probe is allowed (1 hour)
+    pub const DEFAULT_CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(3600);
 
     /// Maximum exponent for exponential backoff (2^8 = 256 seconds)
     pub const MAX_BACKOFF_EXPONENT: u32 = 8;
@@ -46,6 +51,9 @@ pub struct ConnectionPool {
     connection_states: Arc<Mutex<HashMap<String, ConnectionState>>>,
     active_connections: Arc<AtomicUsize>,
     max_idle_time: Duration,
+    circuit_open_duration: Duration,
+    timeouts: Timeouts,
+    collection_timeout: Duration,
     return_tx: mpsc::UnboundedSender<(String, RouterOsConnection)>,
 }
 
@@ -56,7 +64,15 @@ pub struct ConnectionPool {
 pub(crate) struct PooledConnectionGuard {
     connection: Option<RouterOsConnection>,
     pool: ConnectionPool,
+    /// Key of the router's connection state
     key: String,
+    /// Key the connection is returned to the pool under
+    connection_key: String,
+    /// Transport used to re-establish the connection
+    transport: Transport,
+    /// Set while the router has dropped the connection and no replacement
+    /// was established; a broken connection is never returned to the pool
+    broken: bool,
 }
 
 impl PooledConnectionGuard {
@@ -64,17 +80,65 @@ impl PooledConnectionGuard {
     pub(super) fn get_mut(&mut self) -> &mut RouterOsConnection {
         self.connection.as_mut().expect("Connection already taken")
     }
+
+    /// Counts a connection reset if the router dropped the connection in the
+    /// middle of a reply
+    pub(super) async fn record_dropped_reply(&mut self) {
+        if self
+            .connection
+            .as_mut()
+            .is_some_and(RouterOsConnection::take_dropped_reply)
+        {
+            self.pool
+                .connection_states
+                .lock()
+                .await
+                .entry(self.key.clone())
+                .or_insert_with(ConnectionState::new)
+                .record_reset();
+        }
+    }
+
+    /// Replace the underlying connection with a freshly authenticated one
+    ///
+    /// Used when the router closes the session between commands or in the
+    /// middle of a response; only the latter counts as a connection reset.
+    pub(super) async fn reconnect(
+        &mut self,
+        addr: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.record_dropped_reply().await;
+        self.broken = true;
+        let mut conn =
+            RouterOsConnection::connect(addr, self.transport, self.pool.timeouts).await?;
+        let method = conn.login(username, password).await?;
+        self.connection = Some(conn);
+        self.broken = false;
+        let mut states = self.pool.connection_states.lock().await;
+        states
+            .entry(self.key.clone())
+            .or_insert_with(ConnectionState::new)
+            .record_login(method);
+        Ok(())
+    }
 }
 
 impl Drop for PooledConnectionGuard {
     fn drop(&mut self) {
-        if let Some(conn) = self.connection.take() {
+        if let Some(conn) = self.connection.take().filter(|_| !self.broken) {
             // Send connection back to pool via channel (non-blocking)
             // If send fails, pool is shutting down - connection will be dropped
-            if self.pool.return_tx.send((self.key.clone(), conn)).is_err() {
+            if self
+                .pool
+                .return_tx
+                .send((self.connection_key.clone(), conn))
+                .is_err()
+            {
                 tracing::debug!(
                     "Failed to return connection (pool shutting down): {}",
-                    self.key
+                    self.connection_key
                 );
             }
         }
@@ -107,12 +171,45 @@ struct PooledConnection {
     last_used: tokio::time::Instant,
 }
 
+/// Circuit breaker state of a router connection
+///
+/// The circuit opens after many consecutive failures; while open no
+/// connection attempts are made. Once the open duration has elapsed the
+/// circuit is half-open and a single probe is let through: success closes
+/// the circuit, failure opens it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl CircuitState {
+    /// Numeric value exported as `mikrotik_circuit_breaker_state`
+    #[must_use]
+    pub fn as_gauge(self) -> i64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
 /// Tracks connection health and error state
 #[derive(Clone)]
 struct ConnectionState {
     consecutive_errors: u32,
     last_error_time: Option<tokio::time::Instant>,
     last_success_time: Option<tokio::time::Instant>,
+    login_method: Option<LoginMethod>,
+    /// Logins that used a different method than the previous login
+    login_method_changes: u64,
+    /// Times the router dropped a connection in the middle of a reply
+    connection_resets: u64,
+    /// When the half-open circuit let its single probe through, until the
+    /// probe succeeds or fails
+    probe_started: Option<tokio::time::Instant>,
 }
 
 impl ConnectionState {
@@ -121,17 +218,50 @@ impl ConnectionState {
             consecutive_errors: 0,
             last_error_time: None,
             last_success_time: None,
+            login_method: None,
+            login_method_changes: 0,
+            connection_resets: 0,
+            probe_started: None,
+        }
+    }
+
+    fn record_reset(&mut self) {
+        self.connection_resets += 1;
+    }
+
+    fn record_login(&mut self, method: LoginMethod) {
+        if self.login_method.is_some_and(|previous| previous != method) {
+            self.login_method_changes += 1;
         }
+        self.login_method = Some(method);
     }
 
     fn record_success(&mut self) {
         self.consecutive_errors = 0;
         self.last_success_time = Some(tokio::time::Instant::now());
+        self.probe_started = None;
     }
 
     fn record_error(&mut self) {
         self.consecutive_errors = self.consecutive_errors.saturating_add(1);
         self.last_error_time = Some(tokio::time::Instant::now());
+        self.probe_started = None;
+    }
+
+    /// Claims the single probe of a half-open circuit
+    ///
+    /// Returns false while another probe is in flight. A probe that hasn't
+    /// reported back within `lease` was abandoned, e.g. by a timed out
+    /// scrape, and no longer holds the claim.
+    fn try_begin_probe(&mut self, lease: Duration) -> bool {
+        if self
+            .probe_started
+            .is_some_and(|started| started.elapsed() < lease)
+        {
+            return false;
+        }
+        self.probe_started = Some(tokio::time::Instant::now());
+        true
     }
 
     fn backoff_delay(&self) -> Duration {
@@ -141,18 +271,28 @@ impl ConnectionState {
         Duration::from_secs(base_delay.min(max_secs))
     }
 
-    fn should_skip_attempt(&self) -> bool {
+    fn circuit_state(&self, open_duration: Duration) -> CircuitState {
+        if self.consecutive_errors < backoff::CIRCUIT_BREAKER_ERROR_THRESHOLD {
+            return CircuitState::Closed;
+        }
+        match self.last_error_time {
+            Some(last_error) if last_error.elapsed() >= open_duration => CircuitState::HalfOpen,
+            _ => CircuitState::Open,
+        }
+    }
+
+    fn should_skip_attempt(&self, open_duration: Duration) -> bool {
         // Skip if we've had many consecutive errors and not enough time has passed
         if self.consecutive_errors < backoff::MIN_ERRORS_FOR_BACKOFF {
             return false;
         }
 
-        // After 10 consecutive errors, require 1 hour wait
-        if self.consecutive_errors >= backoff::LONG_BACKOFF_ERROR_THRESHOLD {
-            if let Some(last_err) = self.last_error_time {
-                return last_err.elapsed() < backoff::LONG_BACKOFF_DURATION;
-            }
-            return true;
+        // After many consecutive errors the circuit decides: no attempts while
+        // open, a single probe once half-open (a failed probe re-opens it)
+        match self.circuit_state(open_duration) {
+            CircuitState::Open => return true,
+            CircuitState::HalfOpen => return false,
+            CircuitState::Closed => {}
         }
 
         // For moderate errors, use exponential backoff
@@ -164,6 +304,21 @@ impl ConnectionState {
     }
 }
 
+/// Pool key of a connection: a router's plain and TLS sessions, or TLS
+/// sessions with and without certificate verification, are never mixed up
+fn connection_key(key: &str, transport: Transport) -> String {
+    let transport = match transport {
+        Transport::Plain => "plain",
+        Transport::Tls {
+            verify_certificate: true,
+        } => "tls",
+        Transport::Tls {
+            verify_certificate: false,
+        } => "tls-unverified",
+    };
+    format!("{key}/{transport}")
+}
+
 impl Default for ConnectionPool {
     fn default() -> Self {
         Self::new()
@@ -203,10 +358,39 @@ impl ConnectionPool {
             connection_states,
             active_connections,
             max_idle_time: timeouts::POOL_IDLE_TIMEOUT,
+            circuit_open_duration: backoff::DEFAULT_CIRCUIT_OPEN_DURATION,
+            timeouts: Timeouts::default(),
+            collection_timeout: timeouts::COLLECTION_TIMEOUT,
             return_tx,
         }
     }
 
+    /// Sets how long the circuit breaker stays open before probing again
+    #[must_use]
+    pub fn with_circuit_open_duration(mut self, duration: Duration) -> Self {
+        self.circuit_open_duration = duration;
+        self
+    }
+
+    /// Sets the connect and read timeouts of new connections
+    #[must_use]
+    pub fn with_timeouts(mut self, connect: Duration, read: Duration) -> Self {
+        self.timeouts = Timeouts { connect, read };
+        self
+    }
+
+    /// Sets the limit for a router's whole scrape
+    #[must_use]
+    pub fn with_collection_timeout(mut self, timeout: Duration) -> Self {
+        self.collection_timeout = timeout;
+        self
+    }
+
+    /// Limit for a router's whole scrape
+    pub(super) fn collection_timeout(&self) -> Duration {
+        self.collection_timeout
+    }
+
     /// Get or create a connection from the pool with RAII guard
     ///
     /// This method returns a guard that automatically returns the connection
@@ -219,10 +403,12 @@ impl ConnectionPool {
         addr: &str,
         username: &str,
         password: &str,
+        transport: Transport,
     ) -> Result<PooledConnectionGuard, Box<dyn std::error::Error + Send + Sync>> {
         let key = format!("{addr}:{username}");
+        let connection_key = connection_key(&key, transport);
 
-        tracing::trace!("Requesting connection for key: {}", key);
+        tracing::trace!("Requesting connection for key: {}", connection_key);
 
         // Check connection state and apply backoff if needed
         {
@@ -231,7 +417,24 @@ impl ConnectionPool {
                 .entry(key.clone())
                 .or_insert_with(ConnectionState::new);
 
-            if state.should_skip_attempt() {
+            if state.circuit_state(self.circuit_open_duration) == CircuitState::HalfOpen {
+                if !state.try_begin_probe(self.collection_timeout) {
+                    tracing::debug!(
+                        "Skipping connection attempt to {} (circuit half-open, probe in flight)",
+                        addr
+                    );
+                    return Err(Box::new(BackoffError {
+                        addr: addr.to_string(),
+                        consecutive_errors: state.consecutive_errors,
+                    }));
+                }
+                tracing::info!(
+                    "Circuit breaker for {} is half-open, probing after {} consecutive errors",
+                    addr,
+                    state.consecutive_errors
+                );
+            }
+            if state.should_skip_attempt(self.circuit_open_duration) {
                 let delay = state.backoff_delay();
                 tracing::debug!(
                     "Skipping connection attempt to {} (backoff: {} consecutive errors, delay: {:?})",
@@ -239,19 +442,21 @@ impl ConnectionPool {
                     state.consecutive_errors,
                     delay
                 );
-                return Err(format!(
-                    "Connection to {} temporarily disabled due to {} consecutive errors",
-                    addr, state.consecutive_errors
-                )
-                .into());
+                return Err(Box::new(BackoffError {
+                    addr: addr.to_string(),
+                    consecutive_errors: state.consecutive_errors,
+                }));
             }
         }
 
         // Check if we have an available connection
         let conn = {
             let mut pool = self.connections.lock().await;
-            if let Some(mut pooled) = pool.remove(&key) {
-                if pooled.last_used.elapsed() < self.max_idle_time {
+            if let Some(mut pooled) = pool.remove(&connection_key) {
+                if pooled.connection.is_closed().await {
+                    tracing::debug!("Pooled connection for {} was closed by peer", addr);
+                    None
+                } else if pooled.last_used.elapsed() < self.max_idle_time {
                     tracing::debug!("Reusing connection from pool for {}", addr);
                     tracing::trace!("Connection last used: {:?} ago", pooled.last_used.elapsed());
                     pooled.last_used = tokio::time::Instant::now();
@@ -276,18 +481,19 @@ impl ConnectionPool {
         } else {
             // Create new connection
             tracing::debug!("Creating new connection for {}", addr);
-            tracing::trace!("Pool key: {}", key);
-            match RouterOsConnection::connect(addr).await {
+            tracing::trace!("Pool key: {}", connection_key);
+            match RouterOsConnection::connect(addr, transport, self.timeouts).await {
                 Ok(mut conn) => {
                     tracing::trace!("Connection established, attempting login");
                     match conn.login(username, password).await {
-                        Ok(()) => {
+                        Ok(method) => {
                             tracing::trace!("Login successful, connection ready");
                             let mut states = self.connection_states.lock().await;
                             let state = states
                                 .entry(key.clone())
                                 .or_insert_with(ConnectionState::new);
                             state.record_success();
+                            state.record_login(method);
                             tracing::trace!("Connection state reset after successful login");
                             conn
                         }
@@ -302,7 +508,7 @@ impl ConnectionPool {
                                 "Login error recorded, consecutive errors: {}",
                                 state.consecutive_errors
                             );
-                            return Err(e);
+                            return Err(Box::new(LoginError(e)));
                         }
                     }
                 }
@@ -328,6 +534,9 @@ impl ConnectionPool {
             connection: Some(conn),
             pool: self.clone(),
             key,
+            connection_key,
+            transport,
+            broken: false,
         })
     }
 
@@ -356,6 +565,42 @@ impl ConnectionPool {
             .map(|state| (state.consecutive_errors, state.last_success_time.is_some()))
     }
 
+    /// Get circuit breaker state for metrics
+    pub async fn get_circuit_state(&self, addr: &str, username: &str) -> Option<CircuitState> {
+        let key = format!("{addr}:{username}");
+        let states = self.connection_states.lock().await;
+        states
+            .get(&key)
+            .map(|state| state.circuit_state(self.circuit_open_duration))
+    }
+
+    /// Get the method of the latest login and the number of method changes
+    pub async fn get_login_method(&self, addr: &str, username: &str) -> Option<(LoginMethod, u64)> {
+        let key = format!("{addr}:{username}");
+        let states = self.connection_states.lock().await;
+        let state = states.get(&key)?;
+        Some((state.login_method?, state.login_method_changes))
+    }
+
+    /// Get the number of times the router dropped a connection mid-reply
+    pub async fn get_connection_resets(&self, addr: &str, username: &str) -> Option<u64> {
+        let key = format!("{addr}:{username}");
+        let states = self.connection_states.lock().await;
+        states.get(&key).map(|state| state.connection_resets)
+    }
+
+    /// Get the time since the last successful operation with the router
+    ///
+    /// `None` until the router has succeeded once.
+    pub async fn get_last_success_age(&self, addr: &str, username: &str) -> Option<Duration> {
+        let key = format!("{addr}:{username}");
+        let states = self.connection_states.lock().await;
+        states
+            .get(&key)
+            .and_then(|state| state.last_success_time)
+            .map(|time| time.elapsed())
+    }
+
     /// Get pool statistics for metrics
     pub async fn get_pool_stats(&self) -> (usize, usize) {
         let pool = self.connections.lock().await;
@@ -366,6 +611,17 @@ impl ConnectionPool {
         (total, active)
     }
 
+    /// Time since the longest idle pooled connection was last used
+    ///
+    /// Zero when the pool is empty.
+    pub async fn oldest_idle(&self) -> Duration {
+        let pool = self.connections.lock().await;
+        pool.values()
+            .map(|pooled| pooled.last_used.elapsed())
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Clean up expired connections
     pub async fn cleanup(&self) {
         let mut pool = self.connections.lock().await;
@@ -413,6 +669,27 @@ mod tests {
         assert!(state.last_success_time.is_some());
     }
 
+    #[tokio::test]
+    async fn test_get_last_success_age() {
+        let pool = ConnectionPool::new();
+        assert!(
+            pool.get_last_success_age("10.0.0.1:8728", "admin")
+                .await
+                .is_none()
+        );
+
+        pool.record_error("10.0.0.1:8728", "admin").await;
+        assert!(
+            pool.get_last_success_age("10.0.0.1:8728", "admin")
+                .await
+                .is_none()
+        );
+
+        pool.record_success("10.0.0.1:8728", "admin").await;
+        let age = pool.get_last_success_age("10.0.0.1:8728", "admin").await;
+        assert!(age.is_some_and(|age| age < Duration::from_secs(1)));
+    }
+
     #[test]
     fn test_connection_state_record_error() {
         let mut state = ConnectionState::new();
@@ -425,6 +702,23 @@ mod tests {
         assert_eq!(state.consecutive_errors, 2);
     }
 
+    #[test]
+    fn test_connection_state_record_login_counts_changes() {
+        let mut state = ConnectionState::new();
+
+        state.record_login(LoginMethod::New);
+        state.record_login(LoginMethod::New);
+        assert_eq!(state.login_method_changes, 0);
+
+        // The router stops accepting the new method and falls back to legacy
+        state.record_login(LoginMethod::Legacy);
+        assert_eq!(state.login_method, Some(LoginMethod::Legacy));
+        assert_eq!(state.login_method_changes, 1);
+
+        state.record_login(LoginMethod::New);
+        assert_eq!(state.login_method_changes, 2);
+    }
+
     #[test]
     fn test_connection_state_backoff_delay() {
         let mut state = ConnectionState::new();
@@ -468,17 +762,120 @@ mod tests {
         let mut state = ConnectionState::new();
 
         // Less than 3 errors -> should not skip
-        assert!(!state.should_skip_attempt());
+        assert!(!state.should_skip_attempt(backoff::DEFAULT_CIRCUIT_OPEN_DURATION));
 
         state.record_error();
-        assert!(!state.should_skip_attempt());
+        assert!(!state.should_skip_attempt(backoff::DEFAULT_CIRCUIT_OPEN_DURATION));
 
         state.record_error();
-        assert!(!state.should_skip_attempt());
+        assert!(!state.should_skip_attempt(backoff::DEFAULT_CIRCUIT_OPEN_DURATION));
 
         // 3 errors -> should skip (backoff)
         state.record_error();
-        assert!(state.should_skip_attempt());
+        assert!(state.should_skip_attempt(backoff::DEFAULT_CIRCUIT_OPEN_DURATION));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_breaker_opens_and_half_opens() {
+        let open_duration = Duration::from_secs(600);
+        let mut state = ConnectionState::new();
+        for _ in 0..backoff::CIRCUIT_BREAKER_ERROR_THRESHOLD - 1 {
+            state.record_error();
+        }
+        assert_eq!(state.circuit_state(open_duration), CircuitState::Closed);
+
+        state.record_error();
+        assert_eq!(state.circuit_state(open_duration), CircuitState::Open);
+        assert!(state.should_skip_attempt(open_duration));
+
+        tokio::time::advance(open_duration).await;
+        assert_eq!(state.circuit_state(open_duration), CircuitState::HalfOpen);
+        assert!(!state.should_skip_attempt(open_duration));
+
+        // Only one probe is let through until it reports back
+        let lease = Duration::from_secs(35);
+        assert!(state.try_begin_probe(lease));
+        assert!(!state.try_begin_probe(lease));
+
+        // Failed probe re-opens the circuit, a successful one closes it
+        state.record_error();
+        assert_eq!(state.circuit_state(open_duration), CircuitState::Open);
+        state.record_success();
+        assert_eq!(state.circuit_state(open_duration), CircuitState::Closed);
+        assert!(state.probe_started.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_abandoned_half_open_probe_expires() {
+        let lease = Duration::from_secs(35);
+        let mut state = ConnectionState::new();
+        assert!(state.try_begin_probe(lease));
+
+        tokio::time::advance(lease - Duration::from_secs(1)).await;
+        assert!(!state.try_begin_probe(lease));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(state.try_begin_probe(lease));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_half_open_circuit_lets_one_connection_attempt_through() {
+        let open_duration = Duration::from_secs(60);
+        let pool = ConnectionPool::new().with_circuit_open_duration(open_duration);
+        for _ in 0..backoff::CIRCUIT_BREAKER_ERROR_THRESHOLD {
+            pool.record_error("192.0.2.1:8728", "admin").await;
+        }
+        tokio::time::advance(open_duration).await;
+        // A scrape is probing the router right now
+        pool.connection_states
+            .lock()
+            .await
+            .get_mut("192.0.2.1:8728:admin")
+            .unwrap()
+            .probe_started = Some(tokio::time::Instant::now());
+
+        let result = pool
+            .get_connection("192.0.2.1:8728", "admin", "secret", Transport::Plain)
+            .await;
+        assert!(result.is_err_and(|e| e.downcast_ref::<BackoffError>().is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_get_circuit_state() {
+        let pool = ConnectionPool::new().with_circuit_open_duration(Duration::from_secs(60));
+        assert!(
+            pool.get_circuit_state("192.168.1.1", "admin")
+                .await
+                .is_none()
+        );
+
+        for _ in 0..backoff::CIRCUIT_BREAKER_ERROR_THRESHOLD {
+            pool.record_error("192.168.1.1", "admin").await;
+        }
+        assert_eq!(
+            pool.get_circuit_state("192.168.1.1", "admin").await,
+            Some(CircuitState::Open)
+        );
+    }
+
+    #[test]
+    fn test_connection_key_includes_transport() {
+        let plain = connection_key("192.168.1.1:admin", Transport::Plain);
+        let tls = connection_key(
+            "192.168.1.1:admin",
+            Transport::Tls {
+                verify_certificate: true,
+            },
+        );
+        let unverified = connection_key(
+            "192.168.1.1:admin",
+            Transport::Tls {
+                verify_certificate: false,
+            },
+        );
+
+        assert_eq!(plain, "192.168.1.1:admin/plain");
+        assert_ne!(plain, tls);
+        assert_ne!(tls, unverified);
     }
 
     #[test]
@@ -499,6 +896,7 @@ mod tests {
         let (total, active) = pool.get_pool_stats().await;
         assert_eq!(total, 0);
         assert_eq!(active, 0);
+        assert_eq!(pool.oldest_idle().await, Duration::ZERO);
     }
 
     #[tokio::test]
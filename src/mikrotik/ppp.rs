@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Active PPP sessions (`/ppp/active`)
+//!
+//! RouterOS assigns every new session a fresh `.id`, so a session seen for
+//! the first time is one that was established since the previous scrape.
+
+use std::collections::HashMap;
+
+/// Properties read from `/ppp/active/print`
+pub(super) const PPP_PROPERTIES: [&str; 6] =
+    [".id", "name", "service", "caller-id", "address", "uptime"];
+
+/// An active PPP session
+#[derive(Debug, Clone, PartialEq)]
+pub struct PppSessionStats {
+    /// Identifier of this session: the RouterOS `.id`, or the user name on
+    /// responses without one
+    pub session: String,
+    /// User name the session authenticated as
+    pub name: String,
+    /// PPP service, e.g. `pppoe`, `l2tp` or `sstp`
+    pub service: String,
+    /// Client MAC (PPPoE) or address the client connected from
+    pub caller_id: String,
+    /// Address assigned to the client
+    pub address: String,
+    /// Session duration as reported by RouterOS
+    pub uptime: String,
+}
+
+/// Parse `/ppp/active/print` response
+pub(super) fn parse_ppp_sessions(sentences: &[HashMap<String, String>]) -> Vec<PppSessionStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let session = s.get(".id").or_else(|| s.get("name"))?;
+            let field = |key: &str| s.get(key).cloned().unwrap_or_default();
+            Some(PppSessionStats {
+                session: session.clone(),
+                name: field("name"),
+                service: s
+                    .get("service")
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                caller_id: field("caller-id"),
+                address: field("address"),
+                uptime: field("uptime"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_ppp_sessions() {
+        let sentences = vec![
+            sentence(&[
+                (".id", "*80000001"),
+                ("name", "client1"),
+                ("service", "pppoe"),
+                ("caller-id", "AA:BB:CC:DD:EE:01"),
+                ("address", "100.64.0.10"),
+                ("uptime", "2h5m"),
+            ]),
+            sentence(&[("name", "client2"), ("service", "l2tp")]),
+            sentence(&[(".id", "*80000003"), ("name", "client3")]),
+            sentence(&[("service", "pppoe")]),
+        ];
+
+        let sessions = parse_ppp_sessions(&sentences);
+
+        assert_eq!(
+            sessions,
+            vec![
+                PppSessionStats {
+                    session: "*80000001".to_string(),
+                    name: "client1".to_string(),
+                    service: "pppoe".to_string(),
+                    caller_id: "AA:BB:CC:DD:EE:01".to_string(),
+                    address: "100.64.0.10".to_string(),
+                    uptime: "2h5m".to_string(),
+                },
+                PppSessionStats {
+                    session: "client2".to_string(),
+                    name: "client2".to_string(),
+                    service: "l2tp".to_string(),
+                    caller_id: String::new(),
+                    address: String::new(),
+                    uptime: String::new(),
+                },
+                PppSessionStats {
+                    session: "*80000003".to_string(),
+                    name: "client3".to_string(),
+                    service: "unknown".to_string(),
+                    caller_id: String::new(),
+                    address: String::new(),
+                    uptime: String::new(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Simple queue (`/queue/simple`) configuration and counters for MikroTik routers
+//!
+//! Limits and counters are reported as `upload/download` pairs from the
+//! target's point of view, e.g. `max-limit=10M/50M`. A limit of `0` means
+//! unlimited.
+
+use std::collections::HashMap;
+
+/// Configured limits, in bits per second, and counters of a simple queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueStats {
+    pub name: String,
+    /// RouterOS `.id`, only kept when ids are exported as labels
+    pub id: Option<String>,
+    /// Addresses or interfaces the queue applies to, e.g. `192.168.88.10/32`
+    pub target: String,
+    pub max_limit_upload: u64,
+    pub max_limit_download: u64,
+    pub limit_at_upload: u64,
+    pub limit_at_download: u64,
+    /// Counters since the queue was created or last reset
+    pub bytes_upload: u64,
+    pub bytes_download: u64,
+    pub packets_upload: u64,
+    pub packets_download: u64,
+}
+
+/// Parse `/queue/simple/print` response
+pub(super) fn parse_simple_queues(
+    sentences: &[HashMap<String, String>],
+    include_ids: bool,
+) -> Vec<QueueStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let name = s.get("name")?;
+            let (max_limit_upload, max_limit_download) =
+                s.get("max-limit").map_or((0, 0), |v| parse_limit_pair(v));
+            let (limit_at_upload, limit_at_download) =
+                s.get("limit-at").map_or((0, 0), |v| parse_limit_pair(v));
+            let (bytes_upload, bytes_download) =
+                s.get("bytes").map_or((0, 0), |v| parse_counter_pair(v));
+            let (packets_upload, packets_download) =
+                s.get("packets").map_or((0, 0), |v| parse_counter_pair(v));
+            Some(QueueStats {
+                name: name.clone(),
+                id: s.get(".id").filter(|_| include_ids).cloned(),
+                target: s.get("target").cloned().unwrap_or_default(),
+                max_limit_upload,
+                max_limit_download,
+                limit_at_upload,
+                limit_at_download,
+                bytes_upload,
+                bytes_download,
+                packets_upload,
+                packets_download,
+            })
+        })
+        .collect()
+}
+
+/// Split an `upload/download` limit, treating unparsable halves as unlimited
+fn parse_limit_pair(value: &str) -> (u64, u64) {
+    let (upload, download) = value.split_once('/').unwrap_or((value, value));
+    (
+        parse_rate(upload).unwrap_or(0),
+        parse_rate(download).unwrap_or(0),
+    )
+}
+
+/// Split an `upload/download` counter pair, treating unparsable halves as 0
+fn parse_counter_pair(value: &str) -> (u64, u64) {
+    let (upload, download) = value.split_once('/').unwrap_or((value, "0"));
+    (
+        upload.trim().parse().unwrap_or(0),
+        download.trim().parse().unwrap_or(0),
+    )
+}
+
+/// Bits per second from RouterOS rates such as `512k`, `10M` or `1G`
+fn parse_rate(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last()? {
+        (i, 'k' | 'K') => (&value[..i], 1_000),
+        (i, 'M') => (&value[..i], 1_000_000),
+        (i, 'G') => (&value[..i], 1_000_000_000),
+        _ => (value, 1),
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rate_suffixes() {
+        assert_eq!(parse_rate("0"), Some(0));
+        assert_eq!(parse_rate("64000"), Some(64_000));
+        assert_eq!(parse_rate("512k"), Some(512_000));
+        assert_eq!(parse_rate("10M"), Some(10_000_000));
+        assert_eq!(parse_rate("1G"), Some(1_000_000_000));
+        assert!(parse_rate("fast").is_none());
+    }
+
+    #[test]
+    fn test_parse_simple_queues_limits() {
+        let sentences = vec![
+            sentence(&[
+                (".id", "*1"),
+                ("name", "office"),
+                ("target", "192.168.88.0/24"),
+                ("max-limit", "10M/50M"),
+                ("limit-at", "2M/5M"),
+                ("bytes", "1200/96000"),
+                ("packets", "20/80"),
+            ]),
+            sentence(&[(".id", "*2"), ("name", "guest")]),
+            sentence(&[("max-limit", "1M/1M")]),
+        ];
+
+        let queues = parse_simple_queues(&sentences, false);
+
+        assert_eq!(queues.len(), 2);
+        assert_eq!(
+            queues[0],
+            QueueStats {
+                name: "office".to_string(),
+                id: None,
+                target: "192.168.88.0/24".to_string(),
+                max_limit_upload: 10_000_000,
+                max_limit_download: 50_000_000,
+                limit_at_upload: 2_000_000,
+                limit_at_download: 5_000_000,
+                bytes_upload: 1200,
+                bytes_download: 96_000,
+                packets_upload: 20,
+                packets_download: 80,
+            }
+        );
+        assert_eq!(queues[1].max_limit_download, 0);
+        assert_eq!(queues[1].bytes_download, 0);
+    }
+
+    #[test]
+    fn test_parse_simple_queues_keeps_ids_when_requested() {
+        let sentences = vec![sentence(&[(".id", "*1A"), ("name", "office")])];
+
+        let queues = parse_simple_queues(&sentences, true);
+
+        assert_eq!(queues[0].id.as_deref(), Some("*1A"));
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Default routes (`/ip/route` with `dst-address=0.0.0.0/0`)
+//!
+//! In multi-WAN and failover setups there is one default route per uplink and
+//! RouterOS marks the one currently in use `active`. Routes are reported per
+//! gateway so that a failover shows up as the active flag moving between them.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Destination of a default route
+pub(super) const DEFAULT_DST: &str = "0.0.0.0/0";
+
+/// A gateway with at least one default route via it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultRouteStats {
+    pub gateway: String,
+    /// Whether any default route via this gateway is active
+    pub active: bool,
+}
+
+/// Parse `/ip/route/print` response filtered to default routes
+pub(super) fn parse_default_routes(
+    sentences: &[HashMap<String, String>],
+) -> Vec<DefaultRouteStats> {
+    let mut gateways: BTreeMap<&str, bool> = BTreeMap::new();
+    for route in sentences {
+        if route
+            .get("dst-address")
+            .is_some_and(|dst| dst != DEFAULT_DST)
+        {
+            continue;
+        }
+        let Some(gateway) = route.get("gateway").filter(|g| !g.is_empty()) else {
+            continue;
+        };
+        let active = route.get("active").is_some_and(|v| v == "true");
+        *gateways.entry(gateway).or_default() |= active;
+    }
+
+    gateways
+        .into_iter()
+        .map(|(gateway, active)| DefaultRouteStats {
+            gateway: gateway.to_string(),
+            active,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_default_routes_per_gateway() {
+        let sentences = vec![
+            sentence(&[
+                ("dst-address", "0.0.0.0/0"),
+                ("gateway", "203.0.113.1"),
+                ("active", "true"),
+            ]),
+            sentence(&[
+                ("dst-address", "0.0.0.0/0"),
+                ("gateway", "198.51.100.1"),
+                ("distance", "2"),
+            ]),
+            sentence(&[
+                ("dst-address", "0.0.0.0/0"),
+                ("gateway", "198.51.100.1"),
+                ("routing-table", "isp2"),
+                ("active", "false"),
+            ]),
+            sentence(&[
+                ("dst-address", "10.0.0.0/8"),
+                ("gateway", "192.0.2.1"),
+                ("active", "true"),
+            ]),
+            sentence(&[("dst-address", "0.0.0.0/0"), ("type", "blackhole")]),
+        ];
+
+        let routes = parse_default_routes(&sentences);
+
+        assert_eq!(
+            routes,
+            vec![
+                DefaultRouteStats {
+                    gateway: "198.51.100.1".to_string(),
+                    active: false,
+                },
+                DefaultRouteStats {
+                    gateway: "203.0.113.1".to_string(),
+                    active: true,
+                },
+            ]
+        );
+    }
+}
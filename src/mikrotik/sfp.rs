@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! SFP module diagnostics (DDM) from `/interface/ethernet/monitor`
+//!
+//! SFP-capable ports are picked from `/interface/ethernet/print` and each
+//! enabled one is queried with a one-shot monitor. Copper ports are never
+//! monitored, and an empty cage reports no SFP fields and is skipped.
+
+use std::collections::HashMap;
+
+/// Properties read from `/interface/ethernet/monitor`
+pub(super) const SFP_PROPERTIES: [&str; 4] = [
+    "sfp-rx-power",
+    "sfp-tx-power",
+    "sfp-temperature",
+    "sfp-supply-voltage",
+];
+
+/// Properties of `/interface/ethernet/print` that tell SFP cages from copper ports
+pub(super) const SFP_PORT_PROPERTIES: [&str; 4] = [
+    "name",
+    "default-name",
+    "disabled",
+    "sfp-shutdown-temperature",
+];
+
+/// Factory name prefixes of SFP, SFP+, SFP28 and QSFP cages and combo ports
+const SFP_PORT_PREFIXES: [&str; 3] = ["sfp", "qsfp", "combo"];
+
+/// Diagnostics of an SFP module
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfpStats {
+    pub interface: String,
+    /// Received optical power in dBm
+    pub rx_power_dbm: Option<f64>,
+    /// Transmitted optical power in dBm
+    pub tx_power_dbm: Option<f64>,
+    /// Module temperature in degrees Celsius
+    pub temperature_celsius: Option<f64>,
+    /// Module supply voltage in volts
+    pub supply_voltage: Option<f64>,
+}
+
+/// Names of enabled SFP-capable ports from `/interface/ethernet/print`
+///
+/// A port counts as SFP-capable when it has SFP settings or its factory name
+/// (kept in `default-name` after a rename) marks an SFP cage.
+pub(super) fn parse_sfp_ports(sentences: &[HashMap<String, String>]) -> Vec<String> {
+    sentences
+        .iter()
+        .filter(|s| s.get("disabled").is_none_or(|v| v != "true"))
+        .filter(|s| {
+            let factory_name = s.get("default-name").or_else(|| s.get("name"));
+            s.contains_key("sfp-shutdown-temperature")
+                || factory_name.is_some_and(|name| {
+                    SFP_PORT_PREFIXES
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix))
+                })
+        })
+        .filter_map(|s| s.get("name").cloned())
+        .collect()
+}
+
+/// Parse a one-shot `/interface/ethernet/monitor` response for `interface`
+///
+/// Returns `None` when the port reports no SFP diagnostics.
+pub(super) fn parse_sfp_monitor(
+    interface: &str,
+    sentences: &[HashMap<String, String>],
+) -> Option<SfpStats> {
+    let s = sentences.first()?;
+    let value = |key: &str| s.get(key).and_then(|v| parse_measurement(v));
+    let stats = SfpStats {
+        interface: interface.to_string(),
+        rx_power_dbm: value("sfp-rx-power"),
+        tx_power_dbm: value("sfp-tx-power"),
+        temperature_celsius: value("sfp-temperature"),
+        supply_voltage: value("sfp-supply-voltage"),
+    };
+    (stats.rx_power_dbm.is_some()
+        || stats.tx_power_dbm.is_some()
+        || stats.temperature_celsius.is_some()
+        || stats.supply_voltage.is_some())
+    .then_some(stats)
+}
+
+/// Leading number of a measurement such as `-5.123dBm`, `38C` or `3.291V`
+fn parse_measurement(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && c == '-')))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_measurement() {
+        assert_eq!(parse_measurement("-5.123dBm"), Some(-5.123));
+        assert_eq!(parse_measurement("38C"), Some(38.0));
+        assert_eq!(parse_measurement("3.291"), Some(3.291));
+        assert_eq!(parse_measurement("n/a"), None);
+    }
+
+    #[test]
+    fn test_parse_sfp_monitor() {
+        let sentences = vec![sentence(&[
+            ("name", "sfp-sfpplus1"),
+            ("sfp-rx-power", "-7.402dBm"),
+            ("sfp-tx-power", "-2.115dBm"),
+            ("sfp-temperature", "41C"),
+            ("sfp-supply-voltage", "3.301V"),
+        ])];
+
+        assert_eq!(
+            parse_sfp_monitor("sfp-sfpplus1", &sentences),
+            Some(SfpStats {
+                interface: "sfp-sfpplus1".to_string(),
+                rx_power_dbm: Some(-7.402),
+                tx_power_dbm: Some(-2.115),
+                temperature_celsius: Some(41.0),
+                supply_voltage: Some(3.301),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_sfp_monitor_skips_copper_ports() {
+        let sentences = vec![sentence(&[("name", "ether1"), ("rate", "1Gbps")])];
+
+        assert_eq!(parse_sfp_monitor("ether1", &sentences), None);
+        assert_eq!(parse_sfp_monitor("ether1", &[]), None);
+    }
+
+    #[test]
+    fn test_parse_sfp_ports_skips_copper_and_disabled_ports() {
+        let sentences = vec![
+            sentence(&[("name", "ether1"), ("default-name", "ether1")]),
+            sentence(&[("name", "uplink"), ("default-name", "sfp-sfpplus1")]),
+            sentence(&[("name", "sfp2"), ("disabled", "true")]),
+            sentence(&[("name", "wan"), ("sfp-shutdown-temperature", "95")]),
+            sentence(&[("name", "qsfp28-1-1"), ("disabled", "false")]),
+        ];
+
+        assert_eq!(
+            parse_sfp_ports(&sentences),
+            vec!["uplink", "wan", "qsfp28-1-1"]
+        );
+    }
+}
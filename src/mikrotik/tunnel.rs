@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Overlay tunnel interfaces (`/interface/eoip`, `/interface/gre`,
+//! `/interface/vxlan`)
+
+use std::collections::HashMap;
+
+/// Tunnel menus read by the tunnels collector, by tunnel type
+pub(super) const TUNNEL_TYPES: [&str; 3] = ["eoip", "gre", "vxlan"];
+
+/// Running state of a tunnel interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct TunnelStats {
+    /// Tunnel type: `eoip`, `gre` or `vxlan`
+    pub kind: String,
+    pub name: String,
+    pub running: bool,
+}
+
+/// Parse `/interface/<kind>/print` response
+pub(super) fn parse_tunnels(kind: &str, sentences: &[HashMap<String, String>]) -> Vec<TunnelStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            Some(TunnelStats {
+                kind: kind.to_string(),
+                name: s.get("name")?.clone(),
+                running: s.get("running").is_some_and(|v| v == "true"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_tunnels() {
+        let sentences = vec![
+            sentence(&[
+                ("name", "gre-dc1"),
+                ("remote-address", "203.0.113.1"),
+                ("running", "true"),
+            ]),
+            sentence(&[
+                ("name", "gre-dc2"),
+                ("running", "false"),
+                ("disabled", "true"),
+            ]),
+            sentence(&[("remote-address", "203.0.113.3")]),
+        ];
+
+        let tunnels = parse_tunnels("gre", &sentences);
+
+        assert_eq!(
+            tunnels,
+            vec![
+                TunnelStats {
+                    kind: "gre".to_string(),
+                    name: "gre-dc1".to_string(),
+                    running: true,
+                },
+                TunnelStats {
+                    kind: "gre".to_string(),
+                    name: "gre-dc2".to_string(),
+                    running: false,
+                },
+            ]
+        );
+    }
+}
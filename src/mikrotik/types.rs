@@ -3,10 +3,40 @@
 
 //! Type definitions for MikroTik metrics
 
+use crate::config::Collector;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::bgp::BgpSessionStats;
+use super::bonding::BondStats;
+use super::capsman::{CapsmanClientStats, ManagedDeviceStats};
+use super::clock::ClockStats;
+use super::conntrack_table::ConntrackTableStats;
+use super::container::ContainerStats;
+use super::cpu::CpuCoreStats;
+use super::custom::CustomMetric;
+use super::dhcp::DhcpLeaseStats;
+use super::firewall::FirewallRuleStats;
+use super::health::HealthStats;
+use super::interface_queue::InterfaceQueueStats;
+use super::ip_service::IpServiceStats;
+use super::ipsec::IpsecPeerStats;
+use super::license::LicenseStats;
+use super::ping::PingStats;
+use super::ppp::PppSessionStats;
+use super::queue::QueueStats;
+use super::route::DefaultRouteStats;
+use super::sfp::SfpStats;
+use super::tunnel::TunnelStats;
+use super::vrrp::VrrpStats;
+use super::watchdog::UnexpectedReboot;
+use super::wifi::WifiChannelStats;
 use super::wireguard::{WireGuardInterfaceStats, WireGuardPeerStats};
+use super::wireless::WirelessStationStats;
 
 /// Statistics for a network interface
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct InterfaceStats {
     pub name: String,
     pub rx_bytes: u64,
@@ -15,22 +45,46 @@ pub struct InterfaceStats {
     pub tx_packets: u64,
     pub rx_errors: u64,
     pub tx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_drops: u64,
     pub running: bool,
+    pub disabled: bool,
+    /// Configured MTU, `None` when the interface reports none
+    pub mtu: Option<u64>,
+    /// MTU in effect, which can be lower than configured, e.g. when the
+    /// hardware doesn't support it
+    pub actual_mtu: Option<u64>,
+    /// Multicast and broadcast packet counters, only reported by ethernet
+    /// interfaces
+    pub rx_multicast: Option<u64>,
+    pub tx_multicast: Option<u64>,
+    pub rx_broadcast: Option<u64>,
+    pub tx_broadcast: Option<u64>,
 }
 
 /// System resource information from a `MikroTik` router
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SystemResource {
     pub uptime: String,
     pub cpu_load: u64,
+    /// Number of CPU cores, `None` when not reported
+    pub cpu_count: Option<u64>,
     pub free_memory: u64,
     pub total_memory: u64,
+    pub free_hdd_space: u64,
+    pub total_hdd_space: u64,
     pub version: String,
     pub board_name: String,
+    /// CPU architecture, e.g. `arm64`, `mipsbe` or `x86_64`
+    pub architecture: String,
+    /// Router name from `/system/identity`
+    pub identity: String,
+    /// Serial number from `/system/routerboard`, `unknown` on CHR
+    pub serial_number: String,
 }
 
 /// Connection tracking statistics per source address
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ConnectionTrackingStats {
     pub src_address: String,
     pub protocol: String,
@@ -39,14 +93,89 @@ pub struct ConnectionTrackingStats {
 }
 
 /// Complete metrics snapshot from a router
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RouterMetrics {
     pub router_name: String,
     pub interfaces: Vec<InterfaceStats>,
-    pub system: SystemResource,
+    pub interface_queues: Vec<InterfaceQueueStats>,
+    /// `None` when the system collector is disabled for the router
+    pub system: Option<SystemResource>,
+    /// Per-core CPU load, empty when the router does not report it
+    pub cpu_cores: Vec<CpuCoreStats>,
     pub connection_tracking: Vec<ConnectionTrackingStats>,
+    /// Distinct source addresses across IPv4 and IPv6 connections, `None`
+    /// when not collected
+    pub conntrack_distinct_sources: Option<u64>,
+    /// Connection tracking table usage, `None` when not collected
+    pub conntrack_table: Option<ConntrackTableStats>,
     pub wireguard_interfaces: Vec<WireGuardInterfaceStats>,
     pub wireguard_peers: Vec<WireGuardPeerStats>,
+    /// Router clock minus exporter clock when the WireGuard peers were read,
+    /// `None` when not collected
+    pub router_clock_skew_seconds: Option<i64>,
+    pub ip_services: Vec<IpServiceStats>,
+    pub bonds: Vec<BondStats>,
+    /// Number of running script jobs, `None` when not collected
+    pub script_jobs: Option<u64>,
+    /// Active User Manager sessions, `None` when not collected or the package
+    /// isn't installed
+    pub userman_sessions: Option<u64>,
+    /// IPv4 routing table entries, `None` when not collected
+    pub ipv4_routes: Option<u64>,
+    /// IPv6 routing table entries, `None` when not collected or IPv6 is
+    /// disabled
+    pub ipv6_routes: Option<u64>,
+    /// Established IPsec peers, `None` when not collected
+    pub ipsec_active_peers: Option<u64>,
+    /// Active IPsec peers in any state
+    pub ipsec_peers: Vec<IpsecPeerStats>,
+    /// Router clock state, `None` when not collected
+    pub clock: Option<ClockStats>,
+    /// License level, `None` when not collected
+    pub license: Option<LicenseStats>,
+    pub wifi_channels: Vec<WifiChannelStats>,
+    /// Diagnostics of SFP modules
+    pub sfp_modules: Vec<SfpStats>,
+    /// Stations registered to legacy wireless interfaces
+    pub wireless_stations: Vec<WirelessStationStats>,
+    /// CAPs managed by this router when it is a CAPsMAN controller
+    pub managed_devices: Vec<ManagedDeviceStats>,
+    /// Wireless clients registered through this router as a CAPsMAN
+    /// controller
+    pub capsman_clients: Vec<CapsmanClientStats>,
+    /// Reachability of the router's configured ping targets
+    pub ping_targets: Vec<PingStats>,
+    /// Active PPP sessions, `None` when not collected
+    pub ppp_sessions: Option<Vec<PppSessionStats>>,
+    /// DHCP server leases, `None` when not collected
+    pub dhcp_leases: Option<Vec<DhcpLeaseStats>>,
+    pub simple_queues: Vec<QueueStats>,
+    /// Default routes per gateway and whether they are active
+    pub default_routes: Vec<DefaultRouteStats>,
+    /// EoIP, GRE and VXLAN tunnel interfaces
+    pub tunnels: Vec<TunnelStats>,
+    /// VRRP interfaces and whether this router is master
+    pub vrrp: Vec<VrrpStats>,
+    /// BGP sessions and their state
+    pub bgp_sessions: Vec<BgpSessionStats>,
+    /// Firewall filter rule counters
+    pub firewall_rules: Vec<FirewallRuleStats>,
+    /// Containers and their status
+    pub containers: Vec<ContainerStats>,
+    /// Whether the watchdog timer is enabled, `None` when not collected
+    pub watchdog_enabled: Option<bool>,
+    /// Unexpected reboots still in the log, `None` when not collected
+    pub unexpected_reboots: Option<Vec<UnexpectedReboot>>,
+    /// Board health sensors
+    pub health: HealthStats,
+    /// Entries of the router's custom collectors
+    pub custom_metrics: Vec<CustomMetric>,
+    /// Paths of commands that returned an error during the scrape
+    pub command_errors: Vec<String>,
+    /// Number of RouterOS commands issued to build this snapshot
+    pub commands_executed: u32,
+    /// Time spent running each enabled collector's commands
+    pub collector_durations: HashMap<Collector, Duration>,
 }
 
 #[cfg(test)]
@@ -63,7 +192,16 @@ mod tests {
             tx_packets: 20,
             rx_errors: 0,
             tx_errors: 0,
+            rx_drops: 0,
+            tx_drops: 0,
             running: true,
+            disabled: false,
+            mtu: None,
+            actual_mtu: None,
+            rx_multicast: None,
+            tx_multicast: None,
+            rx_broadcast: None,
+            tx_broadcast: None,
         };
 
         assert_eq!(stats.name, "ether1");
@@ -77,10 +215,16 @@ mod tests {
         let resource = SystemResource {
             uptime: "1d2h3m4s".to_string(),
             cpu_load: 50,
+            cpu_count: None,
             free_memory: 1024 * 1024 * 512,
             total_memory: 1024 * 1024 * 1024,
+            free_hdd_space: 64 * 1024 * 1024,
+            total_hdd_space: 128 * 1024 * 1024,
             version: "7.10".to_string(),
             board_name: "RB750Gr3".to_string(),
+            architecture: "arm".to_string(),
+            identity: "gw".to_string(),
+            serial_number: "HEX0123456".to_string(),
         };
 
         assert_eq!(resource.uptime, "1d2h3m4s");
@@ -101,16 +245,31 @@ mod tests {
                 tx_packets: 20,
                 rx_errors: 0,
                 tx_errors: 0,
+                rx_drops: 0,
+                tx_drops: 0,
                 running: true,
+                disabled: false,
+                mtu: None,
+                actual_mtu: None,
+                rx_multicast: None,
+                tx_multicast: None,
+                rx_broadcast: None,
+                tx_broadcast: None,
             }],
-            system: SystemResource {
+            system: Some(SystemResource {
                 uptime: "1d".to_string(),
                 cpu_load: 10,
+                cpu_count: None,
                 free_memory: 1024,
                 total_memory: 2048,
+                free_hdd_space: 64 * 1024 * 1024,
+                total_hdd_space: 128 * 1024 * 1024,
                 version: "7.10".to_string(),
                 board_name: "test".to_string(),
-            },
+                architecture: "arm".to_string(),
+                identity: "gw".to_string(),
+                serial_number: "HEX0123456".to_string(),
+            }),
             connection_tracking: Vec::new(),
             wireguard_interfaces: vec![WireGuardInterfaceStats {
                 name: "wg1".to_string(),
@@ -120,17 +279,19 @@ mod tests {
                 interface: "wg1".to_string(),
                 name: "peer1".to_string(),
                 allowed_address: "10.10.10.1/32".to_string(),
+                public_key: None,
                 endpoint: Some("192.168.1.1:51820".to_string()),
                 rx_bytes: 1024,
                 tx_bytes: 2048,
                 latest_handshake: None,
             }],
+            ..Default::default()
         };
 
         assert_eq!(metrics.router_name, "main-router");
         assert_eq!(metrics.interfaces.len(), 1);
         assert_eq!(metrics.interfaces[0].name, "ether1");
-        assert_eq!(metrics.system.version, "7.10");
+        assert_eq!(metrics.system.as_ref().unwrap().version, "7.10");
         assert_eq!(metrics.wireguard_interfaces.len(), 1);
         assert_eq!(metrics.wireguard_peers.len(), 1);
     }
@@ -145,7 +306,16 @@ mod tests {
             tx_packets: 20,
             rx_errors: 0,
             tx_errors: 0,
+            rx_drops: 0,
+            tx_drops: 0,
             running: true,
+            disabled: false,
+            mtu: None,
+            actual_mtu: None,
+            rx_multicast: None,
+            tx_multicast: None,
+            rx_broadcast: None,
+            tx_broadcast: None,
         };
 
         let cloned = stats.clone();
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! VRRP interfaces (`/interface/vrrp`)
+
+use std::collections::HashMap;
+
+/// State of a VRRP interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrrpStats {
+    /// Name of the VRRP interface
+    pub interface: String,
+    /// Whether this router currently owns the virtual address
+    pub master: bool,
+}
+
+/// Parse `/interface/vrrp/print` response
+pub(super) fn parse_vrrp(sentences: &[HashMap<String, String>]) -> Vec<VrrpStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            Some(VrrpStats {
+                interface: s.get("name")?.clone(),
+                master: s.get("master").is_some_and(|v| v == "true"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_vrrp() {
+        let sentences = vec![
+            sentence(&[
+                ("name", "vrrp-lan"),
+                ("interface", "bridge"),
+                ("vrid", "10"),
+                ("master", "true"),
+            ]),
+            sentence(&[("name", "vrrp-dmz"), ("vrid", "20"), ("backup", "true")]),
+            sentence(&[("vrid", "30")]),
+        ];
+
+        let vrrp = parse_vrrp(&sentences);
+
+        assert_eq!(
+            vrrp,
+            vec![
+                VrrpStats {
+                    interface: "vrrp-lan".to_string(),
+                    master: true,
+                },
+                VrrpStats {
+                    interface: "vrrp-dmz".to_string(),
+                    master: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Watchdog state (`/system/watchdog`) and unexpected reboots (`/log`)
+//!
+//! After a reboot it didn't initiate, RouterOS logs a critical message on the
+//! next boot. The log lives in memory, so such an entry disappears on the
+//! following reboot; an entry seen for the first time is a new reboot.
+
+use std::collections::HashMap;
+
+/// Log topics of the messages written after an unexpected reboot
+pub(super) const REBOOT_LOG_TOPICS: &str = "system,error,critical";
+
+/// An unexpected reboot found in the log
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnexpectedReboot {
+    /// Identifier of the log entry: its time of day and message
+    ///
+    /// The date is left out because RouterOS drops it for today's entries.
+    pub event: String,
+    /// `watchdog`, `kernel_failure` or `unclean_shutdown`
+    pub reason: String,
+}
+
+/// Whether the watchdog timer is enabled, from `/system/watchdog/print`
+pub(super) fn parse_watchdog(sentences: &[HashMap<String, String>]) -> Option<bool> {
+    sentences
+        .iter()
+        .find_map(|s| s.get("watchdog-timer"))
+        .map(|v| v == "yes" || v == "true")
+}
+
+/// Parse critical `/log/print` entries into unexpected reboots
+///
+/// Planned reboots are logged with other messages and are not matched.
+pub(super) fn parse_unexpected_reboots(
+    sentences: &[HashMap<String, String>],
+) -> Vec<UnexpectedReboot> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            let message = s.get("message")?;
+            let lower = message.to_ascii_lowercase();
+            let reason = if lower.contains("watchdog") {
+                "watchdog"
+            } else if lower.contains("kernel failure") {
+                "kernel_failure"
+            } else if lower.contains("without proper shutdown") {
+                "unclean_shutdown"
+            } else {
+                return None;
+            };
+            let time = s
+                .get("time")
+                .and_then(|t| t.split_whitespace().last())
+                .unwrap_or_default();
+            Some(UnexpectedReboot {
+                event: format!("{time} {message}"),
+                reason: reason.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_watchdog() {
+        let enabled = sentence(&[("watchdog-timer", "yes"), ("ping-target", "none")]);
+        let disabled = sentence(&[("watchdog-timer", "no")]);
+
+        assert_eq!(parse_watchdog(&[enabled]), Some(true));
+        assert_eq!(parse_watchdog(&[disabled]), Some(false));
+        assert_eq!(parse_watchdog(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_unexpected_reboots() {
+        let sentences = vec![
+            sentence(&[
+                ("time", "2024-05-01 03:12:09"),
+                ("topics", REBOOT_LOG_TOPICS),
+                (
+                    "message",
+                    "router was rebooted without proper shutdown by watchdog timer",
+                ),
+            ]),
+            sentence(&[
+                ("time", "10:00:01"),
+                (
+                    "message",
+                    "router was rebooted without proper shutdown, probably power outage",
+                ),
+            ]),
+            sentence(&[
+                ("time", "10:00:02"),
+                ("message", "kernel failure in previous boot"),
+            ]),
+            sentence(&[
+                ("time", "10:00:03"),
+                ("message", "login failure for user admin"),
+            ]),
+        ];
+
+        let reboots = parse_unexpected_reboots(&sentences);
+
+        let reasons: Vec<&str> = reboots.iter().map(|r| r.reason.as_str()).collect();
+        assert_eq!(reasons, ["watchdog", "unclean_shutdown", "kernel_failure"]);
+        assert_eq!(
+            reboots[0].event,
+            "03:12:09 router was rebooted without proper shutdown by watchdog timer"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Wireless (`/interface/wifi`) channel utilization for MikroTik routers
+//!
+//! Interfaces are listed with `/interface/wifi/print` and each enabled one is
+//! queried with a one-shot `/interface/wifi/monitor`. Only platforms whose
+//! monitor reports `channel-utilization` produce a value; other interfaces
+//! are skipped rather than reported as idle.
+
+use std::collections::HashMap;
+
+/// Channel utilization of a wireless interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct WifiChannelStats {
+    pub interface: String,
+    /// Share of airtime the channel was busy (0..1)
+    pub utilization: f64,
+}
+
+/// Names of enabled interfaces from `/interface/wifi/print`
+pub(super) fn parse_wifi_interfaces(sentences: &[HashMap<String, String>]) -> Vec<String> {
+    sentences
+        .iter()
+        .filter(|s| s.get("disabled").is_none_or(|v| v != "true"))
+        .filter_map(|s| s.get("name").cloned())
+        .collect()
+}
+
+/// Parse a one-shot `/interface/wifi/monitor` response for `interface`
+///
+/// RouterOS reports utilization in percent, with or without a `%` suffix.
+pub(super) fn parse_wifi_monitor(
+    interface: &str,
+    sentences: &[HashMap<String, String>],
+) -> Option<WifiChannelStats> {
+    let percent: f64 = sentences
+        .iter()
+        .find_map(|s| s.get("channel-utilization"))?
+        .trim_end_matches('%')
+        .parse()
+        .ok()?;
+    Some(WifiChannelStats {
+        interface: interface.to_string(),
+        utilization: (percent / 100.0).clamp(0.0, 1.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_wifi_interfaces_skips_disabled() {
+        let sentences = vec![
+            sentence(&[("name", "wifi1"), ("disabled", "false")]),
+            sentence(&[("name", "wifi2"), ("disabled", "true")]),
+        ];
+
+        assert_eq!(parse_wifi_interfaces(&sentences), vec!["wifi1"]);
+    }
+
+    #[test]
+    fn test_parse_wifi_monitor_percent() {
+        let sentences = vec![sentence(&[
+            ("channel", "2437/ax/Ce"),
+            ("channel-utilization", "87%"),
+        ])];
+
+        let stats = parse_wifi_monitor("wifi1", &sentences).unwrap();
+
+        assert_eq!(stats.interface, "wifi1");
+        assert!((stats.utilization - 0.87).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_wifi_monitor_without_utilization() {
+        let sentences = vec![sentence(&[("channel", "5180/ax/Ceee")])];
+        assert!(parse_wifi_monitor("wifi1", &sentences).is_none());
+    }
+}
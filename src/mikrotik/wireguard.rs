@@ -7,25 +7,30 @@
 //! from RouterOS API responses and structures for storing the parsed data.
 //!
 //! For peer identification, we use `allowed-address` instead of `public-key`
-//! to avoid collecting sensitive information. This approach provides a stable
-//! identifier for monitoring while maintaining privacy.
+//! by default to avoid collecting sensitive information. Routers configured
+//! with `wireguard_peer_key: "public-key"` opt into keying peers by their
+//! public key instead.
 
+use crate::config::WireGuardPeerKey;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 /// Statistics for a WireGuard interface
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WireGuardInterfaceStats {
     pub name: String,
     pub enabled: bool,
 }
 
 /// Statistics for a WireGuard peer
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WireGuardPeerStats {
     pub interface: String,
     pub name: String,
+    /// Empty when the peer has none and is keyed by public key
     pub allowed_address: String,
+    /// Only collected when peers are keyed by public key
+    pub public_key: Option<String>,
     pub endpoint: Option<String>,
     pub rx_bytes: u64,
     pub tx_bytes: u64,
@@ -51,8 +56,16 @@ pub(super) fn parse_wireguard_interfaces(
 }
 
 /// Parse WireGuard peer information from RouterOS API response
+///
+/// `received_at` is the Unix time the response arrived. Handshakes are
+/// reported as time elapsed on the router, so their timestamps are taken
+/// relative to it and don't depend on the router's clock being set.
+///
+/// Peers lacking the property selected by `key` are skipped.
 pub(super) fn parse_wireguard_peers(
     sentences: &[HashMap<String, String>],
+    received_at: u64,
+    key: WireGuardPeerKey,
 ) -> Vec<WireGuardPeerStats> {
     let mut peers = Vec::new();
 
@@ -79,23 +92,30 @@ pub(super) fn parse_wireguard_peers(
             // Support both "last-handshake" (new) and "latest-handshake" (old) field names
             let latest_handshake =
                 get_field_value(sentence, &["last-handshake", "latest-handshake"])
-                    .and_then(|v| parse_handshake_to_timestamp(&v));
-
-            // Use allowed-address as the identifier instead of public-key
-            if let Some(allowed_address) = sentence.get("allowed-address") {
-                peers.push(WireGuardPeerStats {
-                    interface: interface.clone(),
-                    name: sentence
-                        .get("name")
-                        .cloned()
-                        .unwrap_or_else(|| "unnamed-peer".to_string()),
-                    allowed_address: allowed_address.clone(),
-                    endpoint: parse_peer_endpoint(sentence),
-                    rx_bytes,
-                    tx_bytes,
-                    latest_handshake,
-                });
-            }
+                    .and_then(|v| parse_handshake_to_timestamp(&v, received_at));
+
+            let allowed_address = sentence.get("allowed-address").cloned();
+            let public_key = match key {
+                WireGuardPeerKey::AllowedAddress if allowed_address.is_none() => continue,
+                WireGuardPeerKey::AllowedAddress => None,
+                WireGuardPeerKey::PublicKey => match sentence.get("public-key") {
+                    Some(public_key) => Some(public_key.clone()),
+                    None => continue,
+                },
+            };
+            peers.push(WireGuardPeerStats {
+                interface: interface.clone(),
+                name: sentence
+                    .get("name")
+                    .cloned()
+                    .unwrap_or_else(|| "unnamed-peer".to_string()),
+                allowed_address: allowed_address.unwrap_or_default(),
+                public_key,
+                endpoint: parse_peer_endpoint(sentence),
+                rx_bytes,
+                tx_bytes,
+                latest_handshake,
+            });
         }
     }
 
@@ -133,9 +153,10 @@ fn parse_peer_endpoint(fields: &HashMap<String, String>) -> Option<String> {
 /// - "120" (120 seconds, older RouterOS versions)
 /// - "0s" or "" (zero seconds)
 ///
-/// Returns the total seconds as a u64, or None if the handshake was never.
+/// Returns the Unix time of the handshake, `received_at` minus the elapsed
+/// time, or None if the handshake was never.
 /// See: https://help.mikrotik.com/docs/spaces/ROS/pages/69664792/WireGuard
-fn parse_handshake_to_timestamp(handshake_str: &str) -> Option<u64> {
+fn parse_handshake_to_timestamp(handshake_str: &str, received_at: u64) -> Option<u64> {
     if handshake_str.is_empty() || handshake_str == "never" {
         return None;
     }
@@ -146,12 +167,7 @@ fn parse_handshake_to_timestamp(handshake_str: &str) -> Option<u64> {
         parse_routeros_duration(handshake_str)?
     };
 
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .ok()?
-        .as_secs();
-
-    Some(now.saturating_sub(duration_secs))
+    Some(received_at.saturating_sub(duration_secs))
 }
 
 /// Parse RouterOS duration format to seconds
@@ -223,6 +239,9 @@ fn parse_routeros_duration(duration_str: &str) -> Option<u64> {
 mod tests {
     use super::*;
 
+    /// Unix time the peers response is taken to have arrived at
+    const NOW: u64 = 1_700_000_000;
+
     #[test]
     fn test_parse_wireguard_interfaces_empty() {
         let result = parse_wireguard_interfaces(&[]);
@@ -293,7 +312,7 @@ mod tests {
 
     #[test]
     fn test_parse_wireguard_peers_empty() {
-        let result = parse_wireguard_peers(&[]);
+        let result = parse_wireguard_peers(&[], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 0);
     }
 
@@ -311,7 +330,7 @@ mod tests {
         data.insert("tx".to_string(), "2048".to_string());
         data.insert("last-handshake".to_string(), "never".to_string());
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].interface, "wg1");
         assert_eq!(result[0].name, "peer1");
@@ -336,7 +355,7 @@ mod tests {
         data.insert("tx".to_string(), "2048".to_string());
         data.insert("last-handshake".to_string(), "120".to_string()); // 120 seconds since last handshake
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].interface, "wg1");
         assert_eq!(result[0].name, "peer1");
@@ -344,14 +363,7 @@ mod tests {
         assert_eq!(result[0].endpoint, Some("192.168.1.1".to_string()));
         assert_eq!(result[0].rx_bytes, 1024);
         assert_eq!(result[0].tx_bytes, 2048);
-        assert!(result[0].latest_handshake.is_some());
-        let handshake = result[0].latest_handshake.unwrap();
-        let now = SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        assert!(handshake <= now);
-        assert!(handshake >= now - 130); // account for test execution time
+        assert_eq!(result[0].latest_handshake, Some(NOW - 120));
     }
 
     #[test]
@@ -362,7 +374,7 @@ mod tests {
         data.insert("allowed-address".to_string(), "10.10.10.1/32".to_string());
         // Missing endpoint, rx, tx, last-handshake
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].interface, "wg1");
         assert_eq!(result[0].name, "peer1");
@@ -380,7 +392,7 @@ mod tests {
         data.insert("allowed-address".to_string(), "10.10.10.1/32".to_string());
         // Missing name field
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].interface, "wg1");
         assert_eq!(result[0].name, "unnamed-peer"); // Should use default name
@@ -400,7 +412,7 @@ mod tests {
         data.insert("rx".to_string(), "invalid".to_string());
         data.insert("tx".to_string(), "also-invalid".to_string());
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].rx_bytes, 0);
         assert_eq!(result[0].tx_bytes, 0);
@@ -411,7 +423,7 @@ mod tests {
         let mut data = HashMap::new();
         data.insert("allowed-address".to_string(), "10.10.10.1/32".to_string());
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 0);
     }
 
@@ -421,10 +433,33 @@ mod tests {
         data.insert("interface".to_string(), "wg1".to_string());
         data.insert("name".to_string(), "peer1".to_string());
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_parse_wireguard_peers_keyed_by_public_key() {
+        let mut data = HashMap::new();
+        data.insert("interface".to_string(), "wg1".to_string());
+        data.insert("public-key".to_string(), "bWlrcm90aWs=".to_string());
+        let mut without_key = data.clone();
+        without_key.remove("public-key");
+        without_key.insert("allowed-address".to_string(), "10.10.10.1/32".to_string());
+
+        let result = parse_wireguard_peers(
+            &[data.clone(), without_key],
+            NOW,
+            WireGuardPeerKey::PublicKey,
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].public_key.as_deref(), Some("bWlrcm90aWs="));
+        assert_eq!(result[0].allowed_address, "");
+
+        // The default keying never collects public keys
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
+        assert!(result.is_empty());
+    }
+
     #[test]
     fn test_parse_wireguard_peers_multiple() {
         let mut peer1 = HashMap::new();
@@ -449,7 +484,7 @@ mod tests {
         peer2.insert("rx".to_string(), "2048".to_string());
         peer2.insert("tx".to_string(), "4096".to_string());
 
-        let result = parse_wireguard_peers(&[peer1, peer2]);
+        let result = parse_wireguard_peers(&[peer1, peer2], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].interface, "wg1");
         assert_eq!(result[0].name, "peer1");
@@ -476,7 +511,7 @@ mod tests {
         data.insert("rx".to_string(), "1024".to_string());
         data.insert("tx".to_string(), "2048".to_string());
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 0);
     }
 
@@ -490,41 +525,29 @@ mod tests {
             "2001:db8::1".to_string(),
         );
 
-        let result = parse_wireguard_peers(&[data]);
+        let result = parse_wireguard_peers(&[data], NOW, WireGuardPeerKey::AllowedAddress);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].endpoint, Some("2001:db8::1".to_string()));
     }
 
     #[test]
     fn test_parse_handshake_to_timestamp() {
-        let now = SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
         // Test that the function returns None for "never"
-        assert_eq!(parse_handshake_to_timestamp("never"), None);
+        assert_eq!(parse_handshake_to_timestamp("never", NOW), None);
 
         // Test that the function returns None for empty string
-        assert_eq!(parse_handshake_to_timestamp(""), None);
+        assert_eq!(parse_handshake_to_timestamp("", NOW), None);
 
         // Test that the function correctly parses plain integer values (backward compatibility)
-        let ts0 = parse_handshake_to_timestamp("0").unwrap();
-        assert!(ts0 <= now && ts0 >= now - 2);
-
-        let ts120 = parse_handshake_to_timestamp("120").unwrap();
-        assert!(ts120 <= now - 120 && ts120 >= now - 122);
+        assert_eq!(parse_handshake_to_timestamp("0", NOW), Some(NOW));
+        assert_eq!(parse_handshake_to_timestamp("120", NOW), Some(NOW - 120));
 
         // Test that the function correctly parses RouterOS duration format
-        let ts7s = parse_handshake_to_timestamp("7s").unwrap();
-        assert!(ts7s <= now - 7 && ts7s >= now - 9);
-
-        let ts90s = parse_handshake_to_timestamp("1m30s").unwrap();
-        assert!(ts90s <= now - 90 && ts90s >= now - 92);
+        assert_eq!(parse_handshake_to_timestamp("7s", NOW), Some(NOW - 7));
+        assert_eq!(parse_handshake_to_timestamp("1m30s", NOW), Some(NOW - 90));
 
         // Test zero duration
-        let ts0s = parse_handshake_to_timestamp("0s").unwrap();
-        assert!(ts0s <= now && ts0s >= now - 2);
+        assert_eq!(parse_handshake_to_timestamp("0s", NOW), Some(NOW));
     }
 
     #[test]
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2025 Jesof
+
+//! Legacy wireless package stations (`/interface/wireless/registration-table`)
+
+use std::collections::HashMap;
+
+/// A station registered to one of the router's wireless interfaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct WirelessStationStats {
+    pub interface: String,
+    pub mac_address: String,
+    /// Received signal strength in dBm, `None` when not reported
+    pub signal_strength: Option<i64>,
+    /// Current transmit rate in bits per second
+    pub tx_rate: Option<u64>,
+    /// Current receive rate in bits per second
+    pub rx_rate: Option<u64>,
+    /// Time since the station registered, as reported by RouterOS
+    pub uptime: String,
+}
+
+/// Parse `/interface/wireless/registration-table/print` response
+pub(super) fn parse_wireless_stations(
+    sentences: &[HashMap<String, String>],
+) -> Vec<WirelessStationStats> {
+    sentences
+        .iter()
+        .filter_map(|s| {
+            Some(WirelessStationStats {
+                interface: s.get("interface")?.clone(),
+                mac_address: s.get("mac-address")?.clone(),
+                signal_strength: s.get("signal-strength").and_then(|v| parse_signal_dbm(v)),
+                tx_rate: s.get("tx-rate").and_then(|v| parse_rate_bps(v)),
+                rx_rate: s.get("rx-rate").and_then(|v| parse_rate_bps(v)),
+                uptime: s.get("uptime").cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Leading dBm value of a signal such as `-65@MCS7` or `-71@6Mbps`
+pub(super) fn parse_signal_dbm(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().ok()
+}
+
+/// Bits per second from rates such as `54Mbps` or `144.4Mbps-20MHz/2S/SGI`
+pub(super) fn parse_rate_bps(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..end].parse().ok()?;
+    let multiplier = match value[end..].split(['-', '/']).next()? {
+        "bps" | "" => 1.0,
+        "kbps" | "Kbps" => 1e3,
+        "Mbps" => 1e6,
+        "Gbps" => 1e9,
+        _ => return None,
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some((number * multiplier).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_signal_dbm() {
+        assert_eq!(parse_signal_dbm("-65@MCS7"), Some(-65));
+        assert_eq!(parse_signal_dbm("-71@6Mbps"), Some(-71));
+        assert_eq!(parse_signal_dbm("-58"), Some(-58));
+        assert_eq!(parse_signal_dbm("@MCS7"), None);
+    }
+
+    #[test]
+    fn test_parse_rate_bps() {
+        assert_eq!(parse_rate_bps("54Mbps"), Some(54_000_000));
+        assert_eq!(parse_rate_bps("144.4Mbps-20MHz/2S/SGI"), Some(144_400_000));
+        assert_eq!(parse_rate_bps("1Gbps"), Some(1_000_000_000));
+        assert_eq!(parse_rate_bps("fast"), None);
+    }
+
+    #[test]
+    fn test_parse_wireless_stations() {
+        let sentences = vec![
+            sentence(&[
+                ("interface", "wlan1"),
+                ("mac-address", "AA:BB:CC:DD:EE:01"),
+                ("signal-strength", "-65@MCS7"),
+                ("tx-rate", "130Mbps-20MHz/2S"),
+                ("rx-rate", "117Mbps-20MHz/2S"),
+                ("uptime", "3h12m5s"),
+            ]),
+            sentence(&[("interface", "wlan1"), ("mac-address", "AA:BB:CC:DD:EE:02")]),
+            sentence(&[("mac-address", "AA:BB:CC:DD:EE:03")]),
+        ];
+
+        let stations = parse_wireless_stations(&sentences);
+
+        assert_eq!(
+            stations,
+            vec![
+                WirelessStationStats {
+                    interface: "wlan1".to_string(),
+                    mac_address: "AA:BB:CC:DD:EE:01".to_string(),
+                    signal_strength: Some(-65),
+                    tx_rate: Some(130_000_000),
+                    rx_rate: Some(117_000_000),
+                    uptime: "3h12m5s".to_string(),
+                },
+                WirelessStationStats {
+                    interface: "wlan1".to_string(),
+                    mac_address: "AA:BB:CC:DD:EE:02".to_string(),
+                    signal_strength: None,
+                    tx_rate: None,
+                    rx_rate: None,
+                    uptime: String::new(),
+                },
+            ]
+        );
+    }
+}
@@ -11,7 +11,7 @@
 //! ```
 
 // Core types
-pub use crate::config::{Config, RouterConfig};
+pub use crate::config::{Collector, Config, RouterConfig, WireGuardPeerKey, parse_collector_list};
 pub use crate::error::{AppError, Result};
 
 // Metrics types
@@ -19,6 +19,10 @@ pub use crate::metrics::{MetricsRegistry, RouterLabels};
 
 // MikroTik client
 pub use crate::mikrotik::{
-    ConnectionPool, ConnectionTrackingStats, InterfaceStats, RouterMetrics, SystemResource,
-    WireGuardInterfaceStats, WireGuardPeerStats,
+    BondSlaveStats, BondStats, CircuitState, ClockStats, ConnectionPool, ConnectionTrackingStats,
+    ConntrackTableStats, ContainerStats, CpuCoreStats, DefaultRouteStats, DhcpLeaseStats,
+    FirewallRuleStats, HealthSensor, HealthStats, InterfaceQueueStats, InterfaceStats,
+    IpServiceStats, LicenseStats, LoginMethod, ManagedDeviceStats, PingStats, PppSessionStats,
+    QueueStats, RouterMetrics, SystemResource, TunnelStats, UnexpectedReboot, VrrpStats,
+    WifiChannelStats, WireGuardInterfaceStats, WireGuardPeerStats,
 };
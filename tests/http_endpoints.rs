@@ -14,15 +14,11 @@ fn make_state(routers: Vec<RouterConfig>) -> Arc<AppState> {
     let config = Config {
         server_addr: "127.0.0.1:9090".to_string(),
         routers,
-        collection_interval_secs: 30,
+        ..Config::default()
     };
     let metrics = MetricsRegistry::new();
     let pool = Arc::new(ConnectionPool::new());
-    Arc::new(AppState {
-        config,
-        metrics,
-        pool,
-    })
+    Arc::new(AppState::new(config, metrics, pool))
 }
 
 fn test_router(name: &str) -> RouterConfig {
@@ -31,6 +27,7 @@ fn test_router(name: &str) -> RouterConfig {
         address: "192.168.1.1:8728".to_string(),
         username: "admin".to_string(),
         password: secrecy::SecretString::new("password".to_string().into()),
+        ..Default::default()
     }
 }
 
@@ -96,23 +93,36 @@ async fn metrics_contains_router_data_after_update() {
         tx_packets: 20,
         rx_errors: 0,
         tx_errors: 0,
+        rx_drops: 0,
+        tx_drops: 0,
         running: true,
+        disabled: false,
+        mtu: None,
+        actual_mtu: None,
+        rx_multicast: None,
+        tx_multicast: None,
+        rx_broadcast: None,
+        tx_broadcast: None,
     };
     let system = SystemResource {
         uptime: "1d".to_string(),
         cpu_load: 42,
+        cpu_count: None,
         free_memory: 512_000_000,
         total_memory: 1_024_000_000,
+        free_hdd_space: 64 * 1024 * 1024,
+        total_hdd_space: 128 * 1024 * 1024,
         version: "7.10".to_string(),
         board_name: "RB750Gr3".to_string(),
+        architecture: "arm".to_string(),
+        identity: "gw".to_string(),
+        serial_number: "HEX0123456".to_string(),
     };
     let metrics = RouterMetrics {
         router_name: "myrouter".to_string(),
         interfaces: vec![iface],
-        system,
-        connection_tracking: Vec::new(),
-        wireguard_interfaces: vec![],
-        wireguard_peers: vec![],
+        system: Some(system),
+        ..Default::default()
     };
     state.metrics.update_metrics(&metrics).await;
 
@@ -137,6 +147,116 @@ async fn metrics_contains_router_data_after_update() {
     assert!(body.contains("mikrotik_system_cpu_load"));
 }
 
+#[tokio::test]
+async fn metrics_requires_basic_auth_when_configured() {
+    let config = Config {
+        metrics_auth_user: Some("prom".to_string()),
+        metrics_auth_password: Some(secrecy::SecretString::from("s3cret")),
+        ..Config::default()
+    };
+    let state = Arc::new(AppState::new(
+        config,
+        MetricsRegistry::new(),
+        Arc::new(ConnectionPool::new()),
+    ));
+    let request = |path: &str, auth: Option<&str>| {
+        let mut builder = Request::get(path);
+        if let Some(auth) = auth {
+            builder = builder.header("authorization", auth);
+        }
+        builder.body(String::new()).unwrap()
+    };
+
+    let resp = create_router(state.clone())
+        .oneshot(request("/metrics", None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(resp.headers().contains_key("www-authenticate"));
+
+    // base64("prom:wrong")
+    let resp = create_router(state.clone())
+        .oneshot(request("/metrics", Some("Basic cHJvbTp3cm9uZw==")))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // base64("prom:s3cret")
+    let resp = create_router(state.clone())
+        .oneshot(request("/metrics", Some("Basic cHJvbTpzM2NyZXQ=")))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // load balancers probe /health without credentials
+    let resp = create_router(state)
+        .oneshot(request("/health", None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+// --- /api/routers/{name}/snapshot endpoint ---
+
+#[tokio::test]
+async fn snapshot_returns_latest_router_metrics() {
+    let state = make_state(vec![test_router("r1")]);
+    let get = |path: &str| Request::get(path).body(String::new()).unwrap();
+
+    let resp = create_router(state.clone())
+        .oneshot(get("/api/routers/unknown/snapshot"))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = create_router(state.clone())
+        .oneshot(get("/api/routers/r1/snapshot"))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let metrics = RouterMetrics {
+        router_name: "r1".to_string(),
+        interfaces: vec![InterfaceStats {
+            name: "ether1".to_string(),
+            rx_bytes: 1000,
+            tx_bytes: 2000,
+            rx_packets: 10,
+            tx_packets: 20,
+            rx_errors: 0,
+            tx_errors: 0,
+            rx_drops: 0,
+            tx_drops: 0,
+            running: true,
+            disabled: false,
+            mtu: None,
+            actual_mtu: None,
+            rx_multicast: None,
+            tx_multicast: None,
+            rx_broadcast: None,
+            tx_broadcast: None,
+        }],
+        ..Default::default()
+    };
+    state
+        .snapshots
+        .write()
+        .await
+        .insert("r1".to_string(), metrics);
+
+    let resp = create_router(state)
+        .oneshot(get("/api/routers/r1/snapshot"))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let snapshot: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(snapshot["router"], "r1");
+    assert_eq!(snapshot["interfaces"][0]["name"], "ether1");
+    assert_eq!(snapshot["interfaces"][0]["tx_bytes"], 2000);
+    assert!(snapshot["system"].is_null());
+}
+
 // --- /health endpoint ---
 
 #[tokio::test]
@@ -333,6 +453,142 @@ async fn health_multi_router_partial_degradation() {
     assert_eq!(bad["status"], "degraded");
 }
 
+// --- /probe endpoint ---
+
+#[tokio::test]
+async fn probe_rejects_unknown_or_missing_target() {
+    let state = make_state(vec![test_router("r1")]);
+    let app = create_router(state);
+
+    for uri in ["/probe?target=r2", "/probe"] {
+        let resp = app
+            .clone()
+            .oneshot(Request::get(uri).body(String::new()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn probe_returns_only_the_target_router() {
+    let mut unreachable = test_router("r1");
+    unreachable.address = "127.0.0.1:1".to_string();
+    let state = make_state(vec![unreachable, test_router("r2")]);
+    let app = create_router(state.clone());
+
+    let resp = app
+        .oneshot(
+            Request::get("/probe?target=r1")
+                .body(String::new())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(
+        text.contains("mikrotik_scrape_errors_total{router=\"r1\"} 1"),
+        "{text}"
+    );
+    assert!(!text.contains("router=\"r2\""), "{text}");
+
+    // The background registry is left untouched
+    let labels = RouterLabels {
+        router: "r1".to_string(),
+    };
+    assert_eq!(state.metrics.get_scrape_error_count(&labels).await, 0);
+}
+
+// --- /reload endpoint ---
+
+#[tokio::test]
+async fn reload_is_disabled_without_token() {
+    let state = make_state(vec![test_router("r1")]);
+    let app = create_router(state.clone());
+
+    let resp = app
+        .oneshot(Request::post("/reload").body(String::new()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(state.reloader.routers().len(), 1);
+}
+
+#[tokio::test]
+async fn reload_rejects_wrong_token() {
+    let config = Config {
+        routers: vec![test_router("r1")],
+        reload_token: Some("s3cret".into()),
+        ..Config::default()
+    };
+    let state = Arc::new(AppState::new(
+        config,
+        MetricsRegistry::new(),
+        Arc::new(ConnectionPool::new()),
+    ));
+    let app = create_router(state.clone());
+
+    for auth in [None, Some("Bearer wrong"), Some("s3cret")] {
+        let mut request = Request::post("/reload");
+        if let Some(auth) = auth {
+            request = request.header("Authorization", auth);
+        }
+        let resp = app
+            .clone()
+            .oneshot(request.body(String::new()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{auth:?}");
+    }
+    assert_eq!(state.reloader.routers().len(), 1);
+}
+
+#[tokio::test]
+async fn reload_accepts_metrics_basic_auth() {
+    let config = Config {
+        routers: vec![test_router("r1")],
+        metrics_auth_user: Some("prom".to_string()),
+        metrics_auth_password: Some(secrecy::SecretString::from("s3cret")),
+        ..Config::default()
+    };
+    let state = Arc::new(AppState::new(
+        config,
+        MetricsRegistry::new(),
+        Arc::new(ConnectionPool::new()),
+    ));
+    let request = |auth: &str| {
+        Request::post("/reload")
+            .header("authorization", auth)
+            .body(String::new())
+            .unwrap()
+    };
+
+    // base64("prom:wrong")
+    let resp = create_router(state.clone())
+        .oneshot(request("Basic cHJvbTp3cm9uZw=="))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(state.reloader.routers().len(), 1);
+
+    // base64("prom:s3cret")
+    let resp = create_router(state.clone())
+        .oneshot(request("Basic cHJvbTpzM2NyZXQ="))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json["routers"].as_u64(),
+        Some(state.reloader.routers().len() as u64)
+    );
+}
+
 // --- 404 for unknown routes ---
 
 #[tokio::test]